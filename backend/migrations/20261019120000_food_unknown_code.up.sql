@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+CREATE TABLE food_unknown_code (
+    food_id uuid NOT NULL REFERENCES food(food_id),
+    code text NOT NULL,
+    PRIMARY KEY (food_id, code)
+);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE canteen_closure;
+DROP TYPE closure_reason;
@@ -0,0 +1,13 @@
+-- Add up migration script here
+
+CREATE TYPE closure_reason AS ENUM ('HOLIDAY', 'SEMESTER_BREAK', 'EVENT', 'OTHER', 'UNSPECIFIED');
+
+CREATE TABLE canteen_closure (
+  canteen_id uuid NOT NULL REFERENCES canteen(canteen_id) ON DELETE CASCADE,
+  closed_date date NOT NULL,
+  reason closure_reason NOT NULL DEFAULT 'UNSPECIFIED',
+  note text,
+  -- whether this closure got entered by an administrator instead of being detected while parsing
+  manual boolean NOT NULL DEFAULT false,
+  PRIMARY KEY (canteen_id, closed_date)
+);
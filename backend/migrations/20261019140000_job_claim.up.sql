@@ -0,0 +1,9 @@
+-- Add up migration script here
+
+-- scheduled job runs claimed by one of possibly multiple backend instances, so every run happens only once
+CREATE TABLE job_claim (
+  job_name text NOT NULL,
+  scheduled_at timestamptz NOT NULL,
+  claimed_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (job_name, scheduled_at)
+);
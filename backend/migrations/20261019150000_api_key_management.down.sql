@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE api_key
+  DROP COLUMN created_at,
+  DROP COLUMN revoked_at;
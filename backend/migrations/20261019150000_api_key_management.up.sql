@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE api_key
+  ADD COLUMN created_at timestamptz NOT NULL DEFAULT now(),
+  ADD COLUMN revoked_at timestamptz;
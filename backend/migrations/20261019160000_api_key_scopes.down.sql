@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE api_key
+  DROP COLUMN scopes,
+  DROP COLUMN expires_at,
+  DROP COLUMN rate_limit;
+
+DROP TYPE api_key_scope;
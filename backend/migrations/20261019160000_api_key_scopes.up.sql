@@ -0,0 +1,7 @@
+-- Add up migration script here
+CREATE TYPE api_key_scope AS ENUM ('RATE', 'VOTE', 'UPLOAD_IMAGE', 'REPORT');
+
+ALTER TABLE api_key
+  ADD COLUMN scopes api_key_scope[] NOT NULL DEFAULT '{RATE,VOTE,UPLOAD_IMAGE,REPORT}',
+  ADD COLUMN expires_at timestamptz,
+  ADD COLUMN rate_limit integer CHECK (rate_limit > 0);
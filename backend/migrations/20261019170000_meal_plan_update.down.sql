@@ -0,0 +1,18 @@
+-- Add down migration script here
+
+DROP TRIGGER canteen_meal_plan_update ON canteen;
+DROP TRIGGER line_meal_plan_update ON line;
+DROP TRIGGER canteen_closure_meal_plan_update ON canteen_closure;
+DROP TRIGGER food_meal_plan_update ON food;
+DROP TRIGGER food_plan_meal_plan_update ON food_plan;
+DROP TRIGGER food_allergen_meal_plan_update ON food_allergen;
+DROP TRIGGER food_additive_meal_plan_update ON food_additive;
+DROP TRIGGER food_unknown_code_meal_plan_update ON food_unknown_code;
+DROP TRIGGER food_nutrition_data_meal_plan_update ON food_nutrition_data;
+DROP TRIGGER food_env_score_meal_plan_update ON food_env_score;
+DROP TRIGGER meal_rating_meal_plan_update ON meal_rating;
+DROP TRIGGER image_meal_plan_update ON image;
+DROP TRIGGER image_rating_meal_plan_update ON image_rating;
+DROP TRIGGER image_report_meal_plan_update ON image_report;
+DROP FUNCTION touch_meal_plan_update;
+DROP TABLE meal_plan_update;
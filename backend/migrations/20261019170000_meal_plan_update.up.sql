@@ -0,0 +1,45 @@
+-- Add up migration script here
+
+-- time of the last change to the meal plan, including ratings and images, used for caching api responses
+CREATE TABLE meal_plan_update (
+  id boolean PRIMARY KEY DEFAULT true CHECK (id),
+  updated_at timestamptz NOT NULL DEFAULT now()
+);
+
+INSERT INTO meal_plan_update DEFAULT VALUES;
+
+CREATE FUNCTION touch_meal_plan_update() RETURNS trigger AS $$
+BEGIN
+  UPDATE meal_plan_update SET updated_at = now();
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER canteen_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON canteen
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER line_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON line
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER canteen_closure_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON canteen_closure
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_plan_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_plan
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_allergen_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_allergen
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_additive_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_additive
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_unknown_code_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_unknown_code
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_nutrition_data_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_nutrition_data
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER food_env_score_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON food_env_score
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER meal_rating_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON meal_rating
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER image_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON image
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER image_rating_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON image_rating
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
+CREATE TRIGGER image_report_meal_plan_update AFTER INSERT OR UPDATE OR DELETE ON image_report
+  FOR EACH STATEMENT EXECUTE FUNCTION touch_meal_plan_update();
@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+CREATE TYPE erasure_requester AS ENUM ('CLIENT', 'ADMIN');
+
+-- audit trail of erasures of all data linked to a client id,
+-- the client id itself is only stored hashed so it can be checked whether its data got erased without keeping it
+CREATE TABLE data_erasure (
+  erasure_id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  client_id_hash bytea NOT NULL,
+  requested_by erasure_requester NOT NULL,
+  erased_at timestamptz NOT NULL DEFAULT now(),
+  meal_ratings integer NOT NULL,
+  image_votes integer NOT NULL,
+  image_reports integer NOT NULL,
+  images integer NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+-- last successful meal plan parse of each kind, shared by all backend instances for health checks
+CREATE TABLE parse_run (
+  kind text PRIMARY KEY,
+  finished_at timestamptz NOT NULL DEFAULT now(),
+  dishes integer NOT NULL CHECK (dishes >= 0)
+);
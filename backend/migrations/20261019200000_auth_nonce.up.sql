@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+-- nonces of authenticated requests, shared by all backend instances so captured requests can not be replayed against another one
+CREATE TABLE auth_nonce (
+  client_id uuid NOT NULL,
+  nonce text NOT NULL,
+  expires_at timestamptz NOT NULL,
+  PRIMARY KEY (client_id, nonce)
+);
+
+CREATE INDEX auth_nonce_expires_at ON auth_nonce (expires_at);
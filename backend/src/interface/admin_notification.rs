@@ -1,6 +1,7 @@
 //! This interface allows administrators to be notified of reporting requests.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use lettre::address::AddressError;
 use serde::Serialize;
 use thiserror::Error;
@@ -19,6 +20,12 @@ pub trait AdminNotification: Sync + Send {
     async fn notify_admin_image_verified(&self, image_id: Uuid) -> Result<()>;
     /// Notifies an administrator about an image gotten deleted.
     async fn notify_admin_image_deleted(&self, image_id: Uuid) -> Result<()>;
+    /// Sends a data quality report about the upcoming meal plans to an administrator.
+    async fn notify_admin_data_quality_report(&self, report: &DataQualityReport) -> Result<()>;
+    /// Notifies an administrator about allergen and additive codes on the meal plans which are not known yet.
+    async fn notify_admin_unknown_codes(&self, codes: Vec<UnknownCodeInfo>);
+    /// Notifies an administrator about a scheduled job failing repeatedly, even after retrying.
+    async fn notify_admin_job_failure(&self, info: JobFailureInfo);
 }
 
 /// Enum describing the possible ways, the mail notification can fail.
@@ -69,3 +76,75 @@ pub struct ImageReportInfo {
     /// list of urls of other images of the same meal.
     pub other_image_urls: Vec<String>,
 }
+
+/// Structure containing an allergen or additive code which could not be recognized while parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownCodeInfo {
+    /// The raw code as found on the meal plan.
+    pub code: String,
+    /// Names of the dishes this code was found at.
+    pub dish_names: Vec<String>,
+}
+
+/// Structure containing information about a scheduled job failing repeatedly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JobFailureInfo {
+    /// Name of the failing job.
+    pub job_name: String,
+    /// Number of consecutive failed runs, each including all of its retries.
+    pub consecutive_failures: u32,
+    /// Error message of the last failed attempt.
+    pub last_error: String,
+    /// Date and time the last failed run was started at.
+    pub failed_at: DateTime<Local>,
+}
+
+/// Structure containing the result of checking the upcoming meal plans for anomalies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DataQualityReport {
+    /// Date this report was created at.
+    pub report_date: Date,
+    /// First date of the meal plans that were checked.
+    pub from_date: Date,
+    /// Number of planned foods that were checked.
+    pub checked_food_count: usize,
+    /// All anomalies found.
+    pub issues: Vec<DataQualityIssue>,
+}
+
+/// A single anomaly found in the upcoming meal plans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DataQualityIssue {
+    /// Kind of anomaly.
+    pub kind: DataQualityIssueKind,
+    /// Identifier of the affected food.
+    pub food_id: Uuid,
+    /// Name of the affected food.
+    pub food_name: String,
+    /// Date the affected food is served at.
+    pub date: Date,
+    /// Name of the canteen the affected food is served at.
+    pub canteen_name: String,
+    /// Name of the line the affected food is served at.
+    pub line_name: String,
+    /// Human readable description of the anomaly.
+    pub details: String,
+}
+
+/// Enumeration of all anomalies detected by the data quality report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DataQualityIssueKind {
+    /// The type of the food could not be determined while parsing.
+    UnknownFoodType,
+    /// No nutrition data is available for a meal.
+    MissingNutritionData,
+    /// No environmental information is available for a meal.
+    MissingEnvironmentInfo,
+    /// A meal is served for free.
+    ZeroPrice,
+    /// The price of a meal deviates strongly from the usual prices.
+    PriceOutlier,
+    /// Multiple different foods with the same name are served.
+    DuplicateName,
+}
@@ -7,11 +7,13 @@ use thiserror::Error;
 
 use crate::{
     layer::logic::api_command::image_preprocessing::ImagePreprocessingError,
-    util::{ReportReason, Uuid},
+    util::{ClosureReason, Date, ErasureRequester, ReportReason, Uuid},
 };
 
 use super::{
-    admin_notification::MailError, image_storage, image_validation, persistent_data::DataError,
+    admin_notification::{DataQualityReport, MailError},
+    image_storage, image_validation,
+    persistent_data::{model::DataErasure, DataError},
 };
 
 /// Result returned from commands, potentially containing a [`CommandError`].
@@ -52,11 +54,41 @@ pub trait Command: Send + Sync {
     /// command to add a rating to a meal.
     async fn set_meal_rating(&self, meal_id: Uuid, rating: u32, client_id: Uuid) -> Result<()>;
 
+    /// Command to remove the rating of a meal.
+    async fn remove_meal_rating(&self, meal_id: Uuid, client_id: Uuid) -> Result<()>;
+
     /// Marks an image as verified.
     async fn verify_image(&self, image_id: Uuid) -> Result<()>;
 
     /// Deletes an image.
     async fn delete_image(&self, image_id: Uuid) -> Result<()>;
+
+    /// Deletes an image on request of the given client, which must have uploaded it.
+    async fn delete_own_image(&self, image_id: Uuid, client_id: Uuid) -> Result<()>;
+
+    /// Checks the upcoming meal plans for anomalies.
+    /// If `notify_admin` is set, the resulting report gets sent to an administrator.
+    async fn create_data_quality_report(&self, notify_admin: bool) -> Result<DataQualityReport>;
+
+    /// Marks a canteen as closed at the given date, overriding closures detected while parsing.
+    async fn set_canteen_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<String>,
+    ) -> Result<()>;
+
+    /// Removes the closure of a canteen at the given date. Returns whether there was such a closure.
+    async fn remove_canteen_closure(&self, canteen_id: Uuid, date: Date) -> Result<bool>;
+
+    /// Deletes all ratings, votes, reports and images linked to the given client id, including the image files.
+    /// The erasure gets recorded in an audit trail.
+    async fn delete_client_data(
+        &self,
+        client_id: Uuid,
+        requester: ErasureRequester,
+    ) -> Result<DataErasure>;
 }
 
 #[async_trait]
@@ -114,6 +146,12 @@ impl<C: Command> Command for Arc<C> {
             .await
     }
 
+    async fn remove_meal_rating(&self, meal_id: Uuid, client_id: Uuid) -> Result<()> {
+        Self::as_ref(self)
+            .remove_meal_rating(meal_id, client_id)
+            .await
+    }
+
     async fn verify_image(&self, image_id: Uuid) -> Result<()> {
         Self::as_ref(self).verify_image(image_id).await
     }
@@ -121,6 +159,46 @@ impl<C: Command> Command for Arc<C> {
     async fn delete_image(&self, image_id: Uuid) -> Result<()> {
         Self::as_ref(self).delete_image(image_id).await
     }
+
+    async fn delete_own_image(&self, image_id: Uuid, client_id: Uuid) -> Result<()> {
+        Self::as_ref(self)
+            .delete_own_image(image_id, client_id)
+            .await
+    }
+
+    async fn create_data_quality_report(&self, notify_admin: bool) -> Result<DataQualityReport> {
+        Self::as_ref(self)
+            .create_data_quality_report(notify_admin)
+            .await
+    }
+
+    async fn set_canteen_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<String>,
+    ) -> Result<()> {
+        Self::as_ref(self)
+            .set_canteen_closure(canteen_id, date, reason, note)
+            .await
+    }
+
+    async fn remove_canteen_closure(&self, canteen_id: Uuid, date: Date) -> Result<bool> {
+        Self::as_ref(self)
+            .remove_canteen_closure(canteen_id, date)
+            .await
+    }
+
+    async fn delete_client_data(
+        &self,
+        client_id: Uuid,
+        requester: ErasureRequester,
+    ) -> Result<DataErasure> {
+        Self::as_ref(self)
+            .delete_client_data(client_id, requester)
+            .await
+    }
 }
 
 /// Enum describing the possible ways, a command can fail.
@@ -132,6 +210,9 @@ pub enum CommandError {
     /// Error marking missing authentication.
     #[error("no authentication information provided")]
     NoAuth,
+    /// Error marking that an image may only be changed by the client which uploaded it.
+    #[error("the image {0} was not uploaded by this client")]
+    NotUploader(Uuid),
     /// Error marking something went wrong with the data.
     #[error("Data error occurred: {0}")]
     DataError(#[from] DataError),
@@ -0,0 +1,66 @@
+//! This interface allows managing the api keys clients authenticate with.
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::persistent_data::{
+    model::{ApiKey, ApiKeyPermissions},
+    DataError,
+};
+
+/// Result returned from api key management, potentially containing a [`ApiKeyError`].
+pub type Result<T> = std::result::Result<T, ApiKeyError>;
+
+/// Interface for creating, listing, describing and revoking api keys.
+/// Keys are referred to by their identifier, a prefix of the key like the one used for authentication.
+#[async_trait]
+pub trait ApiKeyManagement: Send + Sync {
+    /// Creates a new randomly generated api key with the given description and permissions.
+    /// The returned key is the only place the whole key is available to the administrator.
+    ///
+    /// # Errors
+    /// Returns an error if the key could not be stored.
+    async fn create_api_key(
+        &self,
+        description: String,
+        permissions: ApiKeyPermissions,
+    ) -> Result<ApiKey>;
+
+    /// Returns all api keys, including revoked ones, ordered by their creation.
+    ///
+    /// # Errors
+    /// Returns an error if the keys could not be loaded.
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>>;
+
+    /// Returns the api key identified by `ident`.
+    ///
+    /// # Errors
+    /// Returns an error if no single key is identified by `ident` or the keys could not be loaded.
+    async fn describe_api_key(&self, ident: &str) -> Result<ApiKey>;
+
+    /// Revokes the api key identified by `ident`, so it can no longer be used for authentication.
+    /// Returns the revoked key.
+    ///
+    /// # Errors
+    /// Returns an error if no single key is identified by `ident`, it is already revoked or could not be revoked.
+    async fn revoke_api_key(&self, ident: &str) -> Result<ApiKey>;
+}
+
+/// Enum describing the possible ways, an api key operation can fail.
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+    /// Error while accessing the database.
+    #[error("error accessing the database: {0}")]
+    DataError(#[from] DataError),
+    /// No api key is identified by the given identifier.
+    #[error("no api key starts with `{0}`")]
+    NoSuchKey(String),
+    /// Multiple api keys are identified by the given identifier.
+    #[error("multiple api keys start with `{0}`, please provide more characters")]
+    AmbiguousIdent(String),
+    /// The requested permissions can not be granted, e.g. because the key would already be expired.
+    #[error("invalid api key permissions: {0}")]
+    InvalidPermissions(String),
+    /// The api key has already been revoked.
+    #[error("the api key `{0}` has already been revoked")]
+    AlreadyRevoked(String),
+}
@@ -15,6 +15,8 @@ pub trait ImageStorage: Send + Sync {
     async fn save_image(&self, id: Uuid, image: ImageResource) -> Result<()>;
     /// Deletes an image resource.
     async fn delete_image(&self, id: Uuid) -> Result<()>;
+    /// Returns the ids of all stored images.
+    async fn list_images(&self) -> Result<Vec<Uuid>>;
 }
 
 /// Enum describing possible ways an file operation can go wrong.
@@ -56,6 +56,25 @@ pub enum ImageValidationError {
     FileReaderError(#[from] io::Error),
 }
 
+impl ImageValidationError {
+    /// Returns the name of the variant, e.g. for distinguishing errors in metrics.
+    #[must_use]
+    pub const fn variant_name(&self) -> &'static str {
+        match self {
+            Self::SafeSearchRejectionError(..) => "SafeSearchRejectionError",
+            Self::JsonDecodeFailed => "JsonDecodeFailed",
+            Self::RestRequestFailed => "RestRequestFailed",
+            Self::ImageEncodeFailed(_) => "ImageEncodeFailed",
+            Self::GeminiRejectionError(_) => "GeminiRejectionError",
+            Self::ApiResponseError(..) => "ApiResponseError",
+            Self::GeminiPhraseDecodeFailed(_) => "GeminiPhraseDecodeFailed",
+            Self::TokenGenerationError(_) => "TokenGenerationError",
+            Self::ReqwestError(_) => "ReqwestError",
+            Self::FileReaderError(_) => "FileReaderError",
+        }
+    }
+}
+
 /// Structure that contains all information necessary for the image validation component.
 #[derive(Default)]
 pub struct ImageValidationInfo {
@@ -0,0 +1,78 @@
+//! This interface allows inspecting and triggering regularly scheduled jobs.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use thiserror::Error;
+
+/// Result returned from job control operations, potentially containing a [`JobError`].
+pub type Result<T> = std::result::Result<T, JobError>;
+
+/// Interface for inspecting and triggering scheduled jobs.
+#[async_trait]
+pub trait JobControl: Send + Sync {
+    /// Returns the status of all registered jobs.
+    async fn get_jobs(&self) -> Vec<JobStatus>;
+
+    /// Whether the scheduler is running and jobs get started according to their schedule.
+    fn is_running(&self) -> bool;
+
+    /// Starts the job with the given name immediately in the background, independent of its schedule.
+    async fn trigger_job(&self, name: &str) -> Result<()>;
+}
+
+/// Enum describing the possible ways, controlling a job can fail.
+#[derive(Debug, Error)]
+pub enum JobError {
+    /// There is no job registered with the given name.
+    #[error("no job named `{0}` exists")]
+    NoSuchJob(String),
+    /// The job is currently running and can not be started a second time.
+    #[error("job `{0}` is already running")]
+    AlreadyRunning(String),
+}
+
+/// Structure containing all information about a scheduled job.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobStatus {
+    /// Unique name of the job.
+    pub name: String,
+    /// Cron-like schedule the job runs at.
+    pub schedule: String,
+    /// Whether the job is currently running.
+    pub running: bool,
+    /// Information about the last completed run, if any.
+    pub last_run: Option<JobRun>,
+    /// Date and time the last successful run was started at, if any.
+    pub last_success: Option<DateTime<Local>>,
+    /// Date and time the job is scheduled to run next, if known.
+    pub next_run: Option<DateTime<Local>>,
+    /// Number of runs that failed since the last successful one.
+    pub consecutive_failures: u32,
+}
+
+/// Structure containing information about a single run of a job.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobRun {
+    /// Date and time the run was started at.
+    pub started_at: DateTime<Local>,
+    /// Time the run took in seconds, including all retries.
+    pub duration_secs: f64,
+    /// Number of times the job got retried during this run.
+    pub retries: u32,
+    /// Outcome of the run.
+    pub outcome: JobOutcome,
+}
+
+/// Outcome of a job run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobOutcome {
+    /// The job finished successfully.
+    Success,
+    /// The job failed.
+    Failure {
+        /// Description of the error the job failed with.
+        message: String,
+    },
+}
@@ -0,0 +1,47 @@
+//! This interface allows starting regular maintenance tasks like cleaning up outdated data.
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::{image_storage::ImageError, persistent_data::DataError};
+
+/// Result returned from maintenance tasks, potentially containing a [`MaintenanceError`].
+pub type Result<T> = std::result::Result<T, MaintenanceError>;
+
+/// Interface allowing to start regular maintenance tasks.
+#[async_trait]
+pub trait MaintenanceScheduling: Send + Sync {
+    /// Removes all image reports older than the configured expiry period.
+    ///
+    /// # Errors
+    /// Returns an error if the reports could not be removed.
+    async fn expire_image_reports(&self) -> Result<()>;
+
+    /// Removes all stored image files which do not belong to any image.
+    ///
+    /// # Errors
+    /// Returns an error if the stored images could not be listed or removed.
+    async fn remove_orphaned_images(&self) -> Result<()>;
+
+    /// Refreshes the statistics of the database used for planning queries.
+    ///
+    /// # Errors
+    /// Returns an error if the statistics could not be refreshed.
+    async fn refresh_statistics(&self) -> Result<()>;
+
+    /// Removes meal plan data older than the configured retention period.
+    ///
+    /// # Errors
+    /// Returns an error if the data could not be removed.
+    async fn purge_old_data(&self) -> Result<()>;
+}
+
+/// Enum describing the possible ways, a maintenance task can fail.
+#[derive(Debug, Error)]
+pub enum MaintenanceError {
+    /// Error while accessing the database.
+    #[error("error accessing the database: {0}")]
+    DataError(#[from] DataError),
+    /// Error while accessing the stored images.
+    #[error("error accessing the stored images: {0}")]
+    ImageError(#[from] ImageError),
+}
@@ -24,6 +24,9 @@ pub enum ParseError {
     /// Could not build client for making web requests.
     #[error("the html reqwest client creation failed: {0}")]
     ClientBuilderFailed(String),
+    /// A meal plan archive could not be read or written.
+    #[error("invalid meal plan archive: {0}")]
+    InvalidArchive(String),
 }
 
 #[async_trait]
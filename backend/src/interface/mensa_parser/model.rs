@@ -1,9 +1,11 @@
 //! These structs are used for parse operations.
 
-use crate::util::{Additive, Allergen, FoodType, NutritionData, Price};
+use serde::{Deserialize, Serialize};
+
+use crate::util::{Additive, Allergen, ClosureReason, FoodType, NutritionData, Price};
 
 /// Canteen struct containing all meal plan information of a canteen. Contains raw data.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ParseCanteen {
     /// Name of the canteen.
     pub name: String,
@@ -11,10 +13,22 @@ pub struct ParseCanteen {
     pub lines: Vec<ParseLine>,
     /// Position/Ranking of the canteen
     pub pos: u32,
+    /// Closure announced on the meal plan for this day, if any.
+    #[serde(default)]
+    pub closure: Option<ParseClosure>,
+}
+
+/// Closure of a canteen announced on its meal plan. Contains raw data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseClosure {
+    /// Reason the canteen is closed for, as far as it could be determined from the note.
+    pub reason: ClosureReason,
+    /// Note published together with the closure, e.g. the name of a holiday.
+    pub note: Option<String>,
 }
 
 /// Line struct containing all information of a line and their meals. Contains raw data.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ParseLine {
     /// Name of the line.
     pub name: String,
@@ -25,7 +39,7 @@ pub struct ParseLine {
 }
 
 /// Dish struct containing all information of a meal or side.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Dish {
     /// Name of the dish.
     pub name: String,
@@ -35,6 +49,8 @@ pub struct Dish {
     pub allergens: Vec<Allergen>,
     /// All containing additives. See [Additive]
     pub additives: Vec<Additive>,
+    /// Raw allergen and additive codes that could not be recognized.
+    pub unknown_codes: Vec<String>,
     /// Meal-Type of the dish. See [`FoodType`]
     pub food_type: FoodType,
     /// The environmental score of the dish. See [`ParseEnvironmentInfo`]
@@ -44,7 +60,7 @@ pub struct Dish {
 }
 
 /// This struct contains all environmental information. co2 in grams, water in litres
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ParseEnvironmentInfo {
     /// The number of stars the food has for CO2 emmissions. Out of `max_rating`
     pub co2_rating: u32,
@@ -1,8 +1,11 @@
 //! Module bundle for interface models
 pub mod admin_notification;
 pub mod api_command;
+pub mod api_key_management;
 pub mod image_storage;
 pub mod image_validation;
+pub mod job_control;
+pub mod maintenance;
 pub mod mealplan_management;
 pub mod mensa_parser;
 pub mod persistent_data;
@@ -1,9 +1,16 @@
 //! The interfaces specified here allow access to data stored in a persistent datastore like a database.
 pub mod model;
 
-use crate::interface::persistent_data::model::{ApiKey, Canteen, Image, Line, Meal, Side};
-use crate::util::{Additive, Allergen, Date, FoodType, NutritionData, Price, ReportReason, Uuid};
+use crate::interface::persistent_data::model::{
+    ApiKey, ApiKeyPermissions, Canteen, Closure, DataErasure, Image, ImageReport, ImageVote, Line,
+    Meal, MealRating, Pagination, PlannedFood, Side, UploadedImage,
+};
+use crate::util::{
+    Additive, Allergen, ClosureReason, Date, ErasureRequester, FoodType, NutritionData, Price,
+    ReportReason, Uuid,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use model::ExtendedImage;
 use sqlx::migrate::MigrateError;
 use std::num::TryFromIntError;
@@ -181,6 +188,29 @@ pub trait MealplanManagementDataAccess: Send + Sync {
         date: Date,
         price: Price,
     ) -> Result<()>;
+
+    /// Returns all raw allergen and additive codes which have been recorded as unknown for any food.
+    async fn get_all_unknown_codes(&self) -> Result<Vec<String>>;
+
+    /// Replaces the unknown raw allergen and additive codes recorded for a food.
+    async fn set_unknown_codes(&self, food_id: Uuid, codes: &[String]) -> Result<()>;
+
+    /// Marks a canteen as closed at the given date as announced on its published meal plan.
+    /// The reason and note of a closure detected earlier get updated, closures entered by an administrator are kept as they are.
+    async fn add_parsed_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<&str>,
+    ) -> Result<()>;
+
+    /// Removes a closure of a canteen at the given date which got detected while parsing.
+    /// Closures entered by an administrator are kept.
+    async fn remove_parsed_closure(&self, canteen_id: Uuid, date: Date) -> Result<()>;
+
+    /// Records a successful parse run of the given kind which found `dishes` dishes, replacing the previous run of this kind.
+    async fn record_parse_success(&self, kind: &str, dishes: u32) -> Result<()>;
 }
 
 #[async_trait]
@@ -211,18 +241,111 @@ pub trait CommandDataAccess: Sync + Send {
     /// Adds or updates a rating to the database. The rating will be related to the given meal and the given user.
     async fn add_rating(&self, meal_id: Uuid, user_id: Uuid, rating: u32) -> Result<()>;
 
+    /// Removes the rating of the given meal by the given user.
+    async fn remove_rating(&self, meal_id: Uuid, user_id: Uuid) -> Result<()>;
+
     /// Marks an image as verified. This leads to future reports being ignored.
     async fn verify_image(&self, image_id: Uuid) -> Result<()>;
 
     /// Deletes all entries related to an image.
     async fn delete_image(&self, image_id: Uuid) -> Result<()>;
+
+    /// Returns the id of the user who uploaded the given image, or `None` if there is no such image.
+    async fn get_image_uploader(&self, image_id: Uuid) -> Result<Option<Uuid>>;
+
+    /// Returns all foods planned to be served from the given date on.
+    async fn get_planned_foods(&self, from: Date) -> Result<Vec<PlannedFood>>;
+
+    /// Marks a canteen as closed at the given date. Overrides any existing closure at this date.
+    async fn set_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<String>,
+    ) -> Result<()>;
+
+    /// Removes the closure of a canteen at the given date, if any.
+    /// Returns whether a closure got removed.
+    async fn remove_closure(&self, canteen_id: Uuid, date: Date) -> Result<bool>;
+
+    /// Deletes all ratings, votes, reports and images of the given user and records this in the audit trail.
+    /// Image files are not deleted.
+    async fn delete_user_data(
+        &self,
+        user_id: Uuid,
+        requester: ErasureRequester,
+    ) -> Result<DataErasure>;
+}
+
+/// An interface for regular maintenance tasks. The Maintenance component uses this interface for database access.
+#[async_trait]
+pub trait MaintenanceDataAccess: Send + Sync {
+    /// Deletes all image reports made before the given date. Returns the number of deleted reports.
+    async fn delete_reports_before(&self, date: Date) -> Result<u64>;
+    /// Returns the ids of all images, including hidden ones.
+    async fn get_all_image_ids(&self) -> Result<Vec<Uuid>>;
+    /// Updates the statistics the database uses for planning queries.
+    async fn refresh_statistics(&self) -> Result<()>;
+    /// Deletes all meal plan entries and canteen closures before the given date. Returns the number of deleted entries.
+    async fn delete_meal_plans_before(&self, date: Date) -> Result<u64>;
+}
+
+/// An interface for coordinating scheduled jobs between multiple instances sharing the same database. The Scheduling component uses this interface for database access.
+#[async_trait]
+pub trait JobLockDataAccess: Send + Sync {
+    /// Tries to acquire the lock for running the given job, which is held by at most one instance at a time.
+    /// If `scheduled_at` is set, the scheduled run at this time also gets claimed, so that it only runs once, even after the lock got released.
+    /// Returns `None` if the job is currently running on another instance or the scheduled run was already claimed.
+    async fn try_lock_job(
+        &self,
+        job: &str,
+        scheduled_at: Option<DateTime<Local>>,
+    ) -> Result<Option<Box<dyn JobLock>>>;
+}
+
+/// Lock held while running a job, see [`JobLockDataAccess`].
+#[async_trait]
+pub trait JobLock: Send {
+    /// Releases the lock so the job can be run again.
+    async fn release(self: Box<Self>) -> Result<()>;
+}
+
+/// An interface for checking the availability of the database. The API uses this interface for reporting readiness.
+#[async_trait]
+pub trait HealthDataAccess: Send + Sync {
+    /// Checks whether the database can be reached and answers queries.
+    async fn check_connection(&self) -> Result<()>;
+    /// Returns the time of the last successful parse run of any kind and by any instance, if there was one.
+    async fn get_last_parse_success(&self) -> Result<Option<DateTime<Local>>>;
 }
 
 /// An interface for database access necessary for the authentication process.
 #[async_trait]
 pub trait AuthDataAccess: Sync + Send {
-    /// Loads all api_keys from the database.
+    /// Loads all api keys from the database which have not been revoked.
     async fn get_api_keys(&self) -> Result<Vec<ApiKey>>;
+    /// Loads all api keys from the database, including revoked ones.
+    async fn get_all_api_keys(&self) -> Result<Vec<ApiKey>>;
+    /// Adds a new api key with the given description and permissions.
+    async fn add_api_key(
+        &self,
+        key: &str,
+        description: &str,
+        permissions: &ApiKeyPermissions,
+    ) -> Result<ApiKey>;
+    /// Revokes the given api key. Returns whether the key existed and was not already revoked.
+    async fn revoke_api_key(&self, key: &str) -> Result<bool>;
+    /// Records the use of `nonce` by the given client, which is remembered until `expires_at`.
+    /// Returns whether the nonce was not used by this client before.
+    async fn use_nonce(
+        &self,
+        client_id: Uuid,
+        nonce: &str,
+        expires_at: DateTime<Local>,
+    ) -> Result<bool>;
+    /// Removes all nonces which are expired and no longer need to be remembered. Returns the number of removed nonces.
+    async fn delete_expired_nonces(&self) -> Result<u64>;
 }
 
 #[async_trait]
@@ -232,6 +355,10 @@ pub trait RequestDataAccess: Send + Sync {
     async fn get_canteen(&self, id: Uuid) -> Result<Option<Canteen>>;
     /// Returns all canteens from the database.
     async fn get_canteens(&self) -> Result<Vec<Canteen>>;
+    /// Returns all closures of the given canteen between `from` and `to` (both inclusive), ordered by date.
+    async fn get_closures(&self, canteen_id: Uuid, from: Date, to: Date) -> Result<Vec<Closure>>;
+    /// Checks whether any dishes are planned to be served at the given canteen at the given date.
+    async fn has_meal_plan(&self, canteen_id: Uuid, date: Date) -> Result<bool>;
     /// Returns the line from the database.
     async fn get_line(&self, id: Uuid) -> Result<Option<Line>>;
     /// Returns all lines of a canteen from the database.
@@ -258,8 +385,32 @@ pub trait RequestDataAccess: Send + Sync {
     async fn get_additives(&self, food_id: Uuid) -> Result<Vec<Additive>>;
     /// Returns all allergens related to the given food_id (food_id can be a meal_id or side_id).
     async fn get_allergens(&self, food_id: Uuid) -> Result<Vec<Allergen>>;
+    /// Returns all raw allergen and additive codes of a food which could not be recognized.
+    async fn get_unknown_codes(&self, food_id: Uuid) -> Result<Vec<String>>;
     /// Returns the nutritionial data related to the given food_id (food_id can be a meal_id or side_id).
     async fn get_nutrition_data(&self, food_id: Uuid) -> Result<Option<NutritionData>>;
     /// Returns the environmental data related to the given food_id (food_id can be a meal_id or side_id).
     async fn get_environment_information(&self, food_id: Uuid) -> Result<Option<EnvironmentInfo>>;
+    /// Returns the time of the last change to the meal plan, including canteens, lines, closures and dishes.
+    async fn get_last_meal_plan_update(&self) -> Result<DateTime<Local>>;
+    /// Returns the given page of all images uploaded by the given user, including hidden ones, newest first.
+    async fn get_images_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<UploadedImage>>;
+    /// Returns the given page of all meal ratings of the given user.
+    async fn get_ratings_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<MealRating>>;
+    /// Returns all image votes of the given user.
+    async fn get_image_votes_of_user(&self, client_id: Uuid) -> Result<Vec<ImageVote>>;
+    /// Returns the given page of all image reports of the given user, newest first.
+    async fn get_reports_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<ImageReport>>;
 }
@@ -1,9 +1,14 @@
 //! These structs are used for database operations.
-use crate::util::Price;
+use chrono::{DateTime, Local};
+
 use crate::util::{self, Date};
+use crate::util::{ApiKeyScope, ClosureReason, Price, ReportReason};
 
 use util::{FoodType, Uuid};
 
+/// Number of leading characters of an api key identifying it, see <https://github.com/kronos-et-al/MensaApp/blob/main/doc/ApiAuth.md>.
+pub const API_KEY_IDENT_LENGTH: usize = 10;
+
 /// Struct to storage related data. Contains all api-key related information.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ApiKey {
@@ -11,6 +16,57 @@ pub struct ApiKey {
     pub key: String,
     /// An short description for the api-key.
     pub description: String,
+    /// Time the api-key was created at.
+    pub created_at: DateTime<Local>,
+    /// Time the api-key was revoked at, if so. Revoked keys can no longer be used for authentication.
+    pub revoked_at: Option<DateTime<Local>>,
+    /// What the api-key is allowed to be used for.
+    pub permissions: ApiKeyPermissions,
+}
+
+impl ApiKey {
+    /// Returns the leading characters of the key, which identify it without revealing the whole key.
+    #[must_use]
+    pub fn ident(&self) -> &str {
+        api_key_ident(&self.key)
+    }
+
+    /// Returns whether the api-key has expired at the given time.
+    #[must_use]
+    pub fn is_expired_at(&self, time: DateTime<Local>) -> bool {
+        self.permissions
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= time)
+    }
+}
+
+/// Restrictions on what an api-key can be used for.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ApiKeyPermissions {
+    /// Mutations the api-key is allowed to perform. Without any scope, the key is read-only.
+    pub scopes: Vec<ApiKeyScope>,
+    /// Time after which the api-key can no longer be used for authentication, if any.
+    pub expires_at: Option<DateTime<Local>>,
+    /// Maximum number of authenticated requests per minute, if limited.
+    pub rate_limit: Option<u32>,
+}
+
+impl Default for ApiKeyPermissions {
+    fn default() -> Self {
+        Self {
+            scopes: ApiKeyScope::ALL.to_vec(),
+            expires_at: None,
+            rate_limit: None,
+        }
+    }
+}
+
+/// Returns the leading characters of the given api key, which identify it without revealing the whole key.
+#[must_use]
+pub fn api_key_ident(key: &str) -> &str {
+    key.char_indices()
+        .nth(API_KEY_IDENT_LENGTH)
+        .map_or(key, |(end, _)| &key[..end])
 }
 
 /// Struct for database-operations. Related to the database entity 'canteen'.
@@ -22,6 +78,19 @@ pub struct Canteen {
     pub name: String,
 }
 
+/// Struct for database-operations. Related to the database entity `canteen_closure`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Closure {
+    /// Identification of the closed canteen
+    pub canteen_id: Uuid,
+    /// Day the canteen is closed at
+    pub date: Date,
+    /// Reason the canteen is closed for
+    pub reason: ClosureReason,
+    /// Optional note further describing the closure
+    pub note: Option<String>,
+}
+
 /// Struct for database-operations. Related to the database entity 'line'.
 #[derive(Debug, Clone)]
 pub struct Line {
@@ -109,6 +178,67 @@ pub struct ExtendedImage {
     pub other_image_urls: Vec<String>,
 }
 
+/// This structure contains an image uploaded by a user, including its moderation status.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct UploadedImage {
+    /// Information about the image, see [Image].
+    pub image: Image,
+    /// Whether the image is currently shown to other users, meaning it did not get hidden because of reports.
+    pub visible: bool,
+}
+
+/// This structure contains the rating of a meal by a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MealRating {
+    /// Id of the rated meal.
+    pub meal_id: Uuid,
+    /// The rating, between 1 and 5.
+    pub rating: u32,
+}
+
+/// This structure contains the vote of an image by a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageVote {
+    /// Id of the voted image.
+    pub image_id: Uuid,
+    /// Whether the image got upvoted (`true`) or downvoted (`false`).
+    pub upvote: bool,
+}
+
+/// This structure contains the report of an image by a user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReport {
+    /// Id of the reported image.
+    pub image_id: Uuid,
+    /// Reason the image got reported for.
+    pub reason: ReportReason,
+    /// Date of the report.
+    pub report_date: Date,
+}
+
+/// This structure describes which part of a list to return.
+/// The default returns the complete list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Maximum number of elements to return, or `None` to return all remaining elements.
+    pub limit: Option<u32>,
+    /// Number of elements to skip.
+    pub offset: u32,
+}
+
+/// This structure contains what got erased when deleting all data linked to a client id.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DataErasure {
+    /// Ids of all deleted images uploaded by the client, whose files still have to be deleted.
+    pub image_ids: Vec<Uuid>,
+    /// Number of deleted meal ratings.
+    pub meal_ratings: u64,
+    /// Number of deleted image votes.
+    pub image_votes: u64,
+    /// Number of deleted image reports.
+    pub image_reports: u64,
+}
+
 /// This struct contains all environmental information. co2 in grams, water in litres
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnvironmentInfo {
@@ -129,3 +259,28 @@ pub struct EnvironmentInfo {
     /// The maximum amount of stars for each category
     pub max_rating: u32,
 }
+
+/// This structure contains information about a food planned to be served, as needed for checking the quality of parsed data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFood {
+    /// Identification of the food.
+    pub food_id: Uuid,
+    /// Name of the food.
+    pub name: String,
+    /// Type of the food.
+    pub food_type: FoodType,
+    /// Whether this food is a meal (`true`) or a side (`false`).
+    pub is_meal: bool,
+    /// Price of the food at this date and line.
+    pub price: Price,
+    /// Whether nutrition data is available for this food.
+    pub has_nutrition_data: bool,
+    /// Whether environmental information is available for this food.
+    pub has_environment_info: bool,
+    /// The date on which the food is served.
+    pub date: Date,
+    /// Name of the canteen the food is served at.
+    pub canteen_name: String,
+    /// Name of the line the food is served at.
+    pub line_name: String,
+}
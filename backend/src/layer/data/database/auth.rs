@@ -1,9 +1,16 @@
 //! Module responsible for handling database requests for the authentication process.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use sqlx::{Pool, Postgres};
 
-use crate::interface::persistent_data::{model::ApiKey, AuthDataAccess, Result};
+use crate::{
+    interface::persistent_data::{
+        model::{ApiKey, ApiKeyPermissions},
+        AuthDataAccess, DataError, Result,
+    },
+    util::{ApiKeyScope, Uuid},
+};
 
 /// Class implementing all database requests arising from graphql manipulations.
 #[derive(Debug)]
@@ -11,17 +18,132 @@ pub struct PersistentAuthData {
     pub(super) pool: Pool<Postgres>,
 }
 
+/// Api key as stored in the database.
+struct ApiKeyRow {
+    key: String,
+    description: String,
+    created_at: DateTime<Local>,
+    revoked_at: Option<DateTime<Local>>,
+    scopes: Vec<ApiKeyScope>,
+    expires_at: Option<DateTime<Local>>,
+    rate_limit: Option<i32>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = DataError;
+
+    fn try_from(row: ApiKeyRow) -> Result<Self> {
+        Ok(Self {
+            key: row.key,
+            description: row.description,
+            created_at: row.created_at,
+            revoked_at: row.revoked_at,
+            permissions: ApiKeyPermissions {
+                scopes: row.scopes,
+                expires_at: row.expires_at,
+                rate_limit: row.rate_limit.map(u32::try_from).transpose()?,
+            },
+        })
+    }
+}
+
 #[async_trait]
 impl AuthDataAccess for PersistentAuthData {
     async fn get_api_keys(&self) -> Result<Vec<ApiKey>> {
-        let keys = sqlx::query_as!(
-            ApiKey,
-            "SELECT api_key as key, description FROM api_key ORDER BY api_key"
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"SELECT api_key as key, description, created_at as "created_at: _", revoked_at as "revoked_at: _",
+            scopes as "scopes: _", expires_at as "expires_at: _", rate_limit
+            FROM api_key WHERE revoked_at IS NULL ORDER BY api_key"#
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(ApiKey::try_from)
+        .collect()
+    }
+
+    async fn get_all_api_keys(&self) -> Result<Vec<ApiKey>> {
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"SELECT api_key as key, description, created_at as "created_at: _", revoked_at as "revoked_at: _",
+            scopes as "scopes: _", expires_at as "expires_at: _", rate_limit
+            FROM api_key ORDER BY created_at, api_key"#
         )
         .fetch_all(&self.pool)
-        .await?;
+        .await?
+        .into_iter()
+        .map(ApiKey::try_from)
+        .collect()
+    }
+
+    async fn add_api_key(
+        &self,
+        key: &str,
+        description: &str,
+        permissions: &ApiKeyPermissions,
+    ) -> Result<ApiKey> {
+        let rate_limit = permissions.rate_limit.map(i32::try_from).transpose()?;
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"INSERT INTO api_key (api_key, description, scopes, expires_at, rate_limit) VALUES ($1, $2, $3, $4, $5)
+            RETURNING api_key as key, description, created_at as "created_at: _", revoked_at as "revoked_at: _",
+            scopes as "scopes: _", expires_at as "expires_at: _", rate_limit"#,
+            key,
+            description,
+            &permissions.scopes as &[ApiKeyScope],
+            permissions.expires_at,
+            rate_limit
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .try_into()
+    }
+
+    async fn revoke_api_key(&self, key: &str) -> Result<bool> {
+        let revoked = sqlx::query!(
+            "UPDATE api_key SET revoked_at = now() WHERE api_key = $1 AND revoked_at IS NULL",
+            key
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+
+        Ok(revoked)
+    }
+
+    async fn use_nonce(
+        &self,
+        client_id: Uuid,
+        nonce: &str,
+        expires_at: DateTime<Local>,
+    ) -> Result<bool> {
+        // expired nonces not deleted yet can be used again
+        let unused = sqlx::query!(
+            "
+            INSERT INTO auth_nonce (client_id, nonce, expires_at) VALUES ($1, $2, $3)
+            ON CONFLICT (client_id, nonce) DO UPDATE SET expires_at = EXCLUDED.expires_at
+            WHERE auth_nonce.expires_at < now()
+            ",
+            client_id,
+            nonce,
+            expires_at
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0;
+
+        Ok(unused)
+    }
 
-        Ok(keys)
+    async fn delete_expired_nonces(&self) -> Result<u64> {
+        let deleted = sqlx::query!("DELETE FROM auth_nonce WHERE expires_at < now()")
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        Ok(deleted)
     }
 }
 
@@ -31,26 +153,121 @@ mod tests {
     use sqlx::PgPool;
 
     use super::PersistentAuthData;
-    use crate::interface::persistent_data::{model::ApiKey, AuthDataAccess};
+    use crate::{
+        interface::persistent_data::{model::ApiKeyPermissions, AuthDataAccess},
+        util::{ApiKeyScope, Uuid},
+    };
 
     #[sqlx::test(fixtures("api_key"))]
     async fn test_get_api_keys(pool: PgPool) {
         let auth = PersistentAuthData { pool: pool.clone() };
 
         assert!(auth.get_api_keys().await.is_ok());
-        assert_eq!(auth.get_api_keys().await.unwrap(), provide_dummy_api_keys());
+        let keys = auth
+            .get_api_keys()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|k| (k.key, k.description))
+            .collect::<Vec<_>>();
+        assert_eq!(keys, provide_dummy_api_keys());
+    }
+
+    #[sqlx::test(fixtures("api_key"))]
+    async fn test_manage_api_keys(pool: PgPool) {
+        let auth = PersistentAuthData { pool: pool.clone() };
+
+        let key = auth
+            .add_api_key("new_key", "for testing", &ApiKeyPermissions::default())
+            .await
+            .unwrap();
+        assert_eq!(key.key, "new_key");
+        assert_eq!(key.description, "for testing");
+        assert!(key.revoked_at.is_none());
+        assert_eq!(key.permissions, ApiKeyPermissions::default());
+        assert_eq!(auth.get_api_keys().await.unwrap().len(), 3);
+        assert!(auth
+            .add_api_key("new_key", "duplicate", &ApiKeyPermissions::default())
+            .await
+            .is_err());
+
+        assert!(auth.revoke_api_key("new_key").await.unwrap());
+        assert!(!auth.revoke_api_key("new_key").await.unwrap());
+        assert!(!auth.revoke_api_key("unknown").await.unwrap());
+
+        assert_eq!(auth.get_api_keys().await.unwrap().len(), 2);
+        let all = auth.get_all_api_keys().await.unwrap();
+        assert_eq!(all.len(), 3);
+        let revoked = all.iter().find(|k| k.key == "new_key").unwrap();
+        assert!(revoked.revoked_at.is_some());
+    }
+
+    #[sqlx::test(fixtures("api_key"))]
+    async fn test_api_key_permissions(pool: PgPool) {
+        let auth = PersistentAuthData { pool: pool.clone() };
+
+        let permissions = ApiKeyPermissions {
+            scopes: vec![ApiKeyScope::Rate, ApiKeyScope::Vote],
+            expires_at: Some(chrono::Local::now() + chrono::Duration::days(30)),
+            rate_limit: Some(120),
+        };
+        auth.add_api_key("restricted", "", &permissions)
+            .await
+            .unwrap();
+
+        let key = auth
+            .get_api_keys()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|k| k.key == "restricted")
+            .unwrap();
+        assert_eq!(key.permissions.scopes, permissions.scopes);
+        assert_eq!(key.permissions.rate_limit, Some(120));
+        assert_eq!(
+            key.permissions.expires_at.map(|t| t.timestamp()),
+            permissions.expires_at.map(|t| t.timestamp())
+        );
+
+        let read_only = ApiKeyPermissions {
+            scopes: vec![],
+            ..Default::default()
+        };
+        let key = auth.add_api_key("read_only", "", &read_only).await.unwrap();
+        assert!(key.permissions.scopes.is_empty());
     }
 
-    fn provide_dummy_api_keys() -> Vec<ApiKey> {
+    #[sqlx::test]
+    async fn test_nonces(pool: PgPool) {
+        let auth = PersistentAuthData { pool: pool.clone() };
+        let client = Uuid::default();
+        let other_client = Uuid::from_u128(1);
+        let expires_at = chrono::Local::now() + chrono::Duration::minutes(10);
+
+        assert!(auth.use_nonce(client, "abc", expires_at).await.unwrap());
+        assert!(!auth.use_nonce(client, "abc", expires_at).await.unwrap());
+        assert!(auth
+            .use_nonce(other_client, "abc", expires_at)
+            .await
+            .unwrap());
+        assert!(auth.use_nonce(client, "def", expires_at).await.unwrap());
+
+        let expired = chrono::Local::now() - chrono::Duration::minutes(1);
+        assert!(auth.use_nonce(client, "old", expired).await.unwrap());
+        // expired nonces are forgotten, even before getting deleted
+        assert!(auth.use_nonce(client, "old", expired).await.unwrap());
+        assert_eq!(auth.delete_expired_nonces().await.unwrap(), 1);
+        assert_eq!(auth.delete_expired_nonces().await.unwrap(), 0);
+        assert!(!auth.use_nonce(client, "abc", expires_at).await.unwrap());
+    }
+
+    fn provide_dummy_api_keys() -> Vec<(String, String)> {
         vec![
-            ApiKey {
-                key: "abc".into(),
-                description: String::new(),
-            },
-            ApiKey {
-                key: "YWpzZGg4MnozNzhkMnppZGFzYXNkMiBzYWZzYSBzPGE5MDk4".into(),
-                description: String::new(),
-            },
+            ("abc".into(), String::new()),
+            (
+                "YWpzZGg4MnozNzhkMnppZGFzYXNkMiBzYWZzYSBzPGE5MDk4".into(),
+                String::new(),
+            ),
         ]
     }
 }
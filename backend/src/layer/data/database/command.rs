@@ -4,11 +4,13 @@ use sqlx::{Pool, Postgres};
 
 use crate::{
     interface::persistent_data::{
-        model::{ExtendedImage, Image},
+        model::{DataErasure, ExtendedImage, Image, PlannedFood},
         CommandDataAccess, Result,
     },
     null_error,
-    util::{image_id_to_url, ReportReason, Uuid},
+    util::{
+        image_id_to_url, ClosureReason, Date, ErasureRequester, FoodType, Price, ReportReason, Uuid,
+    },
 };
 
 /// Class implementing all database requests arising from graphql manipulations.
@@ -183,6 +185,17 @@ impl CommandDataAccess for PersistentCommandData {
         Ok(())
     }
 
+    async fn remove_rating(&self, meal_id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM meal_rating WHERE user_id = $1 AND food_id = $2",
+            user_id,
+            meal_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     async fn delete_image(&self, image_id: Uuid) -> Result<()> {
         sqlx::query!("DELETE FROM image WHERE image_id = $1", image_id)
             .execute(&self.pool)
@@ -190,6 +203,14 @@ impl CommandDataAccess for PersistentCommandData {
         Ok(())
     }
 
+    async fn get_image_uploader(&self, image_id: Uuid) -> Result<Option<Uuid>> {
+        let user_id =
+            sqlx::query_scalar!("SELECT user_id FROM image WHERE image_id = $1", image_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(user_id)
+    }
+
     async fn verify_image(&self, image_id: Uuid) -> Result<()> {
         sqlx::query!(
             "UPDATE image SET approved = true WHERE image_id = $1",
@@ -199,6 +220,134 @@ impl CommandDataAccess for PersistentCommandData {
         .await?;
         Ok(())
     }
+
+    async fn get_planned_foods(&self, from: Date) -> Result<Vec<PlannedFood>> {
+        sqlx::query!(
+            r#"
+            SELECT food_id, f.name, food_type as "food_type: FoodType",
+                food_id IN (SELECT food_id FROM meal) as "is_meal!",
+                price_student, price_employee, price_guest, price_pupil,
+                food_id IN (SELECT food_id FROM food_nutrition_data) as "has_nutrition_data!",
+                food_id IN (SELECT food_id FROM food_env_score) as "has_environment_info!",
+                serve_date, c.name as canteen_name, l.name as line_name
+            FROM food_plan JOIN food f USING (food_id)
+                JOIN line l USING (line_id)
+                JOIN canteen c USING (canteen_id)
+            WHERE serve_date >= $1
+            ORDER BY serve_date, c.position, l.position, food_id
+            "#,
+            from
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| {
+            Ok(PlannedFood {
+                food_id: r.food_id,
+                name: r.name,
+                food_type: r.food_type,
+                is_meal: r.is_meal,
+                price: Price {
+                    price_student: u32::try_from(r.price_student)?,
+                    price_employee: u32::try_from(r.price_employee)?,
+                    price_guest: u32::try_from(r.price_guest)?,
+                    price_pupil: u32::try_from(r.price_pupil)?,
+                },
+                has_nutrition_data: r.has_nutrition_data,
+                has_environment_info: r.has_environment_info,
+                date: r.serve_date,
+                canteen_name: r.canteen_name,
+                line_name: r.line_name,
+            })
+        })
+        .collect()
+    }
+
+    async fn set_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<String>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "
+            INSERT INTO canteen_closure(canteen_id, closed_date, reason, note, manual)
+            VALUES ($1, $2, $3, $4, true)
+            ON CONFLICT (canteen_id, closed_date) DO UPDATE
+            SET reason = $3, note = $4, manual = true
+            ",
+            canteen_id,
+            date,
+            reason as _,
+            note
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_closure(&self, canteen_id: Uuid, date: Date) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM canteen_closure WHERE canteen_id = $1 AND closed_date = $2",
+            canteen_id,
+            date
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_user_data(
+        &self,
+        user_id: Uuid,
+        requester: ErasureRequester,
+    ) -> Result<DataErasure> {
+        let mut transaction = self.pool.begin().await?;
+
+        let meal_ratings = sqlx::query!("DELETE FROM meal_rating WHERE user_id = $1", user_id)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        let image_votes = sqlx::query!("DELETE FROM image_rating WHERE user_id = $1", user_id)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        let image_reports = sqlx::query!("DELETE FROM image_report WHERE user_id = $1", user_id)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        // votes and reports of other users on these images get deleted by cascade
+        let image_ids = sqlx::query_scalar!(
+            "DELETE FROM image WHERE user_id = $1 RETURNING image_id",
+            user_id
+        )
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO data_erasure(client_id_hash, requested_by, meal_ratings, image_votes, image_reports, images)
+            VALUES (sha256(convert_to($1::uuid::text, 'UTF8')), $2, $3, $4, $5, $6)
+            "#,
+            user_id,
+            requester as _,
+            i32::try_from(meal_ratings)?,
+            i32::try_from(image_votes)?,
+            i32::try_from(image_reports)?,
+            i32::try_from(image_ids.len())?,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(DataErasure {
+            image_ids,
+            meal_ratings,
+            image_votes,
+            image_reports,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -474,6 +623,20 @@ mod test {
         assert_eq!(1, rating);
     }
 
+    #[sqlx::test(fixtures("meal", "meal_rating"))]
+    async fn test_remove_rating(pool: PgPool) {
+        let command = PersistentCommandData { pool: pool.clone() };
+        let meal_id = Uuid::parse_str("f7337122-b018-48ad-b420-6202dc3cb4ff").unwrap();
+        let user_id = Uuid::parse_str("0562269b-8c46-4d5f-9749-25f93c062748").unwrap();
+
+        let ratings = number_of_ratings(&pool).await;
+        assert!(command.remove_rating(meal_id, user_id).await.is_ok());
+        assert_eq!(number_of_ratings(&pool).await, ratings - 1);
+        assert!(command.remove_rating(meal_id, user_id).await.is_ok());
+        assert!(command.remove_rating(WRONG_UUID, user_id).await.is_ok());
+        assert_eq!(number_of_ratings(&pool).await, ratings - 1);
+    }
+
     async fn number_of_ratings(pool: &PgPool) -> usize {
         sqlx::query!("SELECT * FROM meal_rating")
             .fetch_all(pool)
@@ -498,6 +661,85 @@ mod test {
         );
     }
 
+    #[sqlx::test(fixtures("meal", "image", "rating"))]
+    async fn test_delete_user_data(pool: PgPool) {
+        let command = PersistentCommandData { pool: pool.clone() };
+        let user_id = "c51d2d81-3547-4f07-af58-ed613c6ece67".try_into().unwrap();
+
+        let mut erasure = command
+            .delete_user_data(user_id, ErasureRequester::Client)
+            .await
+            .unwrap();
+        erasure.image_ids.sort();
+        assert_eq!(
+            erasure,
+            DataErasure {
+                image_ids: vec![
+                    "1aa73d5d-1701-4975-aa3c-1422a8bc10e8".try_into().unwrap(),
+                    "68153ab6-ebbf-48f4-b8dd-a9b2a19a5221".try_into().unwrap(),
+                    "76b904fe-d0f1-4122-8832-d0e21acab86d".try_into().unwrap(),
+                ],
+                meal_ratings: 2,
+                image_votes: 2,
+                image_reports: 1,
+            }
+        );
+
+        let remaining = sqlx::query_scalar!(
+            r#"
+            SELECT (SELECT COUNT(*) FROM meal_rating WHERE user_id = $1)
+                + (SELECT COUNT(*) FROM image_rating WHERE user_id = $1)
+                + (SELECT COUNT(*) FROM image_report WHERE user_id = $1)
+                + (SELECT COUNT(*) FROM image WHERE user_id = $1) as "count!"
+            "#,
+            user_id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(remaining, 0);
+
+        // data of other users stays
+        let other_images = sqlx::query_scalar!(r#"SELECT COUNT(*) as "count!" FROM image"#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(other_images, 1);
+
+        let audit = sqlx::query!(
+            r#"
+            SELECT requested_by as "requested_by: ErasureRequester", images, client_id_hash = sha256(convert_to($1::uuid::text, 'UTF8')) as "hash_matches!"
+            FROM data_erasure
+            "#,
+            user_id
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(audit.requested_by, ErasureRequester::Client);
+        assert_eq!(audit.images, 3);
+        assert!(audit.hash_matches);
+
+        // nothing left to delete
+        let erasure = command
+            .delete_user_data(user_id, ErasureRequester::Admin)
+            .await
+            .unwrap();
+        assert_eq!(erasure, DataErasure::default());
+    }
+
+    #[sqlx::test(fixtures("meal", "image"))]
+    async fn test_get_image_uploader(pool: PgPool) {
+        let command = PersistentCommandData { pool };
+        let id = "ea8cce48-a3c7-4f8e-a222-5f3891c13804".try_into().unwrap();
+
+        assert_eq!(
+            command.get_image_uploader(id).await.unwrap(),
+            Some("00adb927-8cb9-4d80-ae01-d8f2e8f2d4cf".try_into().unwrap())
+        );
+        assert_eq!(command.get_image_uploader(WRONG_UUID).await.unwrap(), None);
+    }
+
     #[sqlx::test(fixtures("meal", "image"))]
     async fn test_verify_image(pool: PgPool) {
         let command = PersistentCommandData { pool: pool.clone() };
@@ -511,4 +753,90 @@ mod test {
                 .unwrap()
         );
     }
+
+    #[sqlx::test(fixtures(
+        "canteen",
+        "line",
+        "meal",
+        "food_plan",
+        "nutrition_data",
+        "environment_info"
+    ))]
+    async fn test_get_planned_foods(pool: PgPool) {
+        let command = PersistentCommandData { pool };
+        let today = Local::now().date_naive();
+
+        let foods = command.get_planned_foods(today).await.unwrap();
+        assert_eq!(foods.len(), 4);
+        assert_eq!(
+            foods[0],
+            PlannedFood {
+                food_id: Uuid::parse_str("25cb8c50-75a4-48a2-b4cf-8ab2566d8bec").unwrap(),
+                name: "2 Dampfnudeln mit Vanillesoße".into(),
+                food_type: FoodType::Vegetarian,
+                is_meal: true,
+                price: Price {
+                    price_student: 320,
+                    price_employee: 420,
+                    price_guest: 460,
+                    price_pupil: 355,
+                },
+                has_nutrition_data: true,
+                has_environment_info: true,
+                date: today,
+                canteen_name: "my favorite canteen".into(),
+                line_name: "line 1".into(),
+            }
+        );
+        assert!(foods.iter().any(|f| !f.is_meal && !f.has_nutrition_data));
+
+        let tomorrow = today.succ_opt().unwrap();
+        assert!(command
+            .get_planned_foods(tomorrow)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test(fixtures("canteen", "closure"))]
+    async fn test_set_and_remove_closure(pool: PgPool) {
+        let command = PersistentCommandData { pool: pool.clone() };
+
+        let canteen_id = Uuid::parse_str("10728cc4-1e07-4e18-a9d9-ca45b9782413").unwrap();
+        let parsed_date = Date::from_ymd_opt(2023, 12, 27).unwrap();
+        let new_date = Date::from_ymd_opt(2023, 12, 28).unwrap();
+
+        // overrides parsed closure
+        command
+            .set_closure(
+                canteen_id,
+                parsed_date,
+                ClosureReason::Holiday,
+                Some("Christmas".into()),
+            )
+            .await
+            .unwrap();
+        command
+            .set_closure(canteen_id, new_date, ClosureReason::Event, None)
+            .await
+            .unwrap();
+
+        let closures = sqlx::query!(
+            r#"SELECT closed_date, reason as "reason: ClosureReason", note, manual FROM canteen_closure WHERE canteen_id = $1 ORDER BY closed_date"#,
+            canteen_id
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(closures.len(), 3);
+        assert_eq!(closures[1].closed_date, parsed_date);
+        assert_eq!(closures[1].reason, ClosureReason::Holiday);
+        assert_eq!(closures[1].note.as_deref(), Some("Christmas"));
+        assert!(closures[1].manual);
+        assert_eq!(closures[2].reason, ClosureReason::Event);
+        assert_eq!(closures[2].note, None);
+
+        assert!(command.remove_closure(canteen_id, new_date).await.unwrap());
+        assert!(!command.remove_closure(canteen_id, new_date).await.unwrap());
+    }
 }
@@ -1,12 +1,14 @@
 //! Module containing a factory pattern to construct instances to access the database for all components needing it.
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::interface::persistent_data::Result;
+use crate::{interface::persistent_data::Result, util::metrics};
 
 use super::{
-    auth::PersistentAuthData, command::PersistentCommandData,
-    mealplan_management::PersistentMealplanManagementData, request::PersistentRequestData,
+    auth::PersistentAuthData, command::PersistentCommandData, health::PersistentHealthData,
+    job_lock::PersistentJobLockData, maintenance::PersistentMaintenanceData,
+    mealplan_management::PersistentMealplanManagementData, pool_metrics::PoolMetrics,
+    request::PersistentRequestData,
 };
 
 /// Structure containing all information necessary to connect to a database.
@@ -52,6 +54,15 @@ impl DataAccessFactory {
         })
     }
 
+    /// Exports the usage of the database connection pool as metrics.
+    /// The pool is kept open as long as the metrics are registered.
+    pub fn register_pool_metrics(&self) {
+        match PoolMetrics::new(self.pool.clone()) {
+            Ok(pool_metrics) => metrics::register_collector(pool_metrics),
+            Err(e) => warn!("Could not create database pool metrics: {e}"),
+        }
+    }
+
     /// Returns a object for accessing database requests for api commands.
     #[must_use]
     pub fn get_command_data_access(&self) -> PersistentCommandData {
@@ -74,6 +85,30 @@ impl DataAccessFactory {
         PersistentRequestData::new(self.pool.clone(), self.max_weeks_data)
     }
 
+    /// Returns a object for accessing database requests for maintenance tasks.
+    #[must_use]
+    pub fn get_maintenance_data_access(&self) -> PersistentMaintenanceData {
+        PersistentMaintenanceData {
+            pool: self.pool.clone(),
+        }
+    }
+
+    /// Returns a object for checking the availability of the database.
+    #[must_use]
+    pub fn get_health_data_access(&self) -> PersistentHealthData {
+        PersistentHealthData {
+            pool: self.pool.clone(),
+        }
+    }
+
+    /// Returns a object for coordinating scheduled jobs between multiple instances.
+    #[must_use]
+    pub fn get_job_lock_data_access(&self) -> PersistentJobLockData {
+        PersistentJobLockData {
+            pool: self.pool.clone(),
+        }
+    }
+
     /// Returns a object for accessing database requests for authentication.
     #[must_use]
     pub fn get_auth_data_access(&self) -> PersistentAuthData {
@@ -112,6 +147,9 @@ mod tests {
         let _ = factory.get_mealplan_management_data_access();
         let _ = factory.get_request_data_access();
         let _ = factory.get_auth_data_access();
+        let _ = factory.get_maintenance_data_access();
+        let _ = factory.get_job_lock_data_access();
+        let _ = factory.get_health_data_access();
 
         std::mem::drop(factory); // drop database connection
 
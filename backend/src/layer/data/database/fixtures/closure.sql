@@ -0,0 +1,4 @@
+INSERT INTO canteen_closure (canteen_id, closed_date, reason, note, manual)
+VALUES  ('10728cc4-1e07-4e18-a9d9-ca45b9782413', '2023-12-25', 'HOLIDAY', 'Christmas', true),
+        ('10728cc4-1e07-4e18-a9d9-ca45b9782413', '2023-12-27', 'UNSPECIFIED', NULL, false),
+        ('8f10c56d-da9b-4f62-b4c1-16feb0f98c67', '2023-12-25', 'SEMESTER_BREAK', NULL, true);
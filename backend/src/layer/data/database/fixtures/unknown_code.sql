@@ -0,0 +1,4 @@
+INSERT INTO food_unknown_code (food_id, code)
+VALUES  ('f7337122-b018-48ad-b420-6202dc3cb4ff', 'GEL'),
+        ('25cb8c50-75a4-48a2-b4cf-8ab2566d8bec', 'GEL'),
+        ('25cb8c50-75a4-48a2-b4cf-8ab2566d8bec', 'Xy');
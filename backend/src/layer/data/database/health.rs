@@ -0,0 +1,62 @@
+//! Module responsible for checking the availability of the database.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use sqlx::{Pool, Postgres};
+
+use crate::interface::persistent_data::{HealthDataAccess, Result};
+
+/// Class implementing the database requests for checking the availability of the database.
+#[derive(Debug)]
+pub struct PersistentHealthData {
+    pub(super) pool: Pool<Postgres>,
+}
+
+#[async_trait]
+impl HealthDataAccess for PersistentHealthData {
+    async fn check_connection(&self) -> Result<()> {
+        sqlx::query_scalar!("SELECT 1 as \"one!\"")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_last_parse_success(&self) -> Result<Option<DateTime<Local>>> {
+        let last = sqlx::query_scalar!("SELECT max(finished_at) FROM parse_run")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(last.map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Duration, Local};
+    use sqlx::PgPool;
+
+    use super::PersistentHealthData;
+    use crate::interface::persistent_data::HealthDataAccess;
+
+    #[sqlx::test]
+    async fn test_check_connection(pool: PgPool) {
+        let health = PersistentHealthData { pool: pool.clone() };
+        assert!(health.check_connection().await.is_ok());
+
+        pool.close().await;
+        assert!(health.check_connection().await.is_err());
+    }
+
+    #[sqlx::test]
+    async fn test_get_last_parse_success(pool: PgPool) {
+        let health = PersistentHealthData { pool: pool.clone() };
+        assert!(health.get_last_parse_success().await.unwrap().is_none());
+
+        sqlx::query!("INSERT INTO parse_run(kind, finished_at, dishes) VALUES ('update', now() - interval '1 day', 3), ('full', now(), 5)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let last = health.get_last_parse_success().await.unwrap().unwrap();
+        assert!(Local::now() - last < Duration::hours(1));
+    }
+}
@@ -0,0 +1,167 @@
+//! Module responsible for handling database requests for coordinating scheduled jobs between instances.
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use sqlx::{pool::PoolConnection, Pool, Postgres};
+use tracing::{debug, warn};
+
+use crate::interface::persistent_data::{JobLock, JobLockDataAccess, Result};
+
+/// First key of all advisory locks for jobs, distinguishing them from other advisory locks.
+const JOB_LOCK_NAMESPACE: i32 = 0x4d_4b_4a_42; // "MKJB"
+
+/// Class for performing database operations necessary for coordinating scheduled jobs.
+///
+/// Locks are implemented as postgres [advisory locks](https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS) on a dedicated connection,
+/// so they get released automatically when an instance crashes.
+#[derive(Debug)]
+pub struct PersistentJobLockData {
+    pub(super) pool: Pool<Postgres>,
+}
+
+#[async_trait]
+impl JobLockDataAccess for PersistentJobLockData {
+    async fn try_lock_job(
+        &self,
+        job: &str,
+        scheduled_at: Option<DateTime<Local>>,
+    ) -> Result<Option<Box<dyn JobLock>>> {
+        let mut connection = self.pool.acquire().await?;
+
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1, hashtext($2)) as "locked!""#,
+            JOB_LOCK_NAMESPACE,
+            job
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+
+        if !locked {
+            debug!("Job '{job}' is locked by another instance.");
+            return Ok(None);
+        }
+
+        // the session holding the lock gets closed afterwards, so it can never be returned to the pool while still holding the lock
+        connection.close_on_drop();
+        let mut lock = PgJobLock {
+            connection,
+            job: job.to_string(),
+        };
+
+        if let Some(scheduled_at) = scheduled_at {
+            sqlx::query!("DELETE FROM job_claim WHERE claimed_at < now() - interval '7 days'")
+                .execute(&mut *lock.connection)
+                .await?;
+
+            let claimed = sqlx::query!(
+                "INSERT INTO job_claim (job_name, scheduled_at) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                job,
+                scheduled_at.with_timezone(&Utc)
+            )
+            .execute(&mut *lock.connection)
+            .await?
+            .rows_affected()
+                > 0;
+
+            if !claimed {
+                debug!("Run of job '{job}' scheduled at {scheduled_at} was already claimed.");
+                Box::new(lock).release().await?;
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(Box::new(lock)))
+    }
+}
+
+/// Advisory lock on a job, held as long as the connection is open.
+struct PgJobLock {
+    connection: PoolConnection<Postgres>,
+    job: String,
+}
+
+#[async_trait]
+impl JobLock for PgJobLock {
+    async fn release(mut self: Box<Self>) -> Result<()> {
+        let unlocked = sqlx::query_scalar!(
+            r#"SELECT pg_advisory_unlock($1, hashtext($2)) as "unlocked!""#,
+            JOB_LOCK_NAMESPACE,
+            self.job
+        )
+        .fetch_one(&mut *self.connection)
+        .await?;
+
+        if !unlocked {
+            warn!("Lock of job '{}' was not held on release.", self.job);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Local, TimeZone};
+    use sqlx::PgPool;
+
+    use super::PersistentJobLockData;
+    use crate::interface::persistent_data::JobLockDataAccess;
+
+    #[sqlx::test]
+    async fn test_lock_job(pool: PgPool) {
+        let locks = PersistentJobLockData { pool };
+
+        let lock = locks.try_lock_job("job", None).await.unwrap().unwrap();
+        assert!(locks.try_lock_job("job", None).await.unwrap().is_none());
+        assert!(locks
+            .try_lock_job("other_job", None)
+            .await
+            .unwrap()
+            .is_some());
+
+        lock.release().await.unwrap();
+        let lock = locks.try_lock_job("job", None).await.unwrap().unwrap();
+        lock.release().await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_lock_dropped(pool: PgPool) {
+        let locks = PersistentJobLockData { pool };
+
+        let lock = locks.try_lock_job("job", None).await.unwrap().unwrap();
+        drop(lock);
+        // closing the connection happens in the background
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(locks.try_lock_job("job", None).await.unwrap().is_some());
+    }
+
+    #[sqlx::test]
+    async fn test_claim_scheduled_run(pool: PgPool) {
+        let locks = PersistentJobLockData { pool };
+        let scheduled_at = Local.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap();
+
+        let lock = locks
+            .try_lock_job("job", Some(scheduled_at))
+            .await
+            .unwrap()
+            .unwrap();
+        lock.release().await.unwrap();
+
+        // already claimed, even after the lock got released
+        assert!(locks
+            .try_lock_job("job", Some(scheduled_at))
+            .await
+            .unwrap()
+            .is_none());
+
+        // manual runs and other scheduled runs are not affected
+        let lock = locks.try_lock_job("job", None).await.unwrap().unwrap();
+        lock.release().await.unwrap();
+        let next_run = Local.with_ymd_and_hms(2024, 1, 2, 2, 0, 0).unwrap();
+        let lock = locks
+            .try_lock_job("job", Some(next_run))
+            .await
+            .unwrap()
+            .unwrap();
+        lock.release().await.unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+//! Module responsible for handling database requests for regular maintenance tasks.
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    interface::persistent_data::{MaintenanceDataAccess, Result},
+    util::{Date, Uuid},
+};
+
+/// Class for performing database operations necessary for maintenance tasks.
+#[derive(Debug)]
+pub struct PersistentMaintenanceData {
+    pub(super) pool: Pool<Postgres>,
+}
+
+#[async_trait]
+impl MaintenanceDataAccess for PersistentMaintenanceData {
+    async fn delete_reports_before(&self, date: Date) -> Result<u64> {
+        let result = sqlx::query!("DELETE FROM image_report WHERE report_date < $1", date)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_all_image_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar!("SELECT image_id FROM image")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(ids)
+    }
+
+    async fn refresh_statistics(&self) -> Result<()> {
+        // `ANALYZE` cannot be prepared, so it is not checked at compile time
+        sqlx::raw_sql("ANALYZE").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_meal_plans_before(&self, date: Date) -> Result<u64> {
+        let mut transaction = self.pool.begin().await?;
+
+        let food_plans = sqlx::query!("DELETE FROM food_plan WHERE serve_date < $1", date)
+            .execute(&mut *transaction)
+            .await?;
+        let closures = sqlx::query!("DELETE FROM canteen_closure WHERE closed_date < $1", date)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(food_plans.rows_affected() + closures.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Duration, Local, NaiveDate};
+    use sqlx::PgPool;
+
+    use super::PersistentMaintenanceData;
+    use crate::interface::persistent_data::MaintenanceDataAccess;
+
+    #[sqlx::test(fixtures("meal", "image"))]
+    async fn test_delete_reports_before(pool: PgPool) {
+        let maintenance = PersistentMaintenanceData { pool: pool.clone() };
+        let today = Local::now().date_naive();
+
+        // fixture report is dated tomorrow
+        assert_eq!(maintenance.delete_reports_before(today).await.unwrap(), 0);
+        assert_eq!(
+            maintenance
+                .delete_reports_before(today + Duration::days(2))
+                .await
+                .unwrap(),
+            1
+        );
+
+        let count = sqlx::query_scalar!("SELECT COUNT(*) FROM image_report")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, Some(0));
+    }
+
+    #[sqlx::test(fixtures("meal", "image"))]
+    async fn test_get_all_image_ids(pool: PgPool) {
+        let maintenance = PersistentMaintenanceData { pool };
+
+        // including hidden images
+        assert_eq!(maintenance.get_all_image_ids().await.unwrap().len(), 4);
+    }
+
+    #[sqlx::test]
+    async fn test_refresh_statistics(pool: PgPool) {
+        let maintenance = PersistentMaintenanceData { pool };
+        assert!(maintenance.refresh_statistics().await.is_ok());
+    }
+
+    #[sqlx::test(fixtures("canteen", "line", "meal", "food_plan", "closure"))]
+    async fn test_delete_meal_plans_before(pool: PgPool) {
+        let maintenance = PersistentMaintenanceData { pool };
+        let today = Local::now().date_naive();
+
+        // only closures from 2023-12-25
+        assert_eq!(
+            maintenance
+                .delete_meal_plans_before(NaiveDate::from_ymd_opt(2023, 12, 26).unwrap())
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            maintenance.delete_meal_plans_before(today).await.unwrap(),
+            1
+        );
+        // four meal plan entries from today
+        assert_eq!(
+            maintenance
+                .delete_meal_plans_before(today + Duration::days(1))
+                .await
+                .unwrap(),
+            4
+        );
+    }
+}
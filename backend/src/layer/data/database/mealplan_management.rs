@@ -7,7 +7,7 @@ use crate::{
         mensa_parser::model::ParseEnvironmentInfo,
         persistent_data::{MealplanManagementDataAccess, Result},
     },
-    util::{Additive, Allergen, Date, FoodType, NutritionData, Price, Uuid},
+    util::{Additive, Allergen, ClosureReason, Date, FoodType, NutritionData, Price, Uuid},
 };
 
 /// Class for performing database operations necessary for meal plan management.
@@ -278,6 +278,77 @@ impl MealplanManagementDataAccess for PersistentMealplanManagementData {
     ) -> Result<()> {
         self.add_to_plan(side_id, line_id, date, price).await
     }
+
+    async fn get_all_unknown_codes(&self) -> Result<Vec<String>> {
+        let codes =
+            sqlx::query_scalar!("SELECT DISTINCT code FROM food_unknown_code ORDER BY code")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(codes)
+    }
+
+    async fn set_unknown_codes(&self, food_id: Uuid, codes: &[String]) -> Result<()> {
+        sqlx::query!("DELETE FROM food_unknown_code WHERE food_id = $1", food_id)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO food_unknown_code(food_id, code) SELECT $1, UNNEST($2::text[]) ON CONFLICT DO NOTHING",
+            food_id,
+            codes
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn add_parsed_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "
+            INSERT INTO canteen_closure(canteen_id, closed_date, reason, note) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (canteen_id, closed_date) DO UPDATE SET reason = EXCLUDED.reason, note = EXCLUDED.note
+            WHERE NOT canteen_closure.manual
+            ",
+            canteen_id,
+            date,
+            reason as ClosureReason,
+            note
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn remove_parsed_closure(&self, canteen_id: Uuid, date: Date) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM canteen_closure WHERE canteen_id = $1 AND closed_date = $2 AND NOT manual",
+            canteen_id,
+            date
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_parse_success(&self, kind: &str, dishes: u32) -> Result<()> {
+        sqlx::query!(
+            "
+            INSERT INTO parse_run(kind, dishes) VALUES ($1, $2)
+            ON CONFLICT (kind) DO UPDATE SET finished_at = now(), dishes = $2
+            ",
+            kind,
+            i32::try_from(dishes)?
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
 }
 
 impl PersistentMealplanManagementData {
@@ -1283,6 +1354,115 @@ mod test {
         assert_eq!(price.price_pupil, record.price_pupil as u32);
     }
 
+    #[sqlx::test(fixtures("meal"))]
+    async fn test_unknown_codes(pool: PgPool) {
+        let data = PersistentMealplanManagementData { pool: pool.clone() };
+
+        let meal_id = Uuid::try_from("f7337122-b018-48ad-b420-6202dc3cb4ff").unwrap();
+        let side_id = Uuid::try_from("73cf367b-a536-4b49-ad0c-cb984caa9a08").unwrap();
+
+        assert!(data.get_all_unknown_codes().await.unwrap().is_empty());
+
+        data.set_unknown_codes(meal_id, &["GEL".into(), "Xy".into()])
+            .await
+            .unwrap();
+        data.set_unknown_codes(side_id, &["GEL".into()])
+            .await
+            .unwrap();
+        assert_eq!(
+            data.get_all_unknown_codes().await.unwrap(),
+            vec!["GEL".to_string(), "Xy".to_string()]
+        );
+
+        // codes get replaced
+        data.set_unknown_codes(meal_id, &[]).await.unwrap();
+        data.set_unknown_codes(side_id, &["Ab".into()])
+            .await
+            .unwrap();
+        assert_eq!(
+            data.get_all_unknown_codes().await.unwrap(),
+            vec!["Ab".to_string()]
+        );
+    }
+
+    #[sqlx::test(fixtures("canteen", "closure"))]
+    async fn test_parsed_closures(pool: PgPool) {
+        let data = PersistentMealplanManagementData { pool: pool.clone() };
+
+        let canteen_id = Uuid::try_from("10728cc4-1e07-4e18-a9d9-ca45b9782413").unwrap();
+        let manual_date = Date::from_ymd_opt(2023, 12, 25).unwrap();
+        let parsed_date = Date::from_ymd_opt(2023, 12, 27).unwrap();
+        let new_date = Date::from_ymd_opt(2023, 12, 28).unwrap();
+
+        data.add_parsed_closure(canteen_id, new_date, ClosureReason::Unspecified, None)
+            .await
+            .unwrap();
+        // closures detected earlier get updated
+        data.add_parsed_closure(
+            canteen_id,
+            parsed_date,
+            ClosureReason::SemesterBreak,
+            Some("Betriebsferien"),
+        )
+        .await
+        .unwrap();
+        // manual closures must not be overridden
+        data.add_parsed_closure(canteen_id, manual_date, ClosureReason::Event, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_closures(&pool, canteen_id).await,
+            vec![
+                (manual_date, "HOLIDAY".into(), Some("Christmas".into())),
+                (
+                    parsed_date,
+                    "SEMESTER_BREAK".into(),
+                    Some("Betriebsferien".into())
+                ),
+                (new_date, "UNSPECIFIED".into(), None),
+            ]
+        );
+
+        for date in [manual_date, parsed_date, new_date] {
+            data.remove_parsed_closure(canteen_id, date).await.unwrap();
+        }
+        assert_eq!(
+            get_closures(&pool, canteen_id).await,
+            vec![(manual_date, "HOLIDAY".into(), Some("Christmas".into()))]
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_record_parse_success(pool: PgPool) {
+        let data = PersistentMealplanManagementData { pool: pool.clone() };
+
+        data.record_parse_success("update", 3).await.unwrap();
+        data.record_parse_success("full", 10).await.unwrap();
+        data.record_parse_success("update", 4).await.unwrap();
+
+        let runs = sqlx::query!("SELECT kind, dishes FROM parse_run ORDER BY kind")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.kind, r.dishes))
+            .collect::<Vec<_>>();
+        assert_eq!(runs, vec![("full".into(), 10), ("update".into(), 4)]);
+    }
+
+    async fn get_closures(pool: &PgPool, canteen_id: Uuid) -> Vec<(Date, String, Option<String>)> {
+        sqlx::query!(
+            r#"SELECT closed_date, reason::text as "reason!", note FROM canteen_closure WHERE canteen_id = $1 ORDER BY closed_date"#,
+            canteen_id
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.closed_date, r.reason, r.note))
+        .collect()
+    }
+
     async fn get_env_info(pool: &PgPool, food_id: Uuid) -> ParseEnvironmentInfo {
         ParseEnvironmentInfo::from_row(
             &sqlx::query("SELECT * FROM food_env_score WHERE food_id = $1")
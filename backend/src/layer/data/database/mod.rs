@@ -2,6 +2,10 @@
 pub mod auth;
 pub mod command;
 pub mod factory;
+pub mod health;
+pub mod job_lock;
+pub mod maintenance;
 pub mod mealplan_management;
+mod pool_metrics;
 pub mod request;
 mod types;
@@ -0,0 +1,84 @@
+//! See [`PoolMetrics`].
+use prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    IntGauge, IntGaugeVec, Opts,
+};
+use sqlx::{Pool, Postgres};
+
+/// Collector reporting the usage of the database connection pool at the time of scraping.
+pub(super) struct PoolMetrics {
+    pool: Pool<Postgres>,
+    connections: IntGaugeVec,
+    max_connections: IntGauge,
+}
+
+impl PoolMetrics {
+    pub(super) fn new(pool: Pool<Postgres>) -> prometheus::Result<Self> {
+        Ok(Self {
+            pool,
+            connections: IntGaugeVec::new(
+                Opts::new(
+                    "mensa_db_pool_connections",
+                    "Number of open database connections by state.",
+                ),
+                &["state"],
+            )?,
+            max_connections: IntGauge::new(
+                "mensa_db_pool_max_connections",
+                "Maximum number of database connections.",
+            )?,
+        })
+    }
+}
+
+impl Collector for PoolMetrics {
+    fn desc(&self) -> Vec<&Desc> {
+        self.connections
+            .desc()
+            .into_iter()
+            .chain(self.max_connections.desc())
+            .collect()
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let size = i64::from(self.pool.size());
+        let idle = i64::try_from(self.pool.num_idle()).unwrap_or(size);
+        self.connections.with_label_values(&["idle"]).set(idle);
+        self.connections
+            .with_label_values(&["active"])
+            .set(size - idle);
+        self.max_connections
+            .set(self.pool.options().get_max_connections().into());
+
+        self.connections
+            .collect()
+            .into_iter()
+            .chain(self.max_connections.collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use prometheus::core::Collector;
+    use sqlx::PgPool;
+
+    use super::PoolMetrics;
+
+    #[sqlx::test]
+    async fn test_collect(pool: PgPool) {
+        let _connection = pool.acquire().await.unwrap();
+        let metrics = PoolMetrics::new(pool).unwrap();
+
+        let families = metrics.collect();
+        assert_eq!(families.len(), 2);
+        let active = families[0]
+            .get_metric()
+            .iter()
+            .find(|m| m.get_label()[0].value() == "active")
+            .unwrap();
+        assert!((active.get_gauge().get_value() - 1.0).abs() < f64::EPSILON);
+    }
+}
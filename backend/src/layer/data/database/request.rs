@@ -5,21 +5,24 @@ mod dataloader;
 use async_graphql::dataloader::DataLoader;
 use async_once_cell::OnceCell;
 use async_trait::async_trait;
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{DateTime, Duration, Local, NaiveDate};
 use dataloader::{
     AdditiveLoader, AllergenLoader, CanteenDataloader, CanteenLinesLoader, DownvoteKey,
     EnvironmentInfoLoader, ImageLoader, ImageVoteLoader, LineDataLoader, LineDishKey,
     ManyMealsDataLoader, MealDataLoader, MealKey, NutritionDataLoader, RatingKey, RatingLoader,
-    SidesLoader, UpvoteKey,
+    SidesLoader, UnknownCodeLoader, UpvoteKey,
 };
 use sqlx::{Pool, Postgres};
 
 use crate::{
     interface::persistent_data::{
-        model::{Canteen, EnvironmentInfo, Image, Line, Meal, Side},
+        model::{
+            Canteen, Closure, EnvironmentInfo, Image, ImageReport, ImageVote, Line, Meal,
+            MealRating, Pagination, Side, UploadedImage,
+        },
         DataError, RequestDataAccess, Result,
     },
-    util::{Additive, Allergen, Date, NutritionData, Uuid},
+    util::{Additive, Allergen, ClosureReason, Date, NutritionData, ReportReason, Uuid},
 };
 
 /// Class implementing all database requests arising from graphql manipulations.
@@ -40,6 +43,7 @@ pub struct PersistentRequestData {
     image_vote_loader: DataLoader<ImageVoteLoader>,
     additive_loader: DataLoader<AdditiveLoader>,
     allergen_loader: DataLoader<AllergenLoader>,
+    unknown_code_loader: DataLoader<UnknownCodeLoader>,
     environment_info_loader: DataLoader<EnvironmentInfoLoader>,
     nutrition_data_loader: DataLoader<NutritionDataLoader>,
 }
@@ -62,6 +66,7 @@ impl PersistentRequestData {
             image_vote_loader: DataLoader::new(ImageVoteLoader(pool.clone()), tokio::spawn),
             additive_loader: DataLoader::new(AdditiveLoader(pool.clone()), tokio::spawn),
             allergen_loader: DataLoader::new(AllergenLoader(pool.clone()), tokio::spawn),
+            unknown_code_loader: DataLoader::new(UnknownCodeLoader(pool.clone()), tokio::spawn),
             nutrition_data_loader: DataLoader::new(NutritionDataLoader(pool.clone()), tokio::spawn),
             environment_info_loader: DataLoader::new(
                 EnvironmentInfoLoader(pool.clone()),
@@ -86,6 +91,40 @@ impl RequestDataAccess for PersistentRequestData {
             .and_then(|c| c.ok_or(DataError::NoSuchItem))
     }
 
+    async fn get_closures(&self, canteen_id: Uuid, from: Date, to: Date) -> Result<Vec<Closure>> {
+        sqlx::query_as!(
+            Closure,
+            r#"
+            SELECT canteen_id, closed_date as date, reason as "reason: ClosureReason", note
+            FROM canteen_closure
+            WHERE canteen_id = $1 AND closed_date BETWEEN $2 AND $3
+            ORDER BY closed_date
+            "#,
+            canteen_id,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn has_meal_plan(&self, canteen_id: Uuid, date: Date) -> Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM food_plan JOIN line USING (line_id)
+                WHERE canteen_id = $1 AND serve_date = $2
+            ) as "exists!"
+            "#,
+            canteen_id,
+            date
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
     async fn get_line(&self, id: Uuid) -> Result<Option<Line>> {
         self.line_loader.load_one(id).await
     }
@@ -213,6 +252,13 @@ impl RequestDataAccess for PersistentRequestData {
             .map(Option::unwrap_or_default)
     }
 
+    async fn get_unknown_codes(&self, food_id: Uuid) -> Result<Vec<String>> {
+        self.unknown_code_loader
+            .load_one(food_id)
+            .await
+            .map(Option::unwrap_or_default)
+    }
+
     async fn get_nutrition_data(&self, food_id: Uuid) -> Result<Option<NutritionData>> {
         self.nutrition_data_loader.load_one(food_id).await
     }
@@ -220,6 +266,116 @@ impl RequestDataAccess for PersistentRequestData {
     async fn get_environment_information(&self, food_id: Uuid) -> Result<Option<EnvironmentInfo>> {
         self.environment_info_loader.load_one(food_id).await
     }
+
+    async fn get_last_meal_plan_update(&self) -> Result<DateTime<Local>> {
+        sqlx::query_scalar!("SELECT updated_at FROM meal_plan_update")
+            .fetch_one(&self.pool)
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn get_images_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<UploadedImage>> {
+        sqlx::query!(
+            r#"
+            SELECT image_id as "image_id!", rank as "rank!", upvotes as "upvotes!", downvotes as "downvotes!", approved as "approved!",
+                report_count as "report_count!", link_date as "upload_date!", food_id as "meal_id!", currently_visible as "visible!"
+            FROM image_detail
+            WHERE user_id = $1
+            ORDER BY link_date DESC, image_id
+            LIMIT $2 OFFSET $3
+            "#,
+            client_id,
+            page.limit.map(i64::from),
+            i64::from(page.offset)
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| {
+            Ok(UploadedImage {
+                image: Image {
+                    id: r.image_id,
+                    rank: r.rank,
+                    upvotes: u32::try_from(r.upvotes)?,
+                    downvotes: u32::try_from(r.downvotes)?,
+                    approved: r.approved,
+                    upload_date: r.upload_date,
+                    report_count: u32::try_from(r.report_count)?,
+                    meal_id: r.meal_id,
+                    reporting_users: None,
+                },
+                visible: r.visible,
+            })
+        })
+        .collect()
+    }
+
+    async fn get_ratings_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<MealRating>> {
+        sqlx::query!(
+            "SELECT food_id, rating FROM meal_rating WHERE user_id = $1 ORDER BY food_id LIMIT $2 OFFSET $3",
+            client_id,
+            page.limit.map(i64::from),
+            i64::from(page.offset)
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| {
+            Ok(MealRating {
+                meal_id: r.food_id,
+                rating: u32::try_from(r.rating)?,
+            })
+        })
+        .collect()
+    }
+
+    async fn get_image_votes_of_user(&self, client_id: Uuid) -> Result<Vec<ImageVote>> {
+        let votes = sqlx::query!(
+            "SELECT image_id, rating FROM image_rating WHERE user_id = $1 ORDER BY image_id",
+            client_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|r| ImageVote {
+            image_id: r.image_id,
+            upvote: r.rating > 0,
+        })
+        .collect();
+        Ok(votes)
+    }
+
+    async fn get_reports_of_user(
+        &self,
+        client_id: Uuid,
+        page: Pagination,
+    ) -> Result<Vec<ImageReport>> {
+        let reports = sqlx::query_as!(
+            ImageReport,
+            r#"
+            SELECT image_id, reason as "reason: ReportReason", report_date
+            FROM image_report
+            WHERE user_id = $1
+            ORDER BY report_date DESC, image_id
+            LIMIT $2 OFFSET $3
+            "#,
+            client_id,
+            page.limit.map(i64::from),
+            i64::from(page.offset)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(reports)
+    }
 }
 
 #[cfg(test)]
@@ -273,6 +429,57 @@ mod tests {
         assert_eq!(canteen[2].name, "bad canteen");
     }
 
+    #[sqlx::test(fixtures("canteen", "closure"))]
+    async fn test_get_closures(pool: PgPool) {
+        let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
+        let canteen_id = Uuid::parse_str("10728cc4-1e07-4e18-a9d9-ca45b9782413").unwrap();
+        let christmas = NaiveDate::from_ymd_opt(2023, 12, 25).unwrap();
+
+        let closures = request
+            .get_closures(canteen_id, christmas, christmas + Duration::days(7))
+            .await
+            .unwrap();
+        assert_eq!(closures.len(), 2);
+        assert_eq!(
+            closures[0],
+            Closure {
+                canteen_id,
+                date: christmas,
+                reason: ClosureReason::Holiday,
+                note: Some("Christmas".into()),
+            }
+        );
+        assert_eq!(closures[1].reason, ClosureReason::Unspecified);
+
+        let closures = request
+            .get_closures(
+                canteen_id,
+                christmas.succ_opt().unwrap(),
+                christmas.succ_opt().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(closures.is_empty());
+    }
+
+    #[sqlx::test(fixtures("canteen", "line", "meal", "food_plan"))]
+    async fn test_has_meal_plan(pool: PgPool) {
+        let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
+        let today = Local::now().date_naive();
+        let canteen_id = Uuid::parse_str("10728cc4-1e07-4e18-a9d9-ca45b9782413").unwrap();
+        let other_canteen_id = Uuid::parse_str("8f10c56d-da9b-4f62-b4c1-16feb0f98c67").unwrap();
+
+        assert!(request.has_meal_plan(canteen_id, today).await.unwrap());
+        assert!(!request
+            .has_meal_plan(canteen_id, today + Duration::days(1))
+            .await
+            .unwrap());
+        assert!(!request
+            .has_meal_plan(other_canteen_id, today)
+            .await
+            .unwrap());
+    }
+
     #[sqlx::test(fixtures("canteen", "line"))]
     async fn test_get_line(pool: PgPool) {
         let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
@@ -580,6 +787,29 @@ mod tests {
         ]
     }
 
+    #[sqlx::test(fixtures("meal", "unknown_code"))]
+    async fn test_get_unknown_codes(pool: PgPool) {
+        let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
+        let food_ids = [
+            "f7337122-b018-48ad-b420-6202dc3cb4ff",
+            "73cf367b-a536-4b49-ad0c-cb984caa9a08",
+            "25cb8c50-75a4-48a2-b4cf-8ab2566d8bec",
+        ];
+        let mut codes = Vec::new();
+        for food_id in food_ids {
+            let food_id = Uuid::parse_str(food_id).unwrap();
+            codes.push(request.get_unknown_codes(food_id).await.unwrap());
+        }
+        assert_eq!(
+            codes,
+            vec![
+                vec!["GEL".to_string()],
+                vec![],
+                vec!["GEL".to_string(), "Xy".to_string()],
+            ]
+        );
+    }
+
     #[sqlx::test(fixtures("meal", "environment_info"))]
     async fn test_get_environment_info(pool: PgPool) {
         let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
@@ -658,6 +888,126 @@ mod tests {
         assert_eq!(nutrition_data, provide_dummy_nutrition_data());
     }
 
+    #[sqlx::test(fixtures("canteen"))]
+    async fn test_get_last_meal_plan_update(pool: PgPool) {
+        let request = PersistentRequestData::new(pool.clone(), MAX_WEEKS_DATA);
+        let before = request.get_last_meal_plan_update().await.unwrap();
+        assert_eq!(before, request.get_last_meal_plan_update().await.unwrap());
+
+        sqlx::query!(
+            "INSERT INTO canteen_closure(canteen_id, closed_date) SELECT canteen_id, CURRENT_DATE FROM canteen LIMIT 1"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let after = request.get_last_meal_plan_update().await.unwrap();
+        assert!(after > before, "{after} is not after {before}");
+    }
+
+    #[sqlx::test(fixtures("meal", "image", "rating"))]
+    async fn test_get_data_of_user(pool: PgPool) {
+        let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
+        let client_id = Uuid::parse_str("c51d2d81-3547-4f07-af58-ed613c6ece67").unwrap();
+
+        let images = request
+            .get_images_of_user(client_id, Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(images.len(), 3);
+        let hidden_id = Uuid::parse_str("68153ab6-ebbf-48f4-b8dd-a9b2a19a5221").unwrap();
+        assert!(images
+            .iter()
+            .all(|i| i.visible == (i.image.id != hidden_id)));
+
+        let ratings = request
+            .get_ratings_of_user(client_id, Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(ratings.len(), 2);
+        assert!(ratings.contains(&MealRating {
+            meal_id: Uuid::parse_str("f7337122-b018-48ad-b420-6202dc3cb4ff").unwrap(),
+            rating: 5
+        }));
+
+        let votes = request.get_image_votes_of_user(client_id).await.unwrap();
+        assert_eq!(votes.len(), 2);
+        assert!(votes.iter().all(|v| v.upvote));
+
+        let reports = request
+            .get_reports_of_user(client_id, Pagination::default())
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].reason, ReportReason::Advert);
+        assert_eq!(
+            reports[0].image_id,
+            Uuid::parse_str("ea8cce48-a3c7-4f8e-a222-5f3891c13804").unwrap()
+        );
+
+        assert!(request
+            .get_images_of_user(WRONG_UUID, Pagination::default())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(request
+            .get_reports_of_user(WRONG_UUID, Pagination::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test(fixtures("meal", "image", "rating"))]
+    async fn test_get_data_of_user_paginated(pool: PgPool) {
+        let request = PersistentRequestData::new(pool, MAX_WEEKS_DATA);
+        let client_id = Uuid::parse_str("c51d2d81-3547-4f07-af58-ed613c6ece67").unwrap();
+        let all_images = request
+            .get_images_of_user(client_id, Pagination::default())
+            .await
+            .unwrap();
+
+        let first_page = Pagination {
+            limit: Some(2),
+            offset: 0,
+        };
+        let second_page = Pagination {
+            limit: Some(2),
+            offset: 2,
+        };
+        let images = request
+            .get_images_of_user(client_id, first_page)
+            .await
+            .unwrap();
+        assert_eq!(images, all_images[..2]);
+        let images = request
+            .get_images_of_user(client_id, second_page)
+            .await
+            .unwrap();
+        assert_eq!(images, all_images[2..]);
+
+        let ratings = request
+            .get_ratings_of_user(client_id, second_page)
+            .await
+            .unwrap();
+        assert!(ratings.is_empty());
+        let ratings = request
+            .get_ratings_of_user(
+                client_id,
+                Pagination {
+                    limit: Some(1),
+                    offset: 1,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ratings.len(), 1);
+
+        let reports = request
+            .get_reports_of_user(client_id, first_page)
+            .await
+            .unwrap();
+        assert_eq!(reports.len(), 1);
+    }
+
     fn provide_dummy_nutrition_data() -> Vec<Option<NutritionData>> {
         vec![
             Some(NutritionData {
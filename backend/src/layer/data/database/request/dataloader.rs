@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use async_graphql::dataloader::Loader;
 use futures::{StreamExt, TryStreamExt};
 use sqlx::{Pool, Postgres};
+use tracing::instrument;
 use uuid::Uuid;
 
 use crate::interface::persistent_data::model::{EnvironmentInfo, Image, Side};
@@ -20,6 +21,7 @@ pub(super) struct CanteenDataloader(pub Pool<Postgres>);
 impl Loader<Uuid> for CanteenDataloader {
     type Value = Canteen;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -41,6 +43,7 @@ impl Loader<Uuid> for CanteenDataloader {
 impl Loader<()> for CanteenDataloader {
     type Value = Vec<Canteen>;
     type Error = DataError;
+    #[instrument(skip_all)]
     async fn load(
         &self,
         _keys: &[()],
@@ -59,6 +62,7 @@ pub(super) struct LineDataLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for LineDataLoader {
     type Value = Line;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -82,6 +86,7 @@ pub(super) struct CanteenLinesLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for CanteenLinesLoader {
     type Value = Vec<Line>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -107,6 +112,7 @@ pub(super) struct MealKey {
 impl Loader<MealKey> for MealDataLoader {
     type Value = Meal;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[MealKey],
@@ -161,6 +167,7 @@ pub(super) struct LineDishKey {
 impl Loader<LineDishKey> for ManyMealsDataLoader {
     type Value = Vec<Meal>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[LineDishKey],
@@ -210,6 +217,7 @@ pub(super) struct SidesLoader(pub Pool<Postgres>);
 impl Loader<LineDishKey> for SidesLoader {
     type Value = Vec<Side>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[LineDishKey],
@@ -250,6 +258,7 @@ pub(super) struct ImageLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for ImageLoader {
     type Value = Vec<Image>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -295,6 +304,7 @@ pub(super) struct RatingKey {
 impl Loader<RatingKey> for RatingLoader {
     type Value = u32;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[RatingKey],
@@ -324,6 +334,7 @@ pub(super) struct UpvoteKey {
 impl Loader<UpvoteKey> for ImageVoteLoader {
     type Value = ();
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[UpvoteKey],
@@ -351,6 +362,7 @@ pub(super) struct DownvoteKey {
 impl Loader<DownvoteKey> for ImageVoteLoader {
     type Value = ();
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[DownvoteKey],
@@ -375,6 +387,7 @@ pub(super) struct AdditiveLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for AdditiveLoader {
     type Value = Vec<Additive>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -397,6 +410,7 @@ pub(super) struct AllergenLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for AllergenLoader {
     type Value = Vec<Allergen>;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -414,10 +428,34 @@ impl Loader<Uuid> for AllergenLoader {
         }).await.map_err(Into::into)
     }
 }
+pub(super) struct UnknownCodeLoader(pub Pool<Postgres>);
+impl Loader<Uuid> for UnknownCodeLoader {
+    type Value = Vec<String>;
+    type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
+    async fn load(
+        &self,
+        keys: &[Uuid],
+    ) -> std::result::Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        sqlx::query!(
+            "SELECT food_id, code FROM food_unknown_code WHERE food_id = ANY ($1) ORDER BY code",
+            &keys
+        )
+        .fetch(&self.0)
+        .try_fold(HashMap::<_, Vec<_>>::new(), |mut h, c| async move {
+            h.entry(c.food_id).or_default().push(c.code);
+            Ok(h)
+        })
+        .await
+        .map_err(Into::into)
+    }
+}
+
 pub(super) struct NutritionDataLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for NutritionDataLoader {
     type Value = NutritionData;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
@@ -444,6 +482,7 @@ pub(super) struct EnvironmentInfoLoader(pub Pool<Postgres>);
 impl Loader<Uuid> for EnvironmentInfoLoader {
     type Value = EnvironmentInfo;
     type Error = DataError;
+    #[instrument(skip_all, fields(keys = keys.len()))]
     async fn load(
         &self,
         keys: &[Uuid],
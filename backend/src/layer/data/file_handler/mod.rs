@@ -61,6 +61,24 @@ impl ImageStorage for FileHandler {
 
         Ok(())
     }
+
+    async fn list_images(&self) -> Result<Vec<Uuid>> {
+        let mut ids = Vec::new();
+        let mut entries = fs::read_dir(&self.image_path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == IMAGE_EXTENSION) {
+                if let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| Uuid::parse_str(stem).ok())
+                {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
 }
 
 #[cfg(test)]
@@ -131,4 +149,26 @@ mod tests {
         file_handler.delete_image(uuid).await.unwrap();
         assert!(!fs::try_exists(&image_path).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_list_images() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+
+        let file_handler = FileHandler::new(FileHandlerInfo {
+            image_dir: path.to_path_buf(),
+        });
+
+        let uuid = Uuid::new_v4();
+        let mut image_path = path.to_path_buf();
+        image_path.push(uuid.to_string());
+        image_path.set_extension(IMAGE_EXTENSION);
+        fs::write(&image_path, []).await.unwrap();
+        fs::write(path.join("other.jpg"), []).await.unwrap();
+        fs::write(path.join(format!("{}.png", Uuid::new_v4())), [])
+            .await
+            .unwrap();
+
+        assert_eq!(file_handler.list_images().await.unwrap(), vec![uuid]);
+    }
 }
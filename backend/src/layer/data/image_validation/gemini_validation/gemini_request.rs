@@ -1,6 +1,7 @@
 use crate::interface::image_validation::ImageValidationError::JsonDecodeFailed;
 use crate::interface::image_validation::{parse_request, Result};
 use crate::layer::data::image_validation::gemini_validation::json_request::GeminiResponseJson;
+use tracing::instrument;
 
 // Consider: This is the beta version. Change if depreciated!
 const API_REST_URL: &str =
@@ -46,6 +47,7 @@ impl GeminiRequest {
     /// See [`crate::interface::image_validation::ImageValidationError`] for more info about the errors.
     /// # Return
     /// The assessment of the Gemini api as string.
+    #[instrument(skip_all)]
     pub async fn encoded_image_validation(&self, b64_image: &str) -> Result<String> {
         let json_resp = self.request_api(b64_image).await?.candidates.pop();
         match json_resp {
@@ -11,6 +11,7 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use image::ImageFormat;
 use std::io::Cursor;
+use tracing::instrument;
 
 /// The [`GoogleApiHandler`] struct is used to manage tasks
 /// of the [`crate::layer::data::image_validation`] component.
@@ -72,6 +73,7 @@ impl GoogleApiHandler {
 
 #[async_trait]
 impl ImageValidation for GoogleApiHandler {
+    #[instrument(skip_all)]
     async fn validate_image(&self, image: &ImageResource) -> Result<()> {
         let b64_image = image_to_base64(image)?;
 
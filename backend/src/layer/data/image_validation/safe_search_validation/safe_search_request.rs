@@ -5,6 +5,7 @@ use crate::layer::data::image_validation::safe_search_validation::json_request::
 };
 use google_jwt_auth::usage::Usage::CloudVision;
 use google_jwt_auth::AuthConfig;
+use tracing::instrument;
 
 const API_REST_URL: &str = "https://vision.googleapis.com/v1/images:annotate";
 const PROJECT_ID_HEADER: &str = "x-goog-user-project";
@@ -51,6 +52,7 @@ impl SafeSearchRequest {
     /// See [`crate::interface::image_validation::ImageValidationError`] for more info about the errors.
     /// # Return
     /// The mentioned json ([`SafeSearchJson`]), containing the evaluated values.
+    #[instrument(skip_all)]
     pub async fn encoded_image_validation(&self, b64_image: &str) -> Result<SafeSearchJson> {
         let token = self.auth_config.generate_auth_token(TOKEN_LIFETIME).await?;
         let json_resp = self.request_api(b64_image, token).await?.responses.pop();
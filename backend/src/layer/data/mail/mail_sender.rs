@@ -4,12 +4,15 @@ use async_trait::async_trait;
 use minijinja::{context, Environment, Value};
 
 use crate::{
-    interface::admin_notification::{AdminNotification, ImageReportInfo, Result},
+    interface::admin_notification::{
+        AdminNotification, DataQualityReport, ImageReportInfo, JobFailureInfo, Result,
+        UnknownCodeInfo,
+    },
     layer::data::mail::mail_info::MailInfo,
     util::{self, Uuid},
 };
 use lettre::{
-    message::{Mailbox, MaybeString, SinglePart},
+    message::{Mailbox, MaybeString, MessageBuilder, SinglePart},
     transport::smtp::authentication::Credentials,
     Address, Message, SmtpTransport, Transport,
 };
@@ -18,6 +21,9 @@ use tracing::{error, info};
 
 const REPORT_TEMPLATE: &str = include_str!("./template/template.html");
 const NOTIFY_TEMPLATE: &str = include_str!("./template/notification.html");
+const DATA_QUALITY_TEMPLATE: &str = include_str!("./template/data_quality.html");
+const UNKNOWN_CODES_TEMPLATE: &str = include_str!("./template/unknown_codes.html");
+const JOB_FAILURE_TEMPLATE: &str = include_str!("./template/job_failure.html");
 const REPORT_CSS: &str = include_str!("./template/output.css");
 const SENDER_NAME: &str = "MensaKa";
 const RECEIVER_NAME: &str = "Administrator";
@@ -50,6 +56,41 @@ impl AdminNotification for MailSender {
 
         self.send_message(subject, image_id, body)
     }
+
+    async fn notify_admin_data_quality_report(&self, report: &DataQualityReport) -> Result<()> {
+        let subject = format!(
+            "🔎 Data quality report {}: {} issues",
+            report.report_date,
+            report.issues.len()
+        );
+
+        let body = Self::get_data_quality_body(report);
+
+        self.send_html(Message::builder(), subject, body)?;
+        info!(
+            "Sent data quality report with {} issues to administrators",
+            report.issues.len()
+        );
+        Ok(())
+    }
+
+    async fn notify_admin_unknown_codes(&self, codes: Vec<UnknownCodeInfo>) {
+        if let Err(error) = self.try_notify_admin_unknown_codes(&codes) {
+            error!(
+                ?codes,
+                self.config.admin_email_address, "Error notifying administrator: {error}"
+            );
+        }
+    }
+
+    async fn notify_admin_job_failure(&self, info: JobFailureInfo) {
+        if let Err(error) = self.try_notify_admin_job_failure(&info) {
+            error!(
+                info.job_name,
+                self.config.admin_email_address, "Error notifying administrator: {error}"
+            );
+        }
+    }
 }
 
 impl MailSender {
@@ -95,6 +136,45 @@ impl MailSender {
         Ok(())
     }
 
+    fn try_notify_admin_unknown_codes(&self, codes: &[UnknownCodeInfo]) -> Result<()> {
+        let subject = format!(
+            "❓ {} unknown allergen or additive codes: {}",
+            codes.len(),
+            codes
+                .iter()
+                .map(|c| c.code.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let body = Self::get_unknown_codes_body(codes);
+
+        self.send_html(Message::builder(), subject, body)?;
+        info!(
+            ?codes,
+            "Notified administrators about {} unknown allergen or additive codes",
+            codes.len()
+        );
+        Ok(())
+    }
+
+    fn try_notify_admin_job_failure(&self, info: &JobFailureInfo) -> Result<()> {
+        let subject = format!(
+            "⚠️ Job {} failed {} times in a row",
+            info.job_name, info.consecutive_failures
+        );
+
+        let body = Self::get_job_failure_body(info);
+
+        self.send_html(Message::builder(), subject, body)?;
+        info!(
+            info.job_name,
+            "Notified administrators about {} consecutive failures of job",
+            info.consecutive_failures
+        );
+        Ok(())
+    }
+
     fn get_sender(&self) -> Result<Mailbox> {
         let address = self.config.username.parse::<Address>()?;
         Ok(Mailbox::new(Some(SENDER_NAME.to_string()), address))
@@ -136,16 +216,69 @@ impl MailSender {
             .expect("all arguments provided at compile time")
     }
 
+    fn get_data_quality_body(report: &DataQualityReport) -> String {
+        let env = Environment::new();
+        let template = env
+            .template_from_str(DATA_QUALITY_TEMPLATE)
+            .expect("template always preset");
+
+        template
+            .render(context!(
+                css => REPORT_CSS,
+                ..Value::from_serialize(report),
+            ))
+            .expect("all arguments provided at compile time")
+    }
+
+    fn get_unknown_codes_body(codes: &[UnknownCodeInfo]) -> String {
+        let env = Environment::new();
+        let template = env
+            .template_from_str(UNKNOWN_CODES_TEMPLATE)
+            .expect("template always preset");
+
+        template
+            .render(context!(
+                css => REPORT_CSS,
+                codes => codes,
+            ))
+            .expect("all arguments provided at compile time")
+    }
+
+    fn get_job_failure_body(info: &JobFailureInfo) -> String {
+        let env = Environment::new();
+        let template = env
+            .template_from_str(JOB_FAILURE_TEMPLATE)
+            .expect("template always preset");
+
+        template
+            .render(context!(
+                css => REPORT_CSS,
+                info => info,
+                failed_at => info.failed_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                jobs_url => util::local_to_global_url("/admin/jobs"),
+            ))
+            .expect("all arguments provided at compile time")
+    }
+
     fn get_references_tag(image_id: Uuid) -> String {
         format!("<{image_id}@image-reports.mensa-ka.de>")
     }
 
     fn send_message(&self, subject: impl Into<String>, image_id: Uuid, body: String) -> Result<()> {
-        let message = Message::builder()
+        let builder = Message::builder().references(Self::get_references_tag(image_id));
+        self.send_html(builder, subject, body)
+    }
+
+    fn send_html(
+        &self,
+        builder: MessageBuilder,
+        subject: impl Into<String>,
+        body: String,
+    ) -> Result<()> {
+        let message = builder
             .from(self.get_sender()?)
             .to(self.get_receiver()?)
             .subject(subject)
-            .references(Self::get_references_tag(image_id))
             .singlepart(SinglePart::html(MaybeString::String(body)))?;
         self.mailer.send(&message)?;
         Ok(())
@@ -157,7 +290,10 @@ mod test {
     #![allow(clippy::unwrap_used)]
     use super::REPORT_CSS;
     use crate::{
-        interface::admin_notification::{AdminNotification, ImageReportInfo},
+        interface::admin_notification::{
+            AdminNotification, DataQualityIssue, DataQualityIssueKind, DataQualityReport,
+            ImageReportInfo, JobFailureInfo, UnknownCodeInfo,
+        },
         layer::data::mail::mail_info::MailInfo,
         layer::data::mail::mail_sender::MailSender,
         util::Uuid,
@@ -304,6 +440,121 @@ mod test {
         assert!(sender.notify_admin_image_verified(id).await.is_ok());
     }
 
+    #[test]
+    fn test_get_data_quality_body() {
+        let report = get_data_quality_report();
+        let body = MailSender::get_data_quality_body(&report);
+        assert!(
+            !body.contains("{{ ") && !body.contains(" }}"),
+            "the template must not contain any formatting"
+        );
+        let issue = &report.issues[0];
+        assert!(body.contains(&issue.food_name));
+        assert!(body.contains(&issue.food_id.to_string()));
+        assert!(body.contains(&issue.details));
+        assert!(body.contains("UNKNOWN_FOOD_TYPE"));
+        assert!(body.contains(REPORT_CSS));
+    }
+
+    #[tokio::test]
+    async fn test_notify_admin_data_quality_report() {
+        let mail_info = get_mail_info().unwrap();
+        let sender = MailSender::new(mail_info).unwrap();
+        assert!(sender.mailer.test_connection().unwrap());
+
+        let report = get_data_quality_report();
+
+        assert!(sender
+            .notify_admin_data_quality_report(&report)
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn test_get_unknown_codes_body() {
+        let codes = get_unknown_codes();
+        let body = MailSender::get_unknown_codes_body(&codes);
+        assert!(
+            !body.contains("{{ ") && !body.contains(" }}"),
+            "the template must not contain any formatting"
+        );
+        assert!(body.contains("GEL"));
+        assert!(body.contains("Happy Meal, Sad Meal"));
+        assert!(body.contains(REPORT_CSS));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_notify_admin_unknown_codes() {
+        let mail_info = get_mail_info().unwrap();
+        let sender = MailSender::new(mail_info).unwrap();
+        assert!(sender.mailer.test_connection().unwrap());
+
+        sender.notify_admin_unknown_codes(get_unknown_codes()).await;
+        assert!(!logs_contain("Error notifying administrator"));
+    }
+
+    #[test]
+    fn test_get_job_failure_body() {
+        let info = get_job_failure_info();
+        let body = MailSender::get_job_failure_body(&info);
+        assert!(
+            !body.contains("{{ ") && !body.contains(" }}"),
+            "the template must not contain any formatting"
+        );
+        assert!(body.contains(&info.job_name));
+        assert!(body.contains(&info.last_error));
+        assert!(body.contains("/admin/jobs"));
+        assert!(body.contains(REPORT_CSS));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_notify_admin_job_failure() {
+        let mail_info = get_mail_info().unwrap();
+        let sender = MailSender::new(mail_info).unwrap();
+        assert!(sender.mailer.test_connection().unwrap());
+
+        sender
+            .notify_admin_job_failure(get_job_failure_info())
+            .await;
+        assert!(!logs_contain("Error notifying administrator"));
+    }
+
+    fn get_job_failure_info() -> JobFailureInfo {
+        JobFailureInfo {
+            job_name: "full_parsing".into(),
+            consecutive_failures: 3,
+            last_error: "could not reach meal plan website".into(),
+            failed_at: Local::now(),
+        }
+    }
+
+    fn get_unknown_codes() -> Vec<UnknownCodeInfo> {
+        vec![UnknownCodeInfo {
+            code: "GEL".into(),
+            dish_names: vec!["Happy Meal".into(), "Sad Meal".into()],
+        }]
+    }
+
+    fn get_data_quality_report() -> DataQualityReport {
+        let today = Local::now().date_naive();
+        DataQualityReport {
+            report_date: today,
+            from_date: today,
+            checked_food_count: 42,
+            issues: vec![DataQualityIssue {
+                kind: DataQualityIssueKind::UnknownFoodType,
+                food_id: Uuid::from_u128(567),
+                food_name: "Happy Meal".into(),
+                date: today,
+                canteen_name: "Mensa am Adenauerring".into(),
+                line_name: "Linie 1".into(),
+                details: "food type could not be determined".into(),
+            }],
+        }
+    }
+
     fn get_report_info() -> ImageReportInfo {
         ImageReportInfo {
             reason: crate::util::ReportReason::Advert,
@@ -1,6 +1,6 @@
 /** @type {import('tailwindcss').Config} */
 module.exports = {
-  content: ["template.html", "notification.html"],
+  content: ["template.html", "notification.html", "data_quality.html", "unknown_codes.html", "job_failure.html"],
   theme: {
     colors: {
       'dark-grey': '#1E1E1E',
@@ -0,0 +1,185 @@
+//! This component stores parsed meal plans in json files and provides them again as [`MealplanParser`].
+//!
+//! This allows transferring meal plans between instances or inserting meal plans without access to the canteen's website.
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::{
+    interface::mensa_parser::{model::ParseCanteen, MealplanParser, ParseError, Result},
+    util::Date,
+};
+
+/// Meal plans of all canteens for a single day as stored in an archive.
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveDay {
+    date: Date,
+    canteens: Vec<ParseCanteen>,
+}
+
+/// Class for reading and writing meal plan archives, json files containing parsed meal plans grouped by day.
+#[derive(Debug)]
+pub struct MealplanArchive {
+    path: PathBuf,
+}
+
+impl MealplanArchive {
+    /// Creates a new archive located at the given path. The file is only accessed on reading or writing.
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Writes the given meal plans to the archive, replacing its previous content.
+    /// # Errors
+    /// when the meal plans could not be serialized or the file could not be written.
+    pub async fn write(&self, mealplans: Vec<(Date, Vec<ParseCanteen>)>) -> Result<()> {
+        let mut days = mealplans
+            .into_iter()
+            .map(|(date, canteens)| ArchiveDay { date, canteens })
+            .collect::<Vec<_>>();
+        days.sort_by_key(|day| day.date);
+
+        let json = serde_json::to_vec_pretty(&days)
+            .map_err(|e| ParseError::InvalidArchive(e.to_string()))?;
+        tokio::fs::write(&self.path, json)
+            .await
+            .map_err(|e| self.io_error(&e))
+    }
+
+    async fn read(&self) -> Result<Vec<ArchiveDay>> {
+        let json = tokio::fs::read(&self.path)
+            .await
+            .map_err(|e| self.io_error(&e))?;
+        serde_json::from_slice(&json).map_err(|e| {
+            ParseError::InvalidArchive(format!("{} is malformed: {e}", self.path.display()))
+        })
+    }
+
+    fn io_error(&self, error: &std::io::Error) -> ParseError {
+        ParseError::InvalidArchive(format!("could not access {}: {error}", self.path.display()))
+    }
+}
+
+#[async_trait]
+impl MealplanParser for MealplanArchive {
+    /// Reads the meal plans of the given day from the archive.
+    #[instrument(skip(self))]
+    async fn parse(&self, day: Date) -> Result<Vec<ParseCanteen>> {
+        Ok(self
+            .read()
+            .await?
+            .into_iter()
+            .filter(|archive_day| archive_day.date == day)
+            .flat_map(|archive_day| archive_day.canteens)
+            .collect())
+    }
+
+    /// Reads all meal plans stored in the archive.
+    #[instrument(skip(self))]
+    async fn parse_all(&self) -> Result<Vec<(Date, Vec<ParseCanteen>)>> {
+        Ok(self
+            .read()
+            .await?
+            .into_iter()
+            .map(|archive_day| (archive_day.date, archive_day.canteens))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use crate::{
+        interface::mensa_parser::{
+            model::{Dish, ParseCanteen, ParseLine},
+            MealplanParser, ParseError,
+        },
+        util::{Allergen, Date, FoodType, Price},
+    };
+
+    use super::MealplanArchive;
+
+    fn canteen(name: &str) -> ParseCanteen {
+        ParseCanteen {
+            name: name.into(),
+            pos: 0,
+            lines: vec![ParseLine {
+                name: "Linie 1".into(),
+                pos: 0,
+                dishes: vec![Dish {
+                    name: "Pasta".into(),
+                    price: Price {
+                        price_student: 300,
+                        price_employee: 400,
+                        price_guest: 500,
+                        price_pupil: 350,
+                    },
+                    allergens: vec![Allergen::We],
+                    additives: vec![],
+                    unknown_codes: vec!["XY".into()],
+                    food_type: FoodType::Vegan,
+                    env_score: None,
+                    nutrition_data: None,
+                }],
+            }],
+            closure: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = MealplanArchive::new(dir.path().join("mealplans.json"));
+        let first = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let second = Date::from_ymd_opt(2024, 1, 3).unwrap();
+
+        archive
+            .write(vec![
+                (second, vec![canteen("Mensa B")]),
+                (first, vec![canteen("Mensa A")]),
+            ])
+            .await
+            .unwrap();
+
+        let all = archive.parse_all().await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].0, first);
+        assert_eq!(all[1].1[0].name, "Mensa B");
+
+        let dish = &all[0].1[0].lines[0].dishes[0];
+        assert_eq!(dish.price.price_pupil, 350);
+        assert_eq!(dish.allergens, vec![Allergen::We]);
+        assert_eq!(dish.food_type, FoodType::Vegan);
+        assert_eq!(dish.unknown_codes, vec!["XY".to_string()]);
+
+        let day = archive.parse(second).await.unwrap();
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].name, "Mensa B");
+        assert!(archive
+            .parse(Date::from_ymd_opt(2024, 1, 4).unwrap())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mealplans.json");
+
+        let archive = MealplanArchive::new(&path);
+        assert!(matches!(
+            archive.parse_all().await,
+            Err(ParseError::InvalidArchive(_))
+        ));
+
+        std::fs::write(&path, "{\"not\": \"an archive\"}").unwrap();
+        assert!(matches!(
+            archive.parse_all().await,
+            Err(ParseError::InvalidArchive(_))
+        ));
+    }
+}
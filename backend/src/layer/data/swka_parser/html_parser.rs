@@ -88,10 +88,10 @@
 use std::sync::LazyLock;
 
 use crate::interface::mensa_parser::{
-    model::{Dish, ParseCanteen, ParseEnvironmentInfo, ParseLine},
+    model::{Dish, ParseCanteen, ParseClosure, ParseEnvironmentInfo, ParseLine},
     ParseError,
 };
-use crate::util::{Additive, Allergen, Date, FoodType, NutritionData, Price};
+use crate::util::{Additive, Allergen, ClosureReason, Date, FoodType, NutritionData, Price};
 use regex::Regex;
 use scraper::element_ref::Text;
 use scraper::{ElementRef, Html, Selector};
@@ -172,7 +172,21 @@ const NUMBER_OF_FOOD_TYPES: usize = 8;
 const PRICE_TYPE_COUNT: usize = 4;
 const NUMBER_OF_MILLILITRES_PER_LITRE: u32 = 1000;
 
+/// Name of the placeholder dish listed on closed lines. It may be followed by a note like "GESCHLOSSEN - Feiertag".
 const LINE_CLOSED_MEAL_NAME: &str = "GESCHLOSSEN";
+/// Characters separating the closure note from [`LINE_CLOSED_MEAL_NAME`]. Notes may also be enclosed in parentheses.
+const CLOSURE_NOTE_SEPARATORS: &[char] = &['-', '–', ':', ','];
+/// Lowercase keywords in closure notes indicating the reason of the closure, checked in order.
+const CLOSURE_REASON_KEYWORDS: [(&str, ClosureReason); 8] = [
+    ("feiertag", ClosureReason::Holiday),
+    ("holiday", ClosureReason::Holiday),
+    ("semesterferien", ClosureReason::SemesterBreak),
+    ("vorlesungsfrei", ClosureReason::SemesterBreak),
+    ("betriebsferien", ClosureReason::SemesterBreak),
+    ("semester break", ClosureReason::SemesterBreak),
+    ("veranstaltung", ClosureReason::Event),
+    ("event", ClosureReason::Event),
+];
 
 const RATING_NAME: &str = "data-rating";
 const MAX_RATING_NAME: &str = "data-numstars";
@@ -283,9 +297,57 @@ impl HTMLParser {
             name: Self::get_canteen_name(root_node)?,
             lines: Self::get_lines(day_node),
             pos: position,
+            closure: Self::get_closure(day_node),
         })
     }
 
+    /// A canteen is closed at a day if all of its lines list the closed placeholder instead of dishes.
+    /// The first note following a placeholder is used to determine the reason.
+    fn get_closure(day_node: &ElementRef) -> Option<ParseClosure> {
+        let mut closed = false;
+        let mut note = None;
+        for dish_name_node in day_node.select(&DISH_NAME_NODE_CLASS_SELECTOR) {
+            let dish_name =
+                Self::remove_multiple_whitespaces(&dish_name_node.text().collect::<String>());
+            // any regular dish means the canteen is open
+            let closure_note = Self::get_closure_note(&dish_name)?;
+            closed = true;
+            if note.is_none() && !closure_note.is_empty() {
+                note = Some(closure_note.to_owned());
+            }
+        }
+        closed.then(|| ParseClosure {
+            reason: note
+                .as_deref()
+                .map_or(ClosureReason::Unspecified, Self::get_closure_reason),
+            note,
+        })
+    }
+
+    /// Returns the note following the closed placeholder, if the given dish name is one.
+    fn get_closure_note(dish_name: &str) -> Option<&str> {
+        let (prefix, note) = dish_name.split_at_checked(LINE_CLOSED_MEAL_NAME.len())?;
+        if !prefix.eq_ignore_ascii_case(LINE_CLOSED_MEAL_NAME) {
+            return None;
+        }
+        let note = note
+            .trim_start_matches(|c: char| c.is_whitespace() || CLOSURE_NOTE_SEPARATORS.contains(&c))
+            .trim_end();
+        Some(
+            note.strip_prefix('(')
+                .and_then(|note| note.strip_suffix(')'))
+                .unwrap_or(note),
+        )
+    }
+
+    fn get_closure_reason(note: &str) -> ClosureReason {
+        let note = note.to_lowercase();
+        CLOSURE_REASON_KEYWORDS
+            .iter()
+            .find(|(keyword, _)| note.contains(keyword))
+            .map_or(ClosureReason::Other, |(_, reason)| *reason)
+    }
+
     fn get_canteen_name(root_node: &ElementRef) -> Option<String> {
         let canteen_node = root_node.select(&CANTEEN_NAME_NODE_CLASS_SELECTOR).next()?;
         Some(canteen_node.inner_html())
@@ -336,12 +398,17 @@ impl HTMLParser {
 
     fn get_dish(dish_node: &ElementRef) -> Option<Dish> {
         let name = Self::get_dish_name(dish_node)?;
+        let (allergens, unknown_allergens) =
+            Self::get_dish_allergens(dish_node).unwrap_or_default();
+        let (additives, unknown_additives) =
+            Self::get_dish_additives(dish_node).unwrap_or_default();
         Some(Dish {
             food_type: Self::get_food_type(dish_node).unwrap_or(FoodType::Unknown),
             name,
             price: Self::get_dish_price(dish_node),
-            allergens: Self::get_dish_allergens(dish_node).unwrap_or_default(),
-            additives: Self::get_dish_additives(dish_node).unwrap_or_default(),
+            allergens,
+            additives,
+            unknown_codes: [unknown_allergens, unknown_additives].concat(),
             env_score: Self::get_dish_env_score(dish_node),
             nutrition_data: Self::get_dish_nutrition_data(dish_node),
         })
@@ -351,7 +418,7 @@ impl HTMLParser {
         let dish_name_node = dish_node.select(&DISH_NAME_NODE_CLASS_SELECTOR).next()?;
         let dish_name =
             Self::remove_multiple_whitespaces(&dish_name_node.text().collect::<String>());
-        if dish_name == LINE_CLOSED_MEAL_NAME {
+        if Self::get_closure_note(&dish_name).is_some() {
             None
         } else {
             Some(dish_name)
@@ -389,32 +456,44 @@ impl HTMLParser {
         format!("{euros}{cents}").parse().ok()
     }
 
-    fn get_dish_allergens(dish_node: &ElementRef) -> Option<Vec<Allergen>> {
+    fn get_dish_allergens(dish_node: &ElementRef) -> Option<(Vec<Allergen>, Vec<String>)> {
         let allergens_node = dish_node.select(&DISH_INFO_NODE_CLASS_SELECTOR).next()?;
         Some(Self::get_allergens_through_regex(
             &allergens_node.inner_html(),
         ))
     }
 
-    fn get_allergens_through_regex(string: &str) -> Vec<Allergen> {
-        ALLERGEN_REGEX
-            .find_iter(string)
-            .filter_map(|a| Allergen::parse(a.as_str()))
-            .collect()
+    /// Returns all recognized allergens and the raw codes of all unrecognized ones.
+    fn get_allergens_through_regex(string: &str) -> (Vec<Allergen>, Vec<String>) {
+        Self::get_codes_through_regex(&ALLERGEN_REGEX, string, Allergen::parse)
     }
 
-    fn get_dish_additives(dish_node: &ElementRef) -> Option<Vec<Additive>> {
+    fn get_dish_additives(dish_node: &ElementRef) -> Option<(Vec<Additive>, Vec<String>)> {
         let additives_node = dish_node.select(&DISH_INFO_NODE_CLASS_SELECTOR).next()?;
         Some(Self::get_additives_through_regex(
             &additives_node.inner_html(),
         ))
     }
 
-    fn get_additives_through_regex(string: &str) -> Vec<Additive> {
-        ADDITIVE_REGEX
-            .find_iter(string)
-            .filter_map(|a| Additive::parse(a.as_str()))
-            .collect()
+    /// Returns all recognized additives and the raw codes of all unrecognized ones.
+    fn get_additives_through_regex(string: &str) -> (Vec<Additive>, Vec<String>) {
+        Self::get_codes_through_regex(&ADDITIVE_REGEX, string, Additive::parse)
+    }
+
+    fn get_codes_through_regex<T>(
+        regex: &Regex,
+        string: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> (Vec<T>, Vec<String>) {
+        let mut known = Vec::new();
+        let mut unknown = Vec::new();
+        for code in regex.find_iter(string).map(|c| c.as_str()) {
+            match parse(code) {
+                Some(value) => known.push(value),
+                None => unknown.push(code.to_string()),
+            }
+        }
+        (known, unknown)
     }
 
     fn get_food_type(dish_node: &ElementRef) -> Option<FoodType> {
@@ -547,8 +626,9 @@ mod tests {
     };
 
     use crate::{
-        interface::mensa_parser::model::ParseCanteen,
-        layer::data::swka_parser::html_parser::HTMLParser, util::Date,
+        interface::mensa_parser::model::{ParseCanteen, ParseClosure},
+        layer::data::swka_parser::html_parser::HTMLParser,
+        util::{Additive, Allergen, ClosureReason, Date},
     };
 
     #[tokio::test]
@@ -654,4 +734,80 @@ mod tests {
         assert!(meal.env_score.is_some());
         assert!(meal.nutrition_data.is_some());
     }
+
+    #[test]
+    fn test_unknown_codes() {
+        let (allergens, unknown) = HTMLParser::get_allergens_through_regex("[2,Ei,GEL,ML,Xy]");
+        assert_eq!(allergens, vec![Allergen::Ei, Allergen::ML]);
+        assert_eq!(unknown, vec!["GEL", "Xy"]);
+
+        let (additives, unknown) = HTMLParser::get_additives_through_regex("[2,13,Ei,ML,27]");
+        assert_eq!(
+            additives,
+            vec![Additive::PreservingAgents, Additive::PressedFish]
+        );
+        assert_eq!(unknown, vec!["13"]);
+    }
+
+    fn day_with_dishes(dish_names: &[&str]) -> String {
+        let dishes = dish_names
+            .iter()
+            .map(|name| {
+                format!(r#"<tr class="mt-0"><td><span class="bg"><b>{name}</b></span></td></tr>"#)
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        format!(
+            r#"<div class="main-content"><h1 class="mensa_fullname">Mensa</h1>
+            <ul class="canteen-day-nav"><li><a rel="2023-12-25">Mo 25.12.</a></li></ul>
+            <div class="canteen-day"><table><tr class="mensatype_rows">
+            <td class="mensatype"><div>Linie 1</div></td><td><table>{dishes}</table></td>
+            </tr></table></div></div>"#
+        )
+    }
+
+    #[test]
+    fn test_closure() {
+        let closure = |dish_names: &[&str]| {
+            HTMLParser
+                .transform(&day_with_dishes(dish_names), 0)
+                .unwrap()
+                .remove(0)
+                .1
+                .closure
+        };
+
+        assert_eq!(
+            closure(&["GESCHLOSSEN - Feiertag (Weihnachten)"]),
+            Some(ParseClosure {
+                reason: ClosureReason::Holiday,
+                note: Some("Feiertag (Weihnachten)".into()),
+            })
+        );
+        assert_eq!(
+            closure(&["Geschlossen", "GESCHLOSSEN (Betriebsferien)"]),
+            Some(ParseClosure {
+                reason: ClosureReason::SemesterBreak,
+                note: Some("Betriebsferien".into()),
+            })
+        );
+        assert_eq!(
+            closure(&["GESCHLOSSEN"]),
+            Some(ParseClosure {
+                reason: ClosureReason::Unspecified,
+                note: None,
+            })
+        );
+        assert_eq!(
+            closure(&["GESCHLOSSEN - Sommerfest"]).unwrap().reason,
+            ClosureReason::Other
+        );
+        // lines still serving dishes keep the canteen open
+        assert_eq!(closure(&["GESCHLOSSEN", "Pasta"]), None);
+        assert_eq!(closure(&[]), None);
+
+        let html = day_with_dishes(&["GESCHLOSSEN - Feiertag"]);
+        let canteen = HTMLParser.transform(&html, 0).unwrap().remove(0).1;
+        assert!(canteen.lines[0].dishes.is_empty());
+    }
 }
@@ -8,6 +8,7 @@ use crate::layer::data::swka_parser::swka_link_creator::SwKaLinkCreator;
 use crate::util::Date;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use tracing::instrument;
 
 /// Struct containing all information necessary to create a [`SwKaParseManager`].
 #[derive(Debug, Clone)]
@@ -85,6 +86,7 @@ impl MealplanParser for SwKaParseManager {
     /// The day this function looks for meal plans.<br>
     /// ## Return
     /// All [`ParseCanteen`]s containing meal plan data for the given day or an error if something in the chain above fails.
+    #[instrument(skip(self))]
     async fn parse(&self, day: Date) -> Result<Vec<ParseCanteen>, ParseError> {
         let mut map = self
             .parse_and_sort_canteens_by_days(self.link_creator.get_urls(day))
@@ -100,6 +102,7 @@ impl MealplanParser for SwKaParseManager {
     /// These objects will be returned.<br>
     /// ## Return
     /// All [`ParseCanteen`]s grouped by their [`Date`] or an error if something in the chain above fails.
+    #[instrument(skip(self))]
     async fn parse_all(&self) -> Result<Vec<(Date, Vec<ParseCanteen>)>, ParseError> {
         let map = self
             .parse_and_sort_canteens_by_days(self.link_creator.get_all_urls())
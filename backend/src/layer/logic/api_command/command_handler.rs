@@ -2,20 +2,26 @@
 
 use async_trait::async_trait;
 use chrono::Local;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::{
     interface::{
-        admin_notification::{AdminNotification, ImageReportInfo},
-        api_command::{Command, Result},
+        admin_notification::{AdminNotification, DataQualityReport, ImageReportInfo},
+        api_command::{Command, CommandError, Result},
         image_storage::ImageStorage,
         image_validation::ImageValidation,
-        persistent_data::{model::Image, CommandDataAccess},
+        persistent_data::{
+            model::{DataErasure, Image},
+            CommandDataAccess, DataError,
+        },
     },
-    util::{image_id_to_url, Date, ReportReason, Uuid},
+    util::{image_id_to_url, metrics, ClosureReason, Date, ErasureRequester, ReportReason, Uuid},
 };
 
-use super::image_preprocessing::{ImagePreprocessingInfo, ImagePreprocessor};
+use super::{
+    data_quality::check_data_quality,
+    image_preprocessing::{ImagePreprocessingInfo, ImagePreprocessor},
+};
 
 const REPORT_FACTOR: f64 = 1.0 / 35.0;
 
@@ -107,6 +113,7 @@ where
                 .add_report(image_id, client_id, reason)
                 .await?;
             let will_be_hidden = Self::will_be_hidden(&info.image);
+            metrics::record_image_report(will_be_hidden);
             if will_be_hidden {
                 self.command_data.hide_image(image_id).await?;
                 info!(image_info = ?info, "Automatically hid image {image_id} because reported {} times.", info.image.report_count);
@@ -170,7 +177,11 @@ where
             .preprocess_image(image_file, image_type)?;
 
         // verify with api
-        self.image_validation.validate_image(&image).await?;
+        if let Err(e) = self.image_validation.validate_image(&image).await {
+            metrics::record_image_rejected(e.variant_name());
+            return Err(e.into());
+        }
+        metrics::record_image_accepted();
 
         // link in database
         let image_id = self.command_data.link_image(meal_id, client_id).await?;
@@ -191,6 +202,11 @@ where
         Ok(())
     }
 
+    async fn remove_meal_rating(&self, meal_id: Uuid, client_id: Uuid) -> Result<()> {
+        self.command_data.remove_rating(meal_id, client_id).await?;
+        Ok(())
+    }
+
     async fn delete_image(&self, image_id: Uuid) -> Result<()> {
         self.command_data.delete_image(image_id).await?;
         self.image_storage.delete_image(image_id).await?;
@@ -200,6 +216,21 @@ where
         Ok(())
     }
 
+    async fn delete_own_image(&self, image_id: Uuid, client_id: Uuid) -> Result<()> {
+        let uploader = self
+            .command_data
+            .get_image_uploader(image_id)
+            .await?
+            .ok_or(DataError::NoSuchItem)?;
+        if uploader != client_id {
+            return Err(CommandError::NotUploader(image_id));
+        }
+
+        self.delete_image(image_id).await?;
+        info!(%image_id, "Deleted image on request of its uploader");
+        Ok(())
+    }
+
     async fn verify_image(&self, image_id: Uuid) -> Result<()> {
         self.command_data.verify_image(image_id).await?;
         self.admin_notification
@@ -207,6 +238,72 @@ where
             .await?;
         Ok(())
     }
+
+    async fn create_data_quality_report(&self, notify_admin: bool) -> Result<DataQualityReport> {
+        let today = Local::now().date_naive();
+        let foods = self.command_data.get_planned_foods(today).await?;
+        let report = check_data_quality(&foods, today, today);
+        info!(
+            "Created data quality report: found {} issues in {} planned foods.",
+            report.issues.len(),
+            report.checked_food_count
+        );
+
+        if notify_admin {
+            self.admin_notification
+                .notify_admin_data_quality_report(&report)
+                .await?;
+        }
+        Ok(report)
+    }
+
+    async fn set_canteen_closure(
+        &self,
+        canteen_id: Uuid,
+        date: Date,
+        reason: ClosureReason,
+        note: Option<String>,
+    ) -> Result<()> {
+        self.command_data
+            .set_closure(canteen_id, date, reason, note)
+            .await?;
+        info!(%canteen_id, %date, ?reason, "Marked canteen as closed");
+        Ok(())
+    }
+
+    async fn remove_canteen_closure(&self, canteen_id: Uuid, date: Date) -> Result<bool> {
+        let removed = self.command_data.remove_closure(canteen_id, date).await?;
+        if removed {
+            info!(%canteen_id, %date, "Removed closure of canteen");
+        }
+        Ok(removed)
+    }
+
+    async fn delete_client_data(
+        &self,
+        client_id: Uuid,
+        requester: ErasureRequester,
+    ) -> Result<DataErasure> {
+        let erasure = self
+            .command_data
+            .delete_user_data(client_id, requester)
+            .await?;
+        // the data is already gone from the database, so remaining files get removed when cleaning up orphaned images
+        for &image_id in &erasure.image_ids {
+            if let Err(e) = self.image_storage.delete_image(image_id).await {
+                warn!("Could not delete file of erased image {image_id}: {e}");
+            }
+        }
+        info!(
+            ?requester,
+            meal_ratings = erasure.meal_ratings,
+            image_votes = erasure.image_votes,
+            image_reports = erasure.image_reports,
+            images = erasure.image_ids.len(),
+            "Erased all data of a client"
+        );
+        Ok(erasure)
+    }
 }
 
 #[cfg(test)]
@@ -216,8 +313,9 @@ mod test {
 
     use chrono::Local;
 
-    use crate::interface::api_command::{Command, Result};
+    use crate::interface::api_command::{Command, CommandError, Result};
     use crate::interface::persistent_data::model::Image;
+    use crate::interface::persistent_data::DataError;
     use crate::layer::logic::api_command::mocks::{
         CommandImageStorageMock, CommandImageValidationMock, IMAGE_ID_TO_FAIL, MEAL_ID_TO_FAIL,
     };
@@ -225,7 +323,7 @@ mod test {
         command_handler::CommandHandler,
         mocks::{CommandAdminNotificationMock, CommandDatabaseMock},
     };
-    use crate::util::{ReportReason, Uuid};
+    use crate::util::{ClosureReason, ErasureRequester, ReportReason, Uuid};
 
     use super::ImagePreprocessingInfo;
 
@@ -398,6 +496,19 @@ mod test {
             .is_err());
     }
 
+    #[tokio::test]
+    async fn test_remove_meal_rating() {
+        let handler = get_handler().unwrap();
+        let client_id = Uuid::default();
+        let meal_id = Uuid::try_from("94cf40a7-ade4-4c1f-b718-89b2d418c2d0").unwrap();
+        assert!(handler.remove_meal_rating(meal_id, client_id).await.is_ok());
+
+        assert!(handler
+            .remove_meal_rating(MEAL_ID_TO_FAIL, client_id)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_delete_image() {
         let handler = get_handler().unwrap();
@@ -407,6 +518,28 @@ mod test {
         handler.delete_image(image).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_delete_own_image() {
+        let handler = get_handler().unwrap();
+        let image = Uuid::try_from("94cf40a7-ade4-4c1f-b718-89b2d418c2d0").unwrap();
+        let other_client = Uuid::try_from("b637365e-9ec5-47cf-8e39-eab3e10de4e5").unwrap();
+
+        handler
+            .delete_own_image(image, Uuid::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            handler.delete_own_image(image, other_client).await,
+            Err(CommandError::NotUploader(id)) if id == image
+        ));
+        assert!(matches!(
+            handler
+                .delete_own_image(IMAGE_ID_TO_FAIL, Uuid::default())
+                .await,
+            Err(CommandError::DataError(DataError::NoSuchItem))
+        ));
+    }
+
     #[tokio::test]
     async fn test_verify_image() {
         let handler = get_handler().unwrap();
@@ -416,6 +549,46 @@ mod test {
         handler.verify_image(image).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_create_data_quality_report() {
+        let handler = get_handler().unwrap();
+
+        let report = handler.create_data_quality_report(false).await.unwrap();
+        assert_eq!(report.checked_food_count, 1);
+        assert!(!report.issues.is_empty());
+
+        assert!(handler.create_data_quality_report(true).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_canteen_closure() {
+        let handler = get_handler().unwrap();
+        let today = Local::now().date_naive();
+        let id = Uuid::default();
+
+        assert!(handler
+            .set_canteen_closure(id, today, ClosureReason::Holiday, None)
+            .await
+            .is_ok());
+        assert!(handler.remove_canteen_closure(id, today).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_delete_client_data() {
+        let handler = get_handler().unwrap();
+        let client_id = Uuid::try_from("b637365e-9ec5-47cf-8e39-eab3e10de4e5").unwrap();
+
+        let erasure = handler
+            .delete_client_data(client_id, ErasureRequester::Client)
+            .await
+            .unwrap();
+        assert_eq!(erasure.image_ids.len(), 1);
+        assert!(handler
+            .delete_client_data(IMAGE_ID_TO_FAIL, ErasureRequester::Admin)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_arc() {
         let handler = get_handler().unwrap();
@@ -434,8 +607,21 @@ mod test {
             .await
             .unwrap();
         handler.set_meal_rating(id, 1, id).await.unwrap();
+        handler.remove_meal_rating(id, id).await.unwrap();
         handler.verify_image(id).await.unwrap();
         handler.delete_image(id).await.unwrap();
+        handler.delete_own_image(id, id).await.unwrap();
+        handler.create_data_quality_report(false).await.unwrap();
+        let today = Local::now().date_naive();
+        handler
+            .set_canteen_closure(id, today, ClosureReason::Other, Some("note".into()))
+            .await
+            .unwrap();
+        handler.remove_canteen_closure(id, today).await.unwrap();
+        handler
+            .delete_client_data(id, ErasureRequester::Client)
+            .await
+            .unwrap();
     }
 
     const fn get_handler() -> Result<
@@ -0,0 +1,281 @@
+//! This module checks the upcoming meal plans for anomalies which hint at parsing errors, see [`check_data_quality`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    interface::{
+        admin_notification::{DataQualityIssue, DataQualityIssueKind, DataQualityReport},
+        persistent_data::model::PlannedFood,
+    },
+    util::{Date, FoodType},
+};
+
+/// Factor by which a meal's student price has to deviate from the median to be considered an outlier.
+const PRICE_OUTLIER_FACTOR: f64 = 2.5;
+
+/// Checks the given planned foods for anomalies and collects them into a [`DataQualityReport`].
+///
+/// The following anomalies are detected:
+/// - foods of type [`FoodType::Unknown`]
+/// - meals without nutrition data or environmental information
+/// - meals with a price of zero
+/// - meals whose student price deviates from the median student price by more than a factor of 2.5
+/// - different foods with the same name
+#[must_use]
+pub fn check_data_quality(
+    foods: &[PlannedFood],
+    from_date: Date,
+    report_date: Date,
+) -> DataQualityReport {
+    let median_price = median_meal_price(foods);
+    let duplicates = duplicate_names(foods);
+
+    let mut issues = Vec::new();
+    for food in foods {
+        let mut add_issue = |kind, details: String| {
+            issues.push(DataQualityIssue {
+                kind,
+                food_id: food.food_id,
+                food_name: food.name.clone(),
+                date: food.date,
+                canteen_name: food.canteen_name.clone(),
+                line_name: food.line_name.clone(),
+                details,
+            });
+        };
+
+        if food.food_type == FoodType::Unknown {
+            add_issue(
+                DataQualityIssueKind::UnknownFoodType,
+                "food type could not be determined".into(),
+            );
+        }
+
+        if duplicates.contains(normalize_name(&food.name).as_str()) {
+            add_issue(
+                DataQualityIssueKind::DuplicateName,
+                "multiple foods with this name exist".into(),
+            );
+        }
+
+        if !food.is_meal {
+            continue;
+        }
+
+        if !food.has_nutrition_data {
+            add_issue(
+                DataQualityIssueKind::MissingNutritionData,
+                "no nutrition data available".into(),
+            );
+        }
+
+        if !food.has_environment_info {
+            add_issue(
+                DataQualityIssueKind::MissingEnvironmentInfo,
+                "no environmental information available".into(),
+            );
+        }
+
+        let price = &food.price;
+        if [
+            price.price_student,
+            price.price_employee,
+            price.price_guest,
+            price.price_pupil,
+        ]
+        .contains(&0)
+        {
+            add_issue(
+                DataQualityIssueKind::ZeroPrice,
+                format!("price is zero: {price:?}"),
+            );
+        } else if let Some(median) = median_price {
+            if is_outlier(price.price_student, median) {
+                add_issue(
+                    DataQualityIssueKind::PriceOutlier,
+                    format!(
+                        "student price of {} cents deviates strongly from the median of {median} cents",
+                        price.price_student
+                    ),
+                );
+            }
+        }
+    }
+
+    DataQualityReport {
+        report_date,
+        from_date,
+        checked_food_count: foods.len(),
+        issues,
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Returns all normalized names which are shared by multiple different foods.
+fn duplicate_names(foods: &[PlannedFood]) -> BTreeSet<String> {
+    let mut ids_by_name = BTreeMap::<_, BTreeSet<_>>::new();
+    for food in foods {
+        ids_by_name
+            .entry(normalize_name(&food.name))
+            .or_default()
+            .insert(food.food_id);
+    }
+
+    ids_by_name
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Median of all non-zero student prices of meals.
+fn median_meal_price(foods: &[PlannedFood]) -> Option<u32> {
+    let mut prices = foods
+        .iter()
+        .filter(|f| f.is_meal)
+        .map(|f| f.price.price_student)
+        .filter(|p| *p > 0)
+        .collect::<Vec<_>>();
+    prices.sort_unstable();
+    prices.get(prices.len() / 2).copied()
+}
+
+fn is_outlier(price: u32, median: u32) -> bool {
+    let (price, median) = (f64::from(price), f64::from(median));
+    price > median * PRICE_OUTLIER_FACTOR || price * PRICE_OUTLIER_FACTOR < median
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::Local;
+
+    use super::check_data_quality;
+    use crate::{
+        interface::{
+            admin_notification::DataQualityIssueKind, persistent_data::model::PlannedFood,
+        },
+        util::{FoodType, Price, Uuid},
+    };
+
+    fn planned_food(id: u128, name: &str, student_price: u32) -> PlannedFood {
+        PlannedFood {
+            food_id: Uuid::from_u128(id),
+            name: name.into(),
+            food_type: FoodType::Vegan,
+            is_meal: true,
+            price: Price {
+                price_student: student_price,
+                price_employee: student_price + 100,
+                price_guest: student_price + 200,
+                price_pupil: student_price + 50,
+            },
+            has_nutrition_data: true,
+            has_environment_info: true,
+            date: Local::now().date_naive(),
+            canteen_name: "canteen".into(),
+            line_name: "line".into(),
+        }
+    }
+
+    fn kinds_of(foods: &[PlannedFood], id: u128) -> Vec<DataQualityIssueKind> {
+        let today = Local::now().date_naive();
+        check_data_quality(foods, today, today)
+            .issues
+            .into_iter()
+            .filter(|i| i.food_id == Uuid::from_u128(id))
+            .map(|i| i.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_no_issues() {
+        let foods = vec![
+            planned_food(1, "Pasta", 300),
+            planned_food(2, "Pizza", 350),
+            planned_food(3, "Salad", 280),
+        ];
+        let today = Local::now().date_naive();
+        let report = check_data_quality(&foods, today, today);
+        assert_eq!(report.checked_food_count, 3);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_missing_data_and_unknown_type() {
+        let mut food = planned_food(1, "Pasta", 300);
+        food.food_type = FoodType::Unknown;
+        food.has_nutrition_data = false;
+        food.has_environment_info = false;
+
+        assert_eq!(
+            kinds_of(&[food], 1),
+            vec![
+                DataQualityIssueKind::UnknownFoodType,
+                DataQualityIssueKind::MissingNutritionData,
+                DataQualityIssueKind::MissingEnvironmentInfo,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sides_only_checked_for_type_and_name() {
+        let mut side = planned_food(1, "Salad", 0);
+        side.is_meal = false;
+        side.has_nutrition_data = false;
+        side.has_environment_info = false;
+
+        assert!(kinds_of(&[side], 1).is_empty());
+    }
+
+    #[test]
+    fn test_prices() {
+        let mut free = planned_food(4, "Free", 300);
+        free.price.price_guest = 0;
+        let foods = vec![
+            planned_food(1, "Pasta", 300),
+            planned_food(2, "Pizza", 350),
+            planned_food(3, "Gold", 5000),
+            free,
+            planned_food(5, "Cheap", 10),
+        ];
+
+        assert!(kinds_of(&foods, 1).is_empty());
+        assert_eq!(
+            kinds_of(&foods, 3),
+            vec![DataQualityIssueKind::PriceOutlier]
+        );
+        assert_eq!(kinds_of(&foods, 4), vec![DataQualityIssueKind::ZeroPrice]);
+        assert_eq!(
+            kinds_of(&foods, 5),
+            vec![DataQualityIssueKind::PriceOutlier]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_names() {
+        let foods = vec![
+            planned_food(1, "Pasta", 300),
+            planned_food(2, " pasta", 300),
+            planned_food(3, "Pizza", 300),
+        ];
+        // the same food served twice is no duplicate
+        let mut same = planned_food(3, "Pizza", 300);
+        same.line_name = "other line".into();
+
+        let foods = [foods, vec![same]].concat();
+
+        assert_eq!(
+            kinds_of(&foods, 1),
+            vec![DataQualityIssueKind::DuplicateName]
+        );
+        assert_eq!(
+            kinds_of(&foods, 2),
+            vec![DataQualityIssueKind::DuplicateName]
+        );
+        assert!(kinds_of(&foods, 3).is_empty());
+    }
+}
@@ -5,15 +5,20 @@ use async_trait::async_trait;
 
 use crate::{
     interface::{
-        admin_notification::{self, AdminNotification, ImageReportInfo},
+        admin_notification::{
+            self, AdminNotification, DataQualityReport, ImageReportInfo, JobFailureInfo,
+            UnknownCodeInfo,
+        },
         image_storage::ImageStorage,
         image_validation::ImageValidation,
         persistent_data::{
-            model::{ExtendedImage, Image},
+            model::{DataErasure, ExtendedImage, Image, PlannedFood},
             CommandDataAccess, DataError, Result as DataResult,
         },
     },
-    util::{Date, ImageResource, ReportReason, Uuid},
+    util::{
+        ClosureReason, Date, ErasureRequester, FoodType, ImageResource, Price, ReportReason, Uuid,
+    },
 };
 
 pub const IMAGE_ID_TO_FAIL: Uuid = Uuid::from_u128(7u128);
@@ -126,13 +131,77 @@ impl CommandDataAccess for CommandDatabaseMock {
         }
     }
 
+    /// Removes the rating of the given meal by the given user.
+    async fn remove_rating(&self, meal_id: Uuid, _user_id: Uuid) -> DataResult<()> {
+        if MEAL_ID_TO_FAIL == meal_id {
+            Err(DataError::NoSuchItem)
+        } else {
+            Ok(())
+        }
+    }
+
     async fn delete_image(&self, _image_id: Uuid) -> DataResult<()> {
         Ok(())
     }
 
+    /// All images except [`IMAGE_ID_TO_FAIL`] are uploaded by the default user id.
+    async fn get_image_uploader(&self, image_id: Uuid) -> DataResult<Option<Uuid>> {
+        Ok((image_id != IMAGE_ID_TO_FAIL).then(Uuid::default))
+    }
+
     async fn verify_image(&self, _image_id: Uuid) -> DataResult<()> {
         Ok(())
     }
+
+    async fn get_planned_foods(&self, from: Date) -> DataResult<Vec<PlannedFood>> {
+        Ok(vec![PlannedFood {
+            food_id: Uuid::default(),
+            name: "Happy Meal".into(),
+            food_type: FoodType::Unknown,
+            is_meal: true,
+            price: Price {
+                price_student: 0,
+                price_employee: 0,
+                price_guest: 0,
+                price_pupil: 0,
+            },
+            has_nutrition_data: false,
+            has_environment_info: false,
+            date: from,
+            canteen_name: "Canteen".into(),
+            line_name: "Line".into(),
+        }])
+    }
+
+    async fn set_closure(
+        &self,
+        _canteen_id: Uuid,
+        _date: Date,
+        _reason: ClosureReason,
+        _note: Option<String>,
+    ) -> DataResult<()> {
+        Ok(())
+    }
+
+    async fn remove_closure(&self, _canteen_id: Uuid, _date: Date) -> DataResult<bool> {
+        Ok(true)
+    }
+
+    async fn delete_user_data(
+        &self,
+        user_id: Uuid,
+        _requester: ErasureRequester,
+    ) -> DataResult<DataErasure> {
+        if user_id == IMAGE_ID_TO_FAIL {
+            return Err(DataError::NoSuchItem);
+        }
+        Ok(DataErasure {
+            image_ids: vec![Uuid::default()],
+            meal_ratings: 1,
+            image_votes: 2,
+            image_reports: 3,
+        })
+    }
 }
 
 #[derive(Default, Debug)]
@@ -148,6 +217,14 @@ impl AdminNotification for CommandAdminNotificationMock {
     async fn notify_admin_image_verified(&self, _image_id: Uuid) -> admin_notification::Result<()> {
         Ok(())
     }
+    async fn notify_admin_data_quality_report(
+        &self,
+        _report: &DataQualityReport,
+    ) -> admin_notification::Result<()> {
+        Ok(())
+    }
+    async fn notify_admin_unknown_codes(&self, _codes: Vec<UnknownCodeInfo>) {}
+    async fn notify_admin_job_failure(&self, _info: JobFailureInfo) {}
 }
 
 #[derive(Default, Debug)]
@@ -179,4 +256,8 @@ impl ImageStorage for CommandImageStorageMock {
     async fn delete_image(&self, _image_id: Uuid) -> crate::interface::image_storage::Result<()> {
         Ok(())
     }
+
+    async fn list_images(&self) -> crate::interface::image_storage::Result<Vec<Uuid>> {
+        Ok(Vec::new())
+    }
 }
@@ -1,4 +1,5 @@
 //! This component contains all the logic needed for API requests that do more than just request data queries.
 pub mod command_handler;
+pub mod data_quality;
 pub mod image_preprocessing;
 pub mod mocks;
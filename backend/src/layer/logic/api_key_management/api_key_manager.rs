@@ -0,0 +1,248 @@
+//! See [`ApiKeyManager`].
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Local;
+use rand::RngCore;
+use tracing::info;
+
+use crate::interface::{
+    api_key_management::{ApiKeyError, ApiKeyManagement, Result},
+    persistent_data::{
+        model::{api_key_ident, ApiKey, ApiKeyPermissions},
+        AuthDataAccess,
+    },
+};
+
+/// Number of random bytes a newly generated api key consists of.
+const API_KEY_BYTES: usize = 32;
+
+/// Class for creating, listing and revoking api keys.
+pub struct ApiKeyManager<DataAccess>
+where
+    DataAccess: AuthDataAccess,
+{
+    database: DataAccess,
+}
+
+impl<DataAccess> ApiKeyManager<DataAccess>
+where
+    DataAccess: AuthDataAccess,
+{
+    /// Creates a new api key manager storing the keys using `database`.
+    pub const fn new(database: DataAccess) -> Self {
+        Self { database }
+    }
+
+    /// Finds the single api key starting with `ident`.
+    async fn find_key(&self, ident: &str) -> Result<ApiKey> {
+        let mut matching = self
+            .database
+            .get_all_api_keys()
+            .await?
+            .into_iter()
+            .filter(|key| !ident.is_empty() && key.key.starts_with(ident));
+
+        let key = matching
+            .next()
+            .ok_or_else(|| ApiKeyError::NoSuchKey(ident.into()))?;
+        if matching.next().is_some() {
+            return Err(ApiKeyError::AmbiguousIdent(ident.into()));
+        }
+        Ok(key)
+    }
+}
+
+/// Generates a new api key from a cryptographically secure random number generator.
+fn generate_key() -> String {
+    let mut bytes = [0; API_KEY_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[async_trait]
+impl<DataAccess> ApiKeyManagement for ApiKeyManager<DataAccess>
+where
+    DataAccess: AuthDataAccess,
+{
+    async fn create_api_key(
+        &self,
+        description: String,
+        permissions: ApiKeyPermissions,
+    ) -> Result<ApiKey> {
+        if permissions.rate_limit == Some(0) {
+            return Err(ApiKeyError::InvalidPermissions(
+                "the rate limit has to be positive".into(),
+            ));
+        }
+        if permissions
+            .expires_at
+            .is_some_and(|time| time <= Local::now())
+        {
+            return Err(ApiKeyError::InvalidPermissions(
+                "the expiry date has to be in the future".into(),
+            ));
+        }
+
+        let existing = self.database.get_all_api_keys().await?;
+        // keys are looked up by their identifier on authentication, so it has to be unique
+        let key = std::iter::repeat_with(generate_key)
+            .find(|new| {
+                !existing
+                    .iter()
+                    .any(|key| key.key.starts_with(api_key_ident(new)))
+            })
+            .expect("iterator is infinite");
+
+        let key = self
+            .database
+            .add_api_key(&key, &description, &permissions)
+            .await?;
+        info!(
+            "Created api key `{}` with scopes {:?}: {}",
+            key.ident(),
+            key.permissions.scopes,
+            key.description
+        );
+        Ok(key)
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>> {
+        Ok(self.database.get_all_api_keys().await?)
+    }
+
+    async fn describe_api_key(&self, ident: &str) -> Result<ApiKey> {
+        self.find_key(ident).await
+    }
+
+    async fn revoke_api_key(&self, ident: &str) -> Result<ApiKey> {
+        let key = self.find_key(ident).await?;
+        if key.revoked_at.is_some() || !self.database.revoke_api_key(&key.key).await? {
+            return Err(ApiKeyError::AlreadyRevoked(key.ident().into()));
+        }
+
+        info!("Revoked api key `{}`: {}", key.ident(), key.description);
+        self.find_key(&key.key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Duration, Local};
+
+    use crate::{
+        interface::{
+            api_key_management::{ApiKeyError, ApiKeyManagement},
+            persistent_data::model::{ApiKeyPermissions, API_KEY_IDENT_LENGTH},
+        },
+        layer::logic::api_key_management::mocks::AuthDatabaseMock,
+        util::ApiKeyScope,
+    };
+
+    use super::{generate_key, ApiKeyManager};
+
+    #[test]
+    fn test_generate_key() {
+        let key = generate_key();
+        assert_eq!(key.len(), 43);
+        assert!(key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(key, generate_key());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list() {
+        let manager = ApiKeyManager::new(AuthDatabaseMock::with_keys(&["abc"]));
+
+        let key = manager
+            .create_api_key("test key".into(), ApiKeyPermissions::default())
+            .await
+            .unwrap();
+        assert_eq!(key.description, "test key");
+        assert_eq!(key.ident().len(), API_KEY_IDENT_LENGTH);
+
+        let keys = manager.list_api_keys().await.unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[1], key);
+    }
+
+    #[tokio::test]
+    async fn test_create_with_permissions() {
+        let manager = ApiKeyManager::new(AuthDatabaseMock::default());
+
+        let permissions = ApiKeyPermissions {
+            scopes: vec![ApiKeyScope::Vote],
+            expires_at: Some(Local::now() + Duration::days(1)),
+            rate_limit: Some(10),
+        };
+        let key = manager
+            .create_api_key("voting only".into(), permissions.clone())
+            .await
+            .unwrap();
+        assert_eq!(key.permissions, permissions);
+
+        let zero_limit = ApiKeyPermissions {
+            rate_limit: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.create_api_key(String::new(), zero_limit).await,
+            Err(ApiKeyError::InvalidPermissions(_))
+        ));
+
+        let expired = ApiKeyPermissions {
+            expires_at: Some(Local::now() - Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.create_api_key(String::new(), expired).await,
+            Err(ApiKeyError::InvalidPermissions(_))
+        ));
+        assert_eq!(manager.list_api_keys().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_describe() {
+        let manager = ApiKeyManager::new(AuthDatabaseMock::with_keys(&[
+            "abcdef1234567",
+            "abcxyz1234567",
+        ]));
+
+        assert_eq!(
+            manager.describe_api_key("abcd").await.unwrap().key,
+            "abcdef1234567"
+        );
+        assert!(matches!(
+            manager.describe_api_key("abc").await,
+            Err(ApiKeyError::AmbiguousIdent(_))
+        ));
+        assert!(matches!(
+            manager.describe_api_key("xyz").await,
+            Err(ApiKeyError::NoSuchKey(_))
+        ));
+        assert!(matches!(
+            manager.describe_api_key("").await,
+            Err(ApiKeyError::NoSuchKey(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_revoke() {
+        let manager = ApiKeyManager::new(AuthDatabaseMock::with_keys(&[
+            "abcdef1234567",
+            "abcxyz1234567",
+        ]));
+
+        let revoked = manager.revoke_api_key("abcd").await.unwrap();
+        assert!(revoked.revoked_at.is_some());
+        assert!(matches!(
+            manager.revoke_api_key("abcd").await,
+            Err(ApiKeyError::AlreadyRevoked(_))
+        ));
+
+        let keys = manager.list_api_keys().await.unwrap();
+        assert!(keys[0].revoked_at.is_some());
+        assert!(keys[1].revoked_at.is_none());
+    }
+}
@@ -0,0 +1,113 @@
+//! This crate contains mocks of [`AuthDataAccess`] for testing the api key management component.
+#![cfg(test)]
+#![allow(missing_docs, clippy::unwrap_used, clippy::missing_panics_doc)]
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+
+use crate::{
+    interface::persistent_data::{
+        model::{ApiKey, ApiKeyPermissions},
+        AuthDataAccess, Result,
+    },
+    util::Uuid,
+};
+
+#[derive(Default)]
+pub struct AuthDatabaseMock {
+    keys: Mutex<Vec<ApiKey>>,
+    nonces: Mutex<HashMap<(Uuid, String), DateTime<Local>>>,
+}
+
+impl AuthDatabaseMock {
+    #[must_use]
+    pub fn with_keys(keys: &[&str]) -> Self {
+        let keys = keys
+            .iter()
+            .map(|key| ApiKey {
+                key: (*key).into(),
+                description: String::new(),
+                created_at: Local::now(),
+                revoked_at: None,
+                permissions: ApiKeyPermissions::default(),
+            })
+            .collect();
+        Self {
+            keys: Mutex::new(keys),
+            nonces: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthDataAccess for AuthDatabaseMock {
+    async fn get_api_keys(&self) -> Result<Vec<ApiKey>> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|key| key.revoked_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn get_all_api_keys(&self) -> Result<Vec<ApiKey>> {
+        Ok(self.keys.lock().unwrap().clone())
+    }
+
+    async fn add_api_key(
+        &self,
+        key: &str,
+        description: &str,
+        permissions: &ApiKeyPermissions,
+    ) -> Result<ApiKey> {
+        let key = ApiKey {
+            key: key.into(),
+            description: description.into(),
+            created_at: Local::now(),
+            revoked_at: None,
+            permissions: permissions.clone(),
+        };
+        self.keys.lock().unwrap().push(key.clone());
+        Ok(key)
+    }
+
+    async fn revoke_api_key(&self, key: &str) -> Result<bool> {
+        let revoked = self
+            .keys
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|existing| existing.key == key && existing.revoked_at.is_none())
+            .map(|key| key.revoked_at = Some(Local::now()))
+            .is_some();
+        Ok(revoked)
+    }
+
+    async fn use_nonce(
+        &self,
+        client_id: Uuid,
+        nonce: &str,
+        expires_at: DateTime<Local>,
+    ) -> Result<bool> {
+        let key = (client_id, nonce.to_string());
+        let mut nonces = self.nonces.lock().unwrap();
+        let unused = nonces.get(&key).is_none_or(|expiry| *expiry < Local::now());
+        if unused {
+            nonces.insert(key, expires_at);
+        }
+        drop(nonces);
+        Ok(unused)
+    }
+
+    async fn delete_expired_nonces(&self) -> Result<u64> {
+        let mut nonces = self.nonces.lock().unwrap();
+        let before = nonces.len();
+        nonces.retain(|_, expiry| *expiry >= Local::now());
+        let deleted = before - nonces.len();
+        drop(nonces);
+        Ok(u64::try_from(deleted).unwrap())
+    }
+}
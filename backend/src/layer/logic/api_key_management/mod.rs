@@ -0,0 +1,3 @@
+//! This component manages the api keys clients authenticate with.
+pub mod api_key_manager;
+pub mod mocks;
@@ -0,0 +1,157 @@
+//! See [`MaintenanceManager`].
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::{Duration, Local};
+use tracing::{info, warn};
+
+use crate::interface::{
+    image_storage::ImageStorage,
+    maintenance::{MaintenanceScheduling, Result},
+    persistent_data::MaintenanceDataAccess,
+};
+
+/// Structure containing all information necessary to construct a [`MaintenanceManager`].
+pub struct MaintenanceInfo {
+    /// Number of days after which image reports expire.
+    pub report_expiry_days: u32,
+    /// Number of days meal plan data is kept for.
+    pub data_retention_days: u32,
+}
+
+/// Class performing regular maintenance tasks.
+pub struct MaintenanceManager<DataAccess, Storage>
+where
+    DataAccess: MaintenanceDataAccess,
+    Storage: ImageStorage,
+{
+    info: MaintenanceInfo,
+    database: DataAccess,
+    image_storage: Storage,
+}
+
+impl<DataAccess, Storage> MaintenanceManager<DataAccess, Storage>
+where
+    DataAccess: MaintenanceDataAccess,
+    Storage: ImageStorage,
+{
+    /// Creates a new maintenance manager.
+    pub const fn new(info: MaintenanceInfo, database: DataAccess, image_storage: Storage) -> Self {
+        Self {
+            info,
+            database,
+            image_storage,
+        }
+    }
+}
+
+#[async_trait]
+impl<DataAccess, Storage> MaintenanceScheduling for MaintenanceManager<DataAccess, Storage>
+where
+    DataAccess: MaintenanceDataAccess,
+    Storage: ImageStorage,
+{
+    async fn expire_image_reports(&self) -> Result<()> {
+        let before =
+            Local::now().date_naive() - Duration::days(self.info.report_expiry_days.into());
+        let deleted = self.database.delete_reports_before(before).await?;
+        info!("Removed {deleted} image reports made before {before}.");
+        Ok(())
+    }
+
+    async fn remove_orphaned_images(&self) -> Result<()> {
+        // images get linked in the database before being stored, so files have to be listed first
+        let stored = self.image_storage.list_images().await?;
+        let linked: HashSet<_> = self
+            .database
+            .get_all_image_ids()
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut removed = 0;
+        for id in stored.into_iter().filter(|id| !linked.contains(id)) {
+            match self.image_storage.delete_image(id).await {
+                Ok(()) => removed += 1,
+                Err(e) => warn!("Could not remove orphaned image file {id}: {e}"),
+            }
+        }
+        info!("Removed {removed} orphaned image files.");
+        Ok(())
+    }
+
+    async fn refresh_statistics(&self) -> Result<()> {
+        self.database.refresh_statistics().await?;
+        Ok(())
+    }
+
+    async fn purge_old_data(&self) -> Result<()> {
+        let before =
+            Local::now().date_naive() - Duration::days(self.info.data_retention_days.into());
+        let deleted = self.database.delete_meal_plans_before(before).await?;
+        info!("Removed {deleted} meal plan entries and closures before {before}.");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Duration, Local};
+
+    use super::{MaintenanceInfo, MaintenanceManager};
+    use crate::{
+        interface::maintenance::MaintenanceScheduling,
+        layer::logic::maintenance::mocks::{
+            MaintenanceDatabaseMock, MaintenanceImageStorageMock, LINKED_IMAGE, ORPHANED_IMAGE,
+        },
+    };
+
+    fn manager() -> MaintenanceManager<MaintenanceDatabaseMock, MaintenanceImageStorageMock> {
+        let info = MaintenanceInfo {
+            report_expiry_days: 30,
+            data_retention_days: 365,
+        };
+        MaintenanceManager::new(
+            info,
+            MaintenanceDatabaseMock::default(),
+            MaintenanceImageStorageMock::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_expire_image_reports() {
+        let manager = manager();
+        manager.expire_image_reports().await.unwrap();
+        assert_eq!(
+            manager.database.get_dates(),
+            vec![Local::now().date_naive() - Duration::days(30)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_orphaned_images() {
+        let manager = manager();
+        manager.remove_orphaned_images().await.unwrap();
+        let deleted = manager.image_storage.get_deleted();
+        assert!(deleted.contains(&ORPHANED_IMAGE));
+        assert!(!deleted.contains(&LINKED_IMAGE));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_statistics() {
+        let manager = manager();
+        manager.refresh_statistics().await.unwrap();
+        assert_eq!(manager.database.get_refresh_calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_purge_old_data() {
+        let manager = manager();
+        manager.purge_old_data().await.unwrap();
+        assert_eq!(
+            manager.database.get_dates(),
+            vec![Local::now().date_naive() - Duration::days(365)]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! This crate contains mocks of [`MaintenanceDataAccess`] and [`ImageStorage`] for testing the maintenance component.
+#![cfg(test)]
+#![allow(missing_docs, clippy::unwrap_used, clippy::missing_panics_doc)]
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::{
+    interface::{
+        image_storage::{ImageStorage, Result as ImageResult},
+        persistent_data::{MaintenanceDataAccess, Result as DataResult},
+    },
+    util::{Date, ImageResource, Uuid},
+};
+
+pub const LINKED_IMAGE: Uuid = Uuid::from_u128(1);
+pub const ORPHANED_IMAGE: Uuid = Uuid::from_u128(2);
+
+#[derive(Default)]
+pub struct MaintenanceDatabaseMock {
+    dates: Mutex<Vec<Date>>,
+    refresh_calls: Mutex<u32>,
+}
+
+impl MaintenanceDatabaseMock {
+    pub fn get_dates(&self) -> Vec<Date> {
+        self.dates.lock().unwrap().clone()
+    }
+
+    pub fn get_refresh_calls(&self) -> u32 {
+        *self.refresh_calls.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl MaintenanceDataAccess for MaintenanceDatabaseMock {
+    async fn delete_reports_before(&self, date: Date) -> DataResult<u64> {
+        self.dates.lock().unwrap().push(date);
+        Ok(1)
+    }
+
+    async fn get_all_image_ids(&self) -> DataResult<Vec<Uuid>> {
+        Ok(vec![LINKED_IMAGE])
+    }
+
+    async fn refresh_statistics(&self) -> DataResult<()> {
+        *self.refresh_calls.lock().unwrap() += 1;
+        Ok(())
+    }
+
+    async fn delete_meal_plans_before(&self, date: Date) -> DataResult<u64> {
+        self.dates.lock().unwrap().push(date);
+        Ok(1)
+    }
+}
+
+#[derive(Default)]
+pub struct MaintenanceImageStorageMock {
+    deleted: Mutex<Vec<Uuid>>,
+}
+
+impl MaintenanceImageStorageMock {
+    pub fn get_deleted(&self) -> Vec<Uuid> {
+        self.deleted.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ImageStorage for MaintenanceImageStorageMock {
+    async fn save_image(&self, _id: Uuid, _image: ImageResource) -> ImageResult<()> {
+        Ok(())
+    }
+
+    async fn delete_image(&self, id: Uuid) -> ImageResult<()> {
+        self.deleted.lock().unwrap().push(id);
+        Ok(())
+    }
+
+    async fn list_images(&self) -> ImageResult<Vec<Uuid>> {
+        Ok(vec![LINKED_IMAGE, ORPHANED_IMAGE])
+    }
+}
@@ -0,0 +1,3 @@
+//! This component performs regular maintenance tasks like cleaning up outdated data and orphaned image files.
+pub mod maintenance_manager;
+pub mod mocks;
@@ -1,40 +1,127 @@
 //! See [`MealPlanManager`].
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Instant;
+
+use crate::interface::admin_notification::{AdminNotification, UnknownCodeInfo};
 use crate::interface::mealplan_management::MensaParseScheduling;
-use crate::interface::mensa_parser::model::ParseCanteen;
-use crate::interface::mensa_parser::MealplanParser;
+use crate::interface::mensa_parser::model::{ParseCanteen, ParseClosure};
+use crate::interface::mensa_parser::{MealplanParser, ParseError};
 use crate::interface::persistent_data::MealplanManagementDataAccess;
 use crate::layer::logic::mealplan_management::relation_resolver::RelationResolver;
-use crate::util::Date;
+use crate::util::{metrics, ClosureReason, Date};
 use async_trait::async_trait;
 use chrono::Local;
 use tracing::{error, trace, warn};
 
 /// Class responsible for managing the meal plan update process.
-pub struct MealPlanManager<Parser, DataAccess>
+pub struct MealPlanManager<Parser, DataAccess, Notify>
 where
     Parser: MealplanParser,
     DataAccess: MealplanManagementDataAccess,
+    Notify: AdminNotification,
 {
     resolver: RelationResolver<DataAccess>,
     parser: Parser,
+    admin_notification: Notify,
 }
 
-impl<Parser, DataAccess> MealPlanManager<Parser, DataAccess>
+impl<Parser, DataAccess, Notify> MealPlanManager<Parser, DataAccess, Notify>
 where
     DataAccess: MealplanManagementDataAccess,
     Parser: MealplanParser,
+    Notify: AdminNotification,
 {
     /// Creates a new instance using the given data store and parser to get meal the raw meal plans.
-    pub const fn new(database: DataAccess, meal_plan_parser: Parser) -> Self {
+    /// Administrators get notified about newly found unknown allergen and additive codes using `admin_notification`.
+    pub const fn new(
+        database: DataAccess,
+        meal_plan_parser: Parser,
+        admin_notification: Notify,
+    ) -> Self {
         Self {
             resolver: RelationResolver::new(database),
             parser: meal_plan_parser,
+            admin_notification,
         }
     }
 
-    async fn start_resolving(&self, parse_canteens: Vec<ParseCanteen>, date: Date) {
-        for parse_canteen in parse_canteens {
+    /// Collects all unknown allergen and additive codes of the given canteens which have not been recorded before.
+    async fn find_new_unknown_codes<'a>(
+        &self,
+        parse_canteens: impl IntoIterator<Item = &'a ParseCanteen> + Send,
+    ) -> Vec<UnknownCodeInfo> {
+        let recorded = match self.resolver.get_recorded_unknown_codes().await {
+            Ok(codes) => codes.into_iter().collect::<HashSet<_>>(),
+            Err(error) => {
+                warn!("could not load recorded unknown codes: {error}");
+                return Vec::new();
+            }
+        };
+
+        let mut new_codes = BTreeMap::<_, BTreeSet<_>>::new();
+        let dishes = parse_canteens
+            .into_iter()
+            .flat_map(|canteen| &canteen.lines)
+            .flat_map(|line| &line.dishes);
+        for dish in dishes {
+            for code in dish.unknown_codes.iter().filter(|c| !recorded.contains(*c)) {
+                new_codes
+                    .entry(code.clone())
+                    .or_default()
+                    .insert(dish.name.clone());
+            }
+        }
+
+        new_codes
+            .into_iter()
+            .map(|(code, dish_names)| UnknownCodeInfo {
+                code,
+                dish_names: dish_names.into_iter().collect(),
+            })
+            .collect()
+    }
+
+    async fn notify_new_unknown_codes(&self, codes: Vec<UnknownCodeInfo>) {
+        if !codes.is_empty() {
+            warn!(
+                ?codes,
+                "found {} new unknown allergen or additive codes",
+                codes.len()
+            );
+            self.admin_notification
+                .notify_admin_unknown_codes(codes)
+                .await;
+        }
+    }
+
+    async fn record_parse_success(&self, kind: &str, dishes: usize) {
+        if let Err(error) = self.resolver.record_parse_success(kind, dishes).await {
+            warn!("could not record successful {kind} parse run: {error}");
+        }
+    }
+
+    /// Resolves the given canteens at `date`, storing the closures announced on their meal plans.
+    /// As a fallback, a canteen without any dishes and without announced closure is considered closed
+    /// if it serves dishes on a later day according to `last_serving_dates`, as the meal plan then has been published for this day already.
+    async fn start_resolving(
+        &self,
+        parse_canteens: Vec<ParseCanteen>,
+        date: Date,
+        last_serving_dates: &HashMap<String, Date>,
+    ) {
+        for mut parse_canteen in parse_canteens {
             let name = &parse_canteen.name.clone();
+            if parse_canteen.closure.is_none()
+                && !has_dishes(&parse_canteen)
+                && last_serving_dates
+                    .get(name)
+                    .is_some_and(|last_date| *last_date > date)
+            {
+                parse_canteen.closure = Some(ParseClosure {
+                    reason: ClosureReason::Unspecified,
+                    note: None,
+                });
+            }
             match self.resolver.resolve(parse_canteen, date).await {
                 Ok(()) => trace!(%date, "resolved canteen '{name}' with no errors"),
                 Err(error) => warn!(%date, "resolved canteen '{name}' with errors: {error}"),
@@ -43,24 +130,67 @@ where
     }
 }
 
+fn count_dishes<'a>(canteens: impl IntoIterator<Item = &'a ParseCanteen>) -> usize {
+    canteens
+        .into_iter()
+        .flat_map(|canteen| &canteen.lines)
+        .map(|line| line.dishes.len())
+        .sum()
+}
+
+fn has_dishes(canteen: &ParseCanteen) -> bool {
+    canteen.lines.iter().any(|line| !line.dishes.is_empty())
+}
+
+/// Determines the last day each canteen serves any dishes at.
+fn last_serving_dates<'a>(
+    parse_tuples: impl IntoIterator<Item = (Date, &'a ParseCanteen)>,
+) -> HashMap<String, Date> {
+    let mut dates = HashMap::<String, Date>::new();
+    for (date, canteen) in parse_tuples {
+        if has_dishes(canteen) {
+            dates
+                .entry(canteen.name.clone())
+                .and_modify(|last| *last = (*last).max(date))
+                .or_insert(date);
+        }
+    }
+    dates
+}
+
 #[async_trait]
-impl<DataAccess, Parser> MensaParseScheduling for MealPlanManager<Parser, DataAccess>
+impl<DataAccess, Parser, Notify> MensaParseScheduling
+    for MealPlanManager<Parser, DataAccess, Notify>
 where
     DataAccess: MealplanManagementDataAccess,
     Parser: MealplanParser,
+    Notify: AdminNotification,
 {
     /// This method starts the parsing procedure for all meal plans **of the current day**.<br>
     /// After parsing, the raw data objects (`Vec<ParseCanteen>`) will be inserted by the [`RelationResolver`] with the current day.<br>
     /// If during resolving an error occurs, the resolver stops and a log will be displayed.<br>
     /// Each successful resolving process is also logged.
-    async fn start_update_parsing(&self) {
+    /// Administrators get notified about allergen and additive codes which could not be recognized and have not been seen before.
+    async fn start_update_parsing(&self) -> Result<(), ParseError> {
+        let start = Instant::now();
         let today = Local::now().date_naive();
         match self.parser.parse(today).await {
             Ok(parse_canteens) => {
-                self.start_resolving(parse_canteens, today).await;
+                let dishes = count_dishes(&parse_canteens);
+                let new_codes = self.find_new_unknown_codes(&parse_canteens).await;
+                // closures can only be inferred from the meal plans of later days, so only announced ones get stored
+                self.start_resolving(parse_canteens, today, &HashMap::new())
+                    .await;
+                self.notify_new_unknown_codes(new_codes).await;
+                metrics::record_parse_success(metrics::UPDATE_PARSE, start.elapsed(), dishes);
+                self.record_parse_success(metrics::UPDATE_PARSE, dishes)
+                    .await;
+                Ok(())
             }
             Err(error) => {
                 error!(%today, "canteens parsed with error and can't be resolved: {error}");
+                metrics::record_parse_failure(metrics::UPDATE_PARSE);
+                Err(error)
             }
         }
     }
@@ -69,15 +199,33 @@ where
     /// After parsing, the raw data objects (`Vec<(Date, Vec<ParseCanteen>>`) will be inserted by the [`RelationResolver`].<br>
     /// If during resolving an error occurs, the resolver stops and a log will be displayed.<br>
     /// Each successful resolving process is also logged.
-    async fn start_full_parsing(&self) {
+    /// Besides announced closures, canteens without any dishes on a day before the last day they serve dishes at get marked as closed.
+    async fn start_full_parsing(&self) -> Result<(), ParseError> {
+        let start = Instant::now();
         match self.parser.parse_all().await {
             Ok(parse_tuples) => {
+                let dishes = count_dishes(parse_tuples.iter().flat_map(|(_, c)| c));
+                let new_codes = self
+                    .find_new_unknown_codes(parse_tuples.iter().flat_map(|(_, c)| c))
+                    .await;
+                let last_dates = last_serving_dates(
+                    parse_tuples
+                        .iter()
+                        .flat_map(|(date, canteens)| canteens.iter().map(|c| (*date, c))),
+                );
                 for (date, parse_canteens) in parse_tuples {
-                    self.start_resolving(parse_canteens, date).await;
+                    self.start_resolving(parse_canteens, date, &last_dates)
+                        .await;
                 }
+                self.notify_new_unknown_codes(new_codes).await;
+                metrics::record_parse_success(metrics::FULL_PARSE, start.elapsed(), dishes);
+                self.record_parse_success(metrics::FULL_PARSE, dishes).await;
+                Ok(())
             }
             Err(error) => {
                 error!("canteens parsed with error and can't be resolved: {error}");
+                metrics::record_parse_failure(metrics::FULL_PARSE);
+                Err(error)
             }
         }
     }
@@ -85,20 +233,79 @@ where
 
 #[cfg(test)]
 mod test {
+    #![allow(clippy::unwrap_used)]
+    use chrono::Local;
+
     use crate::interface::mealplan_management::MensaParseScheduling;
-    use crate::layer::logic::mealplan_management::meal_plan_manager::MealPlanManager;
+    use crate::interface::mensa_parser::{model::ParseCanteen, MealplanParser};
+    use crate::layer::logic::api_command::mocks::CommandAdminNotificationMock;
+    use crate::layer::logic::mealplan_management::meal_plan_manager::{
+        has_dishes, last_serving_dates, MealPlanManager,
+    };
     use crate::layer::logic::mealplan_management::test::meal_plan_parser_mock::MealPlanParserMock;
     use crate::layer::logic::mealplan_management::test::mealplan_management_database_mock::MealplanManagementDatabaseMock;
 
     #[tokio::test]
     async fn test_valid_start_update_parsing() {
-        let manager = MealPlanManager::new(MealplanManagementDatabaseMock, MealPlanParserMock);
-        manager.start_update_parsing().await;
+        let manager = MealPlanManager::new(
+            MealplanManagementDatabaseMock,
+            MealPlanParserMock,
+            CommandAdminNotificationMock,
+        );
+        assert!(manager.start_update_parsing().await.is_ok());
     }
 
     #[tokio::test]
     async fn test_valid_start_full_parsing() {
-        let manager = MealPlanManager::new(MealplanManagementDatabaseMock, MealPlanParserMock);
-        manager.start_full_parsing().await;
+        let manager = MealPlanManager::new(
+            MealplanManagementDatabaseMock,
+            MealPlanParserMock,
+            CommandAdminNotificationMock,
+        );
+        assert!(manager.start_full_parsing().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_find_new_unknown_codes() {
+        let manager = MealPlanManager::new(
+            MealplanManagementDatabaseMock,
+            MealPlanParserMock,
+            CommandAdminNotificationMock,
+        );
+        let canteens = MealPlanParserMock
+            .parse(Local::now().date_naive())
+            .await
+            .unwrap();
+        let codes = manager.find_new_unknown_codes(&canteens).await;
+        // "GEL" is already recorded by the database mock
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].code, "Xy");
+        assert!(!codes[0].dish_names.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_last_serving_dates() {
+        let today = Local::now().date_naive();
+        let tomorrow = today.succ_opt().unwrap();
+        let empty_canteen = |name: &str| ParseCanteen {
+            name: name.into(),
+            lines: vec![],
+            pos: 0,
+            closure: None,
+        };
+        let mut served = MealPlanParserMock.parse(today).await.unwrap().remove(0);
+        served.name = "open".into();
+        assert!(has_dishes(&served));
+
+        let open = empty_canteen("open");
+        let closed = empty_canteen("closed");
+        let dates = last_serving_dates([
+            (today, &open),
+            (tomorrow, &served),
+            (tomorrow, &open),
+            (today, &closed),
+        ]);
+        assert_eq!(dates.get("open"), Some(&tomorrow));
+        assert_eq!(dates.get("closed"), None);
     }
 }
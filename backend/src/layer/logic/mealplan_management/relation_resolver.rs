@@ -30,6 +30,7 @@ where
     /// If a similar object already exists, the existing object will be updated with the new object data.<br>
     /// `canteen: ParseCanteen`<br>This struct contains all canteen data e.g. lines and dishes.<br>
     /// `date: Date`<br>This date decides when the meal will be served next.<br>
+    /// If the canteen is closed according to `canteen.closure`, the closure gets stored. Closures detected earlier get removed as soon as dishes are served.<br>
     /// # Errors
     /// Occurring errors get passed to the [`MealPlanManager`](`crate::layer::logic::mealplan_management::meal_plan_manager::MealPlanManager`)
     pub async fn resolve(&self, canteen: ParseCanteen, date: Date) -> Result<(), DataError> {
@@ -43,6 +44,13 @@ where
             None => self.db.insert_canteen(&canteen.name, canteen.pos).await?,
         };
         self.db.dissolve_relations(db_canteen, date).await?;
+        if let Some(closure) = &canteen.closure {
+            self.db
+                .add_parsed_closure(db_canteen, date, closure.reason, closure.note.as_deref())
+                .await?;
+        } else if canteen.lines.iter().any(|line| !line.dishes.is_empty()) {
+            self.db.remove_parsed_closure(db_canteen, date).await?;
+        }
         for line in canteen.lines {
             let name = &line.name.clone();
             if let Err(e) = self.resolve_line(date, line, db_canteen).await {
@@ -101,7 +109,7 @@ where
 
         // Case 1.1: A similar side and meal could be found. Uncommon case.
         // Case 1.2: Or just a meal could be found.
-        if let Some(similar_meal) = similar_meal_result {
+        let food_id = if let Some(similar_meal) = similar_meal_result {
             self.db
                 .update_meal(
                     similar_meal,
@@ -113,6 +121,7 @@ where
             self.db
                 .add_meal_to_plan(similar_meal, line_id, date, dish.price)
                 .await?;
+            similar_meal
         // Case 2: A similar side could be found.
         } else if let Some(similar_side) = similar_side_result {
             self.db
@@ -126,6 +135,7 @@ where
             self.db
                 .add_side_to_plan(similar_side, line_id, date, dish.price)
                 .await?;
+            similar_side
         // Case 3: No similar meal could be found. Dish needs to be determined.
         } else if Self::is_side(dish.price.price_student, average, &dish.name) {
             let side_id = self
@@ -142,6 +152,7 @@ where
             self.db
                 .add_side_to_plan(side_id, line_id, date, dish.price)
                 .await?;
+            side_id
         } else {
             let meal_id = self
                 .db
@@ -157,10 +168,31 @@ where
             self.db
                 .add_meal_to_plan(meal_id, line_id, date, dish.price)
                 .await?;
-        }
+            meal_id
+        };
+
+        self.db
+            .set_unknown_codes(food_id, &dish.unknown_codes)
+            .await?;
         Ok(())
     }
 
+    /// Returns all allergen and additive codes which have already been recorded as unknown.
+    /// # Errors
+    /// Returns an error if the codes could not be retrieved from the data store.
+    pub async fn get_recorded_unknown_codes(&self) -> Result<Vec<String>, DataError> {
+        self.db.get_all_unknown_codes().await
+    }
+
+    /// Records a successful parse run of the given kind, so all instances know when the meal plan was last parsed.
+    /// # Errors
+    /// Returns an error if the run could not be stored.
+    pub async fn record_parse_success(&self, kind: &str, dishes: usize) -> Result<(), DataError> {
+        self.db
+            .record_parse_success(kind, dishes.try_into().unwrap_or(u32::MAX))
+            .await
+    }
+
     fn is_side(dish_price: u32, average: f64, dish_name: &str) -> bool {
         let price_limit = average * Self::SIDE_PERCENTAGE_GAP;
         (f64::from(dish_price)) < price_limit && !dish_name.contains(Self::EDGE_CASE_NAME)
@@ -177,10 +209,10 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::interface::mensa_parser::model::{Dish, ParseCanteen, ParseLine};
+    use crate::interface::mensa_parser::model::{Dish, ParseCanteen, ParseClosure, ParseLine};
     use crate::layer::logic::mealplan_management::relation_resolver::RelationResolver;
     use crate::layer::logic::mealplan_management::test::mealplan_management_database_mock::MealplanManagementDatabaseMock;
-    use crate::util::{FoodType, Price};
+    use crate::util::{ClosureReason, FoodType, Price};
     use chrono::Local;
     use rand::{self, Rng};
     use uuid::Uuid;
@@ -196,6 +228,7 @@ mod test {
             },
             allergens: vec![],
             additives: vec![],
+            unknown_codes: vec![],
             food_type: FoodType::Vegan,
             env_score: None,
             nutrition_data: None,
@@ -213,6 +246,7 @@ mod test {
             },
             allergens: vec![],
             additives: vec![],
+            unknown_codes: vec![],
             food_type: FoodType::Vegan,
             env_score: None,
             nutrition_data: None,
@@ -232,6 +266,7 @@ mod test {
             name: "test_canteen".to_string(),
             lines,
             pos: 42_u32,
+            closure: None,
         }
     }
 
@@ -266,6 +301,18 @@ mod test {
         assert!(res.await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_resolve_closed_canteen() {
+        let resolver = RelationResolver::new(MealplanManagementDatabaseMock);
+        let mut canteen = get_empty_canteen();
+        canteen.closure = Some(ParseClosure {
+            reason: ClosureReason::Holiday,
+            note: Some("Weihnachten".into()),
+        });
+        let res = resolver.resolve(canteen, Local::now().date_naive());
+        assert!(res.await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_resolve_canteens() {
         let resolver = RelationResolver::new(MealplanManagementDatabaseMock);
@@ -28,6 +28,7 @@ fn get_dish(dish_number: u32) -> Dish {
         },
         allergens: vec![Pe, Er],
         additives: vec![Alcohol],
+        unknown_codes: vec!["GEL".into(), "Xy".into()],
         food_type: Vegan,
         env_score: None,
         nutrition_data: None,
@@ -63,6 +64,7 @@ fn get_canteen(canteen_number: u32, line_amount: u32, dish_amount: u32) -> Parse
         name: format!("Autogenerated Canteen number {canteen_number}"),
         lines: get_lines(line_amount, dish_amount),
         pos: 42_u32,
+        closure: None,
     }
 }
 
@@ -4,7 +4,7 @@ use crate::{
         mensa_parser::model::ParseEnvironmentInfo,
         persistent_data::{MealplanManagementDataAccess, Result},
     },
-    util::{Additive, Allergen, Date, FoodType, NutritionData, Price, Uuid},
+    util::{Additive, Allergen, ClosureReason, Date, FoodType, NutritionData, Price, Uuid},
 };
 use async_trait::async_trait;
 
@@ -132,4 +132,30 @@ impl MealplanManagementDataAccess for MealplanManagementDatabaseMock {
     ) -> Result<()> {
         Ok(())
     }
+
+    async fn get_all_unknown_codes(&self) -> Result<Vec<String>> {
+        Ok(vec!["GEL".into()])
+    }
+
+    async fn set_unknown_codes(&self, _food_id: Uuid, _codes: &[String]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn add_parsed_closure(
+        &self,
+        _canteen_id: Uuid,
+        _date: Date,
+        _reason: ClosureReason,
+        _note: Option<&str>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn remove_parsed_closure(&self, _canteen_id: Uuid, _date: Date) -> Result<()> {
+        Ok(())
+    }
+
+    async fn record_parse_success(&self, _kind: &str, _dishes: u32) -> Result<()> {
+        Ok(())
+    }
 }
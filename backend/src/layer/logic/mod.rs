@@ -2,4 +2,6 @@
 //! It is completely independent of the implementations of the other layers and does not interact with the outside world.
 
 pub mod api_command;
+pub mod api_key_management;
+pub mod maintenance;
 pub mod mealplan_management;
@@ -8,28 +8,46 @@ use axum::{
     http::HeaderValue,
     middleware::{self, Next},
     response::IntoResponse,
-    routing::method_routing::get,
-    Router,
+    routing::method_routing::{delete, get, put},
+    Json, Router,
 };
 use axum_extra::{
     headers::{authorization::Basic, Authorization},
     TypedHeader,
 };
+use chrono::{DateTime, Local};
 use hyper::{header::WWW_AUTHENTICATE, HeaderMap, Request, StatusCode};
+use serde::{Deserialize, Serialize};
 
 use tracing::warn;
 
 use crate::{
-    interface::api_command::{Command, CommandError},
-    util::Uuid,
+    interface::{
+        admin_notification::DataQualityReport,
+        api_command::{Command, CommandError},
+        api_key_management::{ApiKeyError, ApiKeyManagement},
+        job_control::{JobControl, JobError, JobStatus},
+        persistent_data::model::{ApiKey, ApiKeyPermissions},
+    },
+    util::{ApiKeyScope, ClosureReason, Date, ErasureRequester, Uuid},
 };
 
+use super::key_store::ApiKeyStore;
+
 #[derive(Clone)]
 pub(super) struct AdminKey(String);
 
 pub(super) type ArcCommand = Arc<dyn Command + Send + Sync>;
-
-pub(super) fn admin_router(admin_key: String, command: ArcCommand) -> Router<()> {
+pub(super) type ArcJobControl = Arc<dyn JobControl>;
+pub(super) type ArcApiKeyManagement = Arc<dyn ApiKeyManagement>;
+
+pub(super) fn admin_router(
+    admin_key: String,
+    command: ArcCommand,
+    job_control: ArcJobControl,
+    key_management: ArcApiKeyManagement,
+    key_store: ApiKeyStore,
+) -> Router<()> {
     let admin_auth = middleware::from_fn_with_state(AdminKey(admin_key), admin_auth_middleware);
     // let router = Router::new()
     //     .route("/version", get(version))
@@ -41,8 +59,43 @@ pub(super) fn admin_router(admin_key: String, command: ArcCommand) -> Router<()>
         .route("/version", get(version))
         .route("/report/delete_image/{image_id}", get(delete_image))
         .route("/report/verify_image/{image_id}", get(verify_image))
-        .layer(admin_auth)
+        .route("/data_quality", get(data_quality_report))
+        .route("/data_quality/send", get(send_data_quality_report))
+        .route(
+            "/closure/{canteen_id}/{date}",
+            put(set_closure).delete(remove_closure),
+        )
+        .route("/client_data/{client_id}", delete(delete_client_data))
         .with_state(command)
+        .merge(job_router(job_control))
+        .merge(api_key_router(key_management, key_store))
+        .layer(admin_auth)
+}
+
+fn job_router(job_control: ArcJobControl) -> Router<()> {
+    Router::new()
+        .route("/jobs", get(jobs))
+        .route("/jobs/{name}/run", get(run_job))
+        .with_state(job_control)
+}
+
+#[derive(Clone)]
+struct ApiKeyState {
+    key_management: ArcApiKeyManagement,
+    key_store: ApiKeyStore,
+}
+
+fn api_key_router(key_management: ArcApiKeyManagement, key_store: ApiKeyStore) -> Router<()> {
+    Router::new()
+        .route("/api_keys", get(list_api_keys).post(create_api_key))
+        .route(
+            "/api_keys/{ident}",
+            get(describe_api_key).delete(revoke_api_key),
+        )
+        .with_state(ApiKeyState {
+            key_management,
+            key_store,
+        })
 }
 
 impl IntoResponse for CommandError {
@@ -53,6 +106,31 @@ impl IntoResponse for CommandError {
     }
 }
 
+impl IntoResponse for JobError {
+    fn into_response(self) -> axum::response::Response {
+        let status = match self {
+            Self::NoSuchJob(_) => StatusCode::NOT_FOUND,
+            Self::AlreadyRunning(_) => StatusCode::CONFLICT,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+impl IntoResponse for ApiKeyError {
+    fn into_response(self) -> axum::response::Response {
+        let status = match self {
+            Self::NoSuchKey(_) => StatusCode::NOT_FOUND,
+            Self::AmbiguousIdent(_) | Self::InvalidPermissions(_) => StatusCode::BAD_REQUEST,
+            Self::AlreadyRevoked(_) => StatusCode::CONFLICT,
+            Self::DataError(_) => {
+                warn!("On Admin API request: {self}");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
 #[debug_handler]
 async fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -77,6 +155,196 @@ async fn delete_image(
     Ok(format!("Successfully deleted image {image_id}"))
 }
 
+#[debug_handler]
+async fn data_quality_report(
+    State(command): State<ArcCommand>,
+) -> Result<Json<DataQualityReport>, CommandError> {
+    let report = command.create_data_quality_report(false).await?;
+    Ok(Json(report))
+}
+
+#[debug_handler]
+async fn send_data_quality_report(
+    State(command): State<ArcCommand>,
+) -> Result<String, CommandError> {
+    let report = command.create_data_quality_report(true).await?;
+    Ok(format!(
+        "Successfully sent data quality report with {} issues",
+        report.issues.len()
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct ClosureInput {
+    reason: ClosureReason,
+    note: Option<String>,
+}
+
+#[debug_handler]
+async fn set_closure(
+    State(command): State<ArcCommand>,
+    Path((canteen_id, date)): Path<(Uuid, Date)>,
+    Json(input): Json<ClosureInput>,
+) -> Result<String, CommandError> {
+    command
+        .set_canteen_closure(canteen_id, date, input.reason, input.note)
+        .await?;
+    Ok(format!(
+        "Successfully marked canteen {canteen_id} as closed on {date}"
+    ))
+}
+
+#[debug_handler]
+async fn remove_closure(
+    State(command): State<ArcCommand>,
+    Path((canteen_id, date)): Path<(Uuid, Date)>,
+) -> Result<(StatusCode, String), CommandError> {
+    if command.remove_canteen_closure(canteen_id, date).await? {
+        Ok((
+            StatusCode::OK,
+            format!("Successfully removed closure of canteen {canteen_id} on {date}"),
+        ))
+    } else {
+        Ok((
+            StatusCode::NOT_FOUND,
+            format!("Canteen {canteen_id} is not closed on {date}"),
+        ))
+    }
+}
+
+#[debug_handler]
+async fn delete_client_data(
+    State(command): State<ArcCommand>,
+    Path(client_id): Path<Uuid>,
+) -> Result<String, CommandError> {
+    let erasure = command
+        .delete_client_data(client_id, ErasureRequester::Admin)
+        .await?;
+    Ok(format!(
+        "Successfully deleted all data of client {client_id}: {} meal ratings, {} image votes, {} image reports and {} images",
+        erasure.meal_ratings,
+        erasure.image_votes,
+        erasure.image_reports,
+        erasure.image_ids.len()
+    ))
+}
+
+#[debug_handler]
+async fn jobs(State(job_control): State<ArcJobControl>) -> Json<Vec<JobStatus>> {
+    Json(job_control.get_jobs().await)
+}
+
+#[debug_handler]
+async fn run_job(
+    State(job_control): State<ArcJobControl>,
+    Path(name): Path<String>,
+) -> Result<String, JobError> {
+    job_control.trigger_job(&name).await?;
+    Ok(format!("Successfully started job {name}"))
+}
+
+/// Api key as returned by the admin api. The whole key is only included right after creation.
+#[derive(Debug, Serialize)]
+struct ApiKeyOutput {
+    ident: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    description: String,
+    created_at: DateTime<Local>,
+    revoked_at: Option<DateTime<Local>>,
+    scopes: Vec<ApiKeyScope>,
+    expires_at: Option<DateTime<Local>>,
+    rate_limit: Option<u32>,
+}
+
+impl ApiKeyOutput {
+    fn new(key: ApiKey, include_key: bool) -> Self {
+        Self {
+            ident: key.ident().into(),
+            key: include_key.then_some(key.key),
+            description: key.description,
+            created_at: key.created_at,
+            revoked_at: key.revoked_at,
+            scopes: key.permissions.scopes,
+            expires_at: key.permissions.expires_at,
+            rate_limit: key.permissions.rate_limit,
+        }
+    }
+}
+
+/// Api key to create. Without explicit scopes, the key gets all of them.
+#[derive(Debug, Deserialize)]
+struct ApiKeyInput {
+    description: String,
+    scopes: Option<Vec<ApiKeyScope>>,
+    expires_at: Option<DateTime<Local>>,
+    rate_limit: Option<u32>,
+}
+
+impl ApiKeyInput {
+    fn permissions(&self) -> ApiKeyPermissions {
+        ApiKeyPermissions {
+            scopes: self
+                .scopes
+                .clone()
+                .unwrap_or_else(|| ApiKeyScope::ALL.to_vec()),
+            expires_at: self.expires_at,
+            rate_limit: self.rate_limit,
+        }
+    }
+}
+
+async fn reload_api_keys(key_store: &ApiKeyStore) {
+    if let Err(e) = key_store.reload().await {
+        warn!("Could not reload api keys after change: {e}");
+    }
+}
+
+#[debug_handler]
+async fn list_api_keys(
+    State(state): State<ApiKeyState>,
+) -> Result<Json<Vec<ApiKeyOutput>>, ApiKeyError> {
+    let keys = state.key_management.list_api_keys().await?;
+    Ok(Json(
+        keys.into_iter()
+            .map(|key| ApiKeyOutput::new(key, false))
+            .collect(),
+    ))
+}
+
+#[debug_handler]
+async fn create_api_key(
+    State(state): State<ApiKeyState>,
+    Json(input): Json<ApiKeyInput>,
+) -> Result<(StatusCode, Json<ApiKeyOutput>), ApiKeyError> {
+    let permissions = input.permissions();
+    let key = state
+        .key_management
+        .create_api_key(input.description, permissions)
+        .await?;
+    reload_api_keys(&state.key_store).await;
+    Ok((StatusCode::CREATED, Json(ApiKeyOutput::new(key, true))))
+}
+
+#[debug_handler]
+async fn describe_api_key(
+    State(state): State<ApiKeyState>,
+    Path(ident): Path<String>,
+) -> Result<Json<ApiKeyOutput>, ApiKeyError> {
+    let key = state.key_management.describe_api_key(&ident).await?;
+    Ok(Json(ApiKeyOutput::new(key, false)))
+}
+
+#[debug_handler]
+async fn revoke_api_key(
+    State(state): State<ApiKeyState>,
+    Path(ident): Path<String>,
+) -> Result<Json<ApiKeyOutput>, ApiKeyError> {
+    let key = state.key_management.revoke_api_key(&ident).await?;
+    reload_api_keys(&state.key_store).await;
+    Ok(Json(ApiKeyOutput::new(key, false)))
+}
+
 const ADMIN_USER: &str = "admin";
 const XXX_AUTHENTICATE_CONTENT: &str = "Basic realm=MensaKaAdmin";
 
@@ -124,11 +392,17 @@ mod test {
     use hyper::{header::AUTHORIZATION, HeaderMap, StatusCode};
     use reqwest::Client;
 
-    use super::ADMIN_USER;
+    use axum::Router;
+
+    use super::{ArcCommand, ADMIN_USER};
     use crate::{
         layer::trigger::api::{
             admin::admin_router,
-            mock::{CommandMock, FAIL_ID},
+            key_store::ApiKeyStore,
+            mock::{
+                ApiKeyManagementMock, AuthDataMock, CommandMock, JobControlMock, FAIL_ID, JOB_NAME,
+                RUNNING_JOB_NAME,
+            },
         },
         util::Uuid,
     };
@@ -138,7 +412,7 @@ mod test {
         let key: String = "asdasdasdasd".into();
         let command = Arc::new(CommandMock);
 
-        let router = admin_router(key.clone(), command);
+        let router = test_router(key.clone(), command);
         let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8081));
         println!("socket: {socket}");
         let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
@@ -202,6 +476,26 @@ mod test {
                 .status()
         );
 
+        let report = authed_client
+            .get(format!("http://{socket}/data_quality"))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(report["checked_food_count"], 0);
+
+        assert_eq!(
+            StatusCode::OK,
+            authed_client
+                .get(format!("http://{socket}/data_quality/send"))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+
         assert_eq!(
             StatusCode::UNAUTHORIZED,
             authed_client
@@ -230,6 +524,234 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_closure_api() {
+        let key: String = "asdasdasdasd".into();
+        let router = test_router(key.clone(), Arc::new(CommandMock));
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8082));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, build_auth_string(ADMIN_USER, &key));
+        let authed_client = Client::builder().default_headers(headers).build().unwrap();
+        let id = Uuid::default();
+
+        assert_eq!(
+            StatusCode::OK,
+            authed_client
+                .put(format!("http://{socket}/closure/{id}/2000-01-01"))
+                .json(&serde_json::json!({"reason": "HOLIDAY", "note": "New Year"}))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+
+        assert_eq!(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            authed_client
+                .put(format!("http://{socket}/closure/{id}/2000-01-01"))
+                .json(&serde_json::json!({"reason": "NO_REASON"}))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+
+        assert_eq!(
+            StatusCode::OK,
+            authed_client
+                .delete(format!("http://{socket}/closure/{id}/2000-01-01"))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_data_api() {
+        let key: String = "asdasdasdasd".into();
+        let router = test_router(key.clone(), Arc::new(CommandMock));
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8087));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, build_auth_string(ADMIN_USER, &key));
+        let authed_client = Client::builder().default_headers(headers).build().unwrap();
+        let id = Uuid::default();
+
+        let response = authed_client
+            .delete(format!("http://{socket}/client_data/{id}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.text().await.unwrap().contains("1 images"));
+
+        assert_eq!(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            authed_client
+                .delete(format!("http://{socket}/client_data/{FAIL_ID}"))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_job_api() {
+        let key: String = "asdasdasdasd".into();
+        let router = test_router(key.clone(), Arc::new(CommandMock));
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8083));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        assert_eq!(
+            StatusCode::UNAUTHORIZED,
+            reqwest::get(format!("http://{socket}/jobs"))
+                .await
+                .unwrap()
+                .status()
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, build_auth_string(ADMIN_USER, &key));
+        let authed_client = Client::builder().default_headers(headers).build().unwrap();
+
+        let jobs = authed_client
+            .get(format!("http://{socket}/jobs"))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(jobs[0]["name"], JOB_NAME);
+        assert_eq!(jobs[0]["last_run"]["outcome"]["result"], "SUCCESS");
+
+        for (job, status) in [
+            (JOB_NAME, StatusCode::OK),
+            (RUNNING_JOB_NAME, StatusCode::CONFLICT),
+            ("unknown", StatusCode::NOT_FOUND),
+        ] {
+            assert_eq!(
+                status,
+                authed_client
+                    .get(format!("http://{socket}/jobs/{job}/run"))
+                    .send()
+                    .await
+                    .unwrap()
+                    .status()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_key_api() {
+        let key: String = "asdasdasdasd".into();
+        let router = test_router(key.clone(), Arc::new(CommandMock));
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8086));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        assert_eq!(
+            StatusCode::UNAUTHORIZED,
+            reqwest::get(format!("http://{socket}/api_keys"))
+                .await
+                .unwrap()
+                .status()
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, build_auth_string(ADMIN_USER, &key));
+        let authed_client = Client::builder().default_headers(headers).build().unwrap();
+
+        let keys = authed_client
+            .get(format!("http://{socket}/api_keys"))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(keys[0]["ident"], "1234567890");
+        assert!(keys[1]["key"].is_null(), "keys are not revealed");
+
+        let created = authed_client
+            .post(format!("http://{socket}/api_keys"))
+            .json(&serde_json::json!({"description": "new app"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(created.status(), StatusCode::CREATED);
+        let created = created.json::<serde_json::Value>().await.unwrap();
+        assert_eq!(created["key"], "new_generated_key");
+        assert_eq!(created["description"], "new app");
+        assert_eq!(created["scopes"].as_array().unwrap().len(), 4);
+
+        let restricted = authed_client
+            .post(format!("http://{socket}/api_keys"))
+            .json(&serde_json::json!({
+                "description": "voting app",
+                "scopes": ["VOTE"],
+                "expires_at": "2099-01-01T00:00:00Z",
+                "rate_limit": 30
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(restricted["scopes"], serde_json::json!(["VOTE"]));
+        assert_eq!(restricted["rate_limit"], 30);
+        assert!(restricted["expires_at"].is_string());
+
+        let described = authed_client
+            .get(format!("http://{socket}/api_keys/1234567890"))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert!(described["revoked_at"].is_null());
+
+        let revoked = authed_client
+            .delete(format!("http://{socket}/api_keys/1234567890"))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert!(revoked["revoked_at"].is_string());
+
+        assert_eq!(
+            StatusCode::NOT_FOUND,
+            authed_client
+                .delete(format!("http://{socket}/api_keys/unknown"))
+                .send()
+                .await
+                .unwrap()
+                .status()
+        );
+    }
+
+    fn test_router(key: String, command: ArcCommand) -> Router<()> {
+        admin_router(
+            key,
+            command,
+            Arc::new(JobControlMock),
+            Arc::new(ApiKeyManagementMock),
+            ApiKeyStore::new(Arc::new(AuthDataMock)),
+        )
+    }
+
     fn build_auth_string(username: &str, password: &str) -> HeaderValue {
         let auth_string = format!("{username}:{password}");
         let auth_string = base64::engine::general_purpose::STANDARD.encode(auth_string);
@@ -25,7 +25,15 @@ use multer::{parse_boundary, Multipart};
 use sha2::Sha512;
 use thiserror::Error;
 
-use crate::{interface::persistent_data::model::ApiKey, util::Uuid};
+use async_graphql::{ErrorExtensions, InputType};
+use chrono::Local;
+
+use crate::{
+    interface::persistent_data::model::ApiKey,
+    util::{ApiKeyScope, Uuid},
+};
+
+use super::{key_store::ApiKeyStore, replay::ReplayGuard};
 
 pub(super) type AuthResult<T> = Result<T, AuthError>;
 
@@ -43,6 +51,9 @@ pub enum AuthError {
     /// No or invalid authentication provided but the request needs to be authenticated.
     #[error("One of the queries/mutations you requested requires authentication. Your auth info: {0:?} See {url} for more details.", url = AUTH_DOC_URL)]
     MissingOrInvalidAuth(AuthInfo),
+    /// The request is authenticated, but the api key used is not permitted to perform the mutation.
+    #[error("The api key used is not permitted to perform this mutation, it lacks the `{0}` scope. See {url} for more details.", url = AUTH_DOC_URL)]
+    MissingScope(ApiKeyScope),
 }
 
 /// Reasons why authentication failed.
@@ -59,6 +70,55 @@ pub enum AuthFailReason {
     InvalidApiKey,
     /// Provided HMAC hash does not match with request.
     HashNotMatching(Vec<u8>),
+    /// Api key has expired.
+    ExpiredApiKey,
+    /// Api key has been used for more requests than its rate limit allows.
+    RateLimitExceeded,
+    /// Timestamp or nonce was left empty, but legacy requests without them are not accepted.
+    MissingTimestampOrNonce,
+    /// Timestamp of the request deviates from the server time by the given number of seconds, more than the allowed clock skew.
+    TimestampOutOfRange(u64),
+    /// Nonce has already been used in a previous request.
+    NonceReused,
+    /// Whether the nonce has been used before could not be checked.
+    NonceCheckFailed,
+}
+
+impl AuthFailReason {
+    /// Machine-readable identifier of this reason, as returned in graphql errors.
+    pub(super) const fn code(&self) -> &'static str {
+        match self {
+            Self::NoAuthHeader => "NO_AUTH_HEADER",
+            Self::MissingApiIdentOrHash => "MISSING_API_IDENT_OR_HASH",
+            Self::HashNotInBase64 => "HASH_NOT_IN_BASE64",
+            Self::InvalidApiKey => "INVALID_API_KEY",
+            Self::HashNotMatching(_) => "HASH_NOT_MATCHING",
+            Self::ExpiredApiKey => "EXPIRED_API_KEY",
+            Self::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            Self::MissingTimestampOrNonce => "MISSING_TIMESTAMP_OR_NONCE",
+            Self::TimestampOutOfRange(_) => "TIMESTAMP_OUT_OF_RANGE",
+            Self::NonceReused => "NONCE_REUSED",
+            Self::NonceCheckFailed => "NONCE_CHECK_FAILED",
+        }
+    }
+}
+
+impl ErrorExtensions for AuthError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| match self {
+            Self::MissingClientId => e.set("code", "MISSING_CLIENT_ID"),
+            Self::MissingOrInvalidAuth(auth_info) => {
+                e.set("code", "UNAUTHENTICATED");
+                if let Err(reason) = &auth_info.authenticated {
+                    e.set("reason", reason.code());
+                }
+            }
+            Self::MissingScope(scope) => {
+                e.set("code", "FORBIDDEN");
+                e.set("scope", scope.to_value());
+            }
+        })
+    }
 }
 
 /// Structure containing all information necessary for authenticating a client.
@@ -73,6 +133,8 @@ pub struct AuthInfo {
     pub api_ident: String,
     /// User provided HMAC hash for request.
     pub hash: String,
+    /// Mutations the api key used permits, empty if not authenticated.
+    pub scopes: Vec<ApiKeyScope>,
 }
 
 impl Display for AuthInfo {
@@ -103,6 +165,10 @@ pub(super) struct MensaAuthHeader {
     /// SHA-512 hash of all request parameters, the client id and the name of the request.
     /// This hash has to be checked to authenticate a command.
     pub(super) hash: String,
+    /// Time of the request in seconds since the unix epoch, included in the hash. Not sent by older app versions.
+    pub(super) timestamp: Option<i64>,
+    /// Random value only used for a single request, included in the hash. Not sent by older app versions.
+    pub(super) nonce: Option<String>,
 }
 
 impl Credentials for MensaAuthHeader {
@@ -133,10 +199,22 @@ impl IntoResponse for AuthMiddlewareError {
     }
 }
 
+/// State needed for authenticating requests.
+#[derive(Clone)]
+pub(super) struct AuthState {
+    pub(super) body_limit: usize,
+    pub(super) api_keys: ApiKeyStore,
+    pub(super) replay_guard: ReplayGuard,
+}
+
 pub(super) async fn auth_middleware(
     content_type: Option<TypedHeader<ContentType>>,
     auth: Option<TypedHeader<Authorization<MensaAuthHeader>>>,
-    extract::State((body_limit, api_keys)): extract::State<(usize, Vec<ApiKey>)>,
+    extract::State(AuthState {
+        body_limit,
+        api_keys,
+        replay_guard,
+    }): extract::State<AuthState>,
     req: Request<axum::body::Body>,
     next: Next,
 ) -> Result<impl IntoResponse, AuthMiddlewareError> {
@@ -154,14 +232,43 @@ pub(super) async fn auth_middleware(
         _ => &body_bytes,
     };
 
-    let auth = AuthInfo {
-        authenticated: authenticate(auth_header.as_ref(), &api_keys, bytes_to_hash),
+    let keys = api_keys.keys();
+    let api_key = match (
+        authenticate(auth_header.as_ref(), &keys, bytes_to_hash),
+        &auth_header,
+    ) {
+        (Ok(key), Some(auth)) => replay_guard
+            .check(auth.client_id, auth.timestamp, auth.nonce.as_deref())
+            .await
+            .map(|()| key),
+        (Ok(_), None) => unreachable!("authenticated requests have a header"),
+        (Err(e), _) => Err(e),
+    }
+    .and_then(|key| {
+        if api_keys.try_acquire(key) {
+            Ok(key)
+        } else {
+            Err(AuthFailReason::RateLimitExceeded)
+        }
+    });
 
-        client_id: auth_header.as_ref().map(|a| a.client_id),
-        api_ident: auth_header
+    let auth = AuthInfo {
+        scopes: api_key
             .as_ref()
-            .map(|a| a.api_ident.clone())
+            .map(|key| key.permissions.scopes.clone())
             .unwrap_or_default(),
+        client_id: auth_header.as_ref().map(|a| a.client_id),
+        // identify the key the same way regardless of how many characters were provided
+        api_ident: api_key.as_ref().map_or_else(
+            |_| {
+                auth_header
+                    .as_ref()
+                    .map(|a| a.api_ident.clone())
+                    .unwrap_or_default()
+            },
+            |key| key.ident().into(),
+        ),
+        authenticated: api_key.map(|_| ()),
         hash: auth_header
             .as_ref()
             .map(|a| a.hash.clone())
@@ -200,25 +307,29 @@ async fn extract_operation_bytes_from_multipart(
     operations_bytes.ok_or(AuthMiddlewareError::MissingOperationsPart)
 }
 
-fn authenticate(
+/// Checks the hash of the request against the api key identified in the header.
+/// Returns the api key used if valid.
+fn authenticate<'a>(
     info: Option<&MensaAuthHeader>,
-    api_keys: &[ApiKey],
+    api_keys: &'a [ApiKey],
     bytes_to_hash: &[u8],
-) -> Result<(), AuthFailReason> {
+) -> Result<&'a ApiKey, AuthFailReason> {
     let auth = info.ok_or(AuthFailReason::NoAuthHeader)?;
 
     if auth.api_ident.is_empty() || auth.hash.is_empty() {
         return Err(AuthFailReason::MissingApiIdentOrHash);
     }
 
-    let api_key = &api_keys
+    let api_key = api_keys
         .iter()
         .find(|k| k.key.starts_with(&auth.api_ident))
-        .ok_or(AuthFailReason::InvalidApiKey)?
-        .key;
+        .ok_or(AuthFailReason::InvalidApiKey)?;
 
-    let mut hmac =
-        Hmac::<Sha512>::new_from_slice(api_key.as_bytes()).expect("HMAC can take keys of any size");
+    let mut hmac = Hmac::<Sha512>::new_from_slice(api_key.key.as_bytes())
+        .expect("HMAC can take keys of any size");
+    if let (Some(timestamp), Some(nonce)) = (auth.timestamp, &auth.nonce) {
+        hmac.update(signed_prefix(auth.client_id, timestamp, nonce).as_bytes());
+    }
     hmac.update(bytes_to_hash);
     let hash = hmac.finalize().into_bytes().to_vec();
 
@@ -226,15 +337,27 @@ fn authenticate(
         .decode(&auth.hash)
         .map_err(|_| AuthFailReason::HashNotInBase64)?;
 
-    if hash == given_hash {
-        Ok(())
-    } else {
-        Err(AuthFailReason::HashNotMatching(hash))
+    if hash != given_hash {
+        return Err(AuthFailReason::HashNotMatching(hash));
     }
+
+    if api_key.is_expired_at(Local::now()) {
+        return Err(AuthFailReason::ExpiredApiKey);
+    }
+
+    Ok(api_key)
+}
+
+/// Prefix of the hashed bytes signing the client id, timestamp and nonce of a request.
+/// Signing the client id prevents replaying a request with the same nonce under another client id.
+fn signed_prefix(client_id: Uuid, timestamp: i64, nonce: &str) -> String {
+    format!("{client_id}{AUTH_SEPARATOR}{timestamp}{AUTH_SEPARATOR}{nonce}{AUTH_SEPARATOR}")
 }
 
 const AUTH_TYPE: &str = "Mensa";
 const AUTH_SEPARATOR: char = ':';
+/// Maximum length of a nonce, limiting the storage needed for remembering used ones.
+const MAX_NONCE_LENGTH: usize = 64;
 /// Parses and decodes the auth header into an [`AuthInfo`]
 #[must_use]
 fn read_auth_from_header(header: &str) -> Option<MensaAuthHeader> {
@@ -255,10 +378,21 @@ fn read_auth_from_header(header: &str) -> Option<MensaAuthHeader> {
     let api_ident = *parts.get(1)?;
     let hash = *parts.get(2)?;
 
+    // older app versions do not send timestamp and nonce
+    let (timestamp, nonce) = match parts.get(3..) {
+        Some([timestamp, nonce]) if !nonce.is_empty() && nonce.len() <= MAX_NONCE_LENGTH => {
+            (Some(timestamp.parse().ok()?), Some((*nonce).into()))
+        }
+        Some([]) => (None, None),
+        _ => return None,
+    };
+
     Some(MensaAuthHeader {
         client_id,
         api_ident: api_ident.into(),
         hash: hash.into(),
+        timestamp,
+        nonce,
     })
 }
 
@@ -267,6 +401,7 @@ mod tests {
     #![allow(clippy::unwrap_used)]
 
     use super::*;
+    use crate::interface::persistent_data::model::ApiKeyPermissions;
     use std::str::FromStr;
 
     #[test]
@@ -285,6 +420,31 @@ mod tests {
         assert_eq!(auth_info.hash, hash, "wrong hash");
     }
 
+    #[test]
+    fn test_auth_info_parsing_timestamp_and_nonce() {
+        let client_id = Uuid::new_v4();
+        let encode = |auth: String| {
+            format!(
+                "{AUTH_TYPE} {}",
+                general_purpose::STANDARD.encode(format!("{client_id}:{auth}"))
+            )
+        };
+
+        let auth_info =
+            read_auth_from_header(&encode("abc:1234:1700000000:xyz".into())).expect("valid");
+        assert_eq!(auth_info.timestamp, Some(1_700_000_000));
+        assert_eq!(auth_info.nonce.as_deref(), Some("xyz"));
+
+        assert!(read_auth_from_header(&encode("abc:1234:1700000000".into())).is_none());
+        assert!(read_auth_from_header(&encode("abc:1234:yesterday:xyz".into())).is_none());
+        assert!(read_auth_from_header(&encode("abc:1234:1700000000:".into())).is_none());
+        assert!(read_auth_from_header(&encode(format!(
+            "abc:1234:1700000000:{}",
+            "n".repeat(MAX_NONCE_LENGTH + 1)
+        )))
+        .is_none());
+    }
+
     #[test]
     fn test_auth_info_parsing_client_only() {
         let api_indent = "";
@@ -312,6 +472,8 @@ mod tests {
             client_id: Uuid::try_from("1d75d380-cf07-4edb-9046-a2d981bc219d").unwrap(),
             api_ident: "abc".into(),
             hash: "123".into(),
+            timestamp: None,
+            nonce: None,
         });
         assert_eq!(expected_auth_info, auth_info);
     }
@@ -385,19 +547,108 @@ mod tests {
             client_id: Uuid::from_str("e997c2e3-68e1-4b6d-b328-4adcd573c834").unwrap(),
             api_ident: "123".into(),
             hash: hash64,
+            timestamp: None,
+            nonce: None,
         };
 
         let key_list = &[
             ApiKey {
                 description: String::new(),
                 key: String::from("abc"),
+                created_at: Local::now(),
+                revoked_at: None,
+                permissions: ApiKeyPermissions::default(),
             },
             ApiKey {
                 description: String::new(),
                 key: api_key.into(),
+                created_at: Local::now(),
+                revoked_at: None,
+                permissions: ApiKeyPermissions::default(),
             },
         ];
 
         assert!(authenticate(Some(&header), key_list, bytes).is_ok());
     }
+
+    #[test]
+    fn test_authenticate_expired() {
+        let bytes = &[1u8, 2u8, 3u8];
+        let api_key = "1234567890";
+
+        let hash = Hmac::<Sha512>::new_from_slice(api_key.as_bytes())
+            .unwrap()
+            .chain_update(bytes)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        let header = MensaAuthHeader {
+            client_id: Uuid::default(),
+            api_ident: "123".into(),
+            hash: base64::prelude::BASE64_STANDARD.encode(hash),
+            timestamp: None,
+            nonce: None,
+        };
+
+        let mut key = ApiKey {
+            description: String::new(),
+            key: api_key.into(),
+            created_at: Local::now(),
+            revoked_at: None,
+            permissions: ApiKeyPermissions {
+                expires_at: Some(Local::now() + chrono::Duration::hours(1)),
+                ..Default::default()
+            },
+        };
+        assert!(authenticate(Some(&header), &[key.clone()], bytes).is_ok());
+
+        key.permissions.expires_at = Some(Local::now() - chrono::Duration::hours(1));
+        assert!(matches!(
+            authenticate(Some(&header), &[key], bytes),
+            Err(AuthFailReason::ExpiredApiKey)
+        ));
+    }
+
+    #[test]
+    fn test_authenticate_signed_timestamp() {
+        let bytes = &[1u8, 2u8, 3u8];
+        let api_key = "1234567890";
+
+        let hash = Hmac::<Sha512>::new_from_slice(api_key.as_bytes())
+            .unwrap()
+            .chain_update(b"00000000-0000-0000-0000-000000000000:1700000000:xyz:")
+            .chain_update(bytes)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        let mut header = MensaAuthHeader {
+            client_id: Uuid::default(),
+            api_ident: "123".into(),
+            hash: base64::prelude::BASE64_STANDARD.encode(hash),
+            timestamp: Some(1_700_000_000),
+            nonce: Some("xyz".into()),
+        };
+        let keys = &[ApiKey {
+            description: String::new(),
+            key: api_key.into(),
+            created_at: Local::now(),
+            revoked_at: None,
+            permissions: ApiKeyPermissions::default(),
+        }];
+        assert!(authenticate(Some(&header), keys, bytes).is_ok());
+
+        header.timestamp = Some(1_700_000_001);
+        assert!(matches!(
+            authenticate(Some(&header), keys, bytes),
+            Err(AuthFailReason::HashNotMatching(_))
+        ));
+
+        // the hash can not be reused with another client id
+        header.timestamp = Some(1_700_000_000);
+        header.client_id = Uuid::from_u128(1);
+        assert!(matches!(
+            authenticate(Some(&header), keys, bytes),
+            Err(AuthFailReason::HashNotMatching(_))
+        ));
+    }
 }
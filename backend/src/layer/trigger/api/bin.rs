@@ -1,4 +1,4 @@
-use std::env::temp_dir;
+use std::{env::temp_dir, time::Duration};
 
 use dotenvy::dotenv;
 use mensa_app_backend::layer::{
@@ -36,6 +36,18 @@ async fn main() {
         rate_limit: None,
         max_body_size: 10 << 20,
         admin_key: "admin".into(),
+        max_parse_age_hours: 48,
+        api_key_refresh_interval: Duration::from_mins(1),
+        max_clock_skew: Duration::from_mins(5),
+        allow_legacy_auth: true,
+        request_rate_limit: None,
+        mutation_rate_limit: None,
+        upload_rate_limit: None,
+        rate_limit_ip_header: None,
+        max_query_depth: 10,
+        max_query_complexity: 10_000,
+        persisted_query_cache_size: 100,
+        cache_max_age: Duration::from_mins(1),
     };
 
     let image_pre_info = ImagePreprocessingInfo {
@@ -55,6 +67,9 @@ async fn main() {
         )
         .expect("could not create command mock"),
         AuthDataMock,
+        mock::ApiKeyManagementMock,
+        mock::HealthDataMock,
+        mock::JobControlMock,
     )
     .await;
     server.start().await;
@@ -0,0 +1,179 @@
+//! Machine-readable error codes for graphql errors.
+//!
+//! Each error returned to clients gets the extension `code`, a stable identifier clients can rely on instead of the error message.
+//! Some errors provide further details as additional extensions, e.g. why an image was rejected.
+
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
+    ErrorExtensionValues, ErrorExtensions, Response, ServerError,
+};
+
+use crate::interface::{
+    api_command::CommandError, image_validation::ImageValidationError, persistent_data::DataError,
+};
+
+use super::{auth::AuthError, util::UploadError};
+
+/// Code of errors caused by the server, which the client can not do anything about.
+const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+/// Code of errors caused by an invalid request, e.g. a query with invalid syntax, unknown fields or invalid arguments.
+const BAD_REQUEST: &str = "BAD_REQUEST";
+/// Message of the error returned by [`async_graphql::extensions::ApolloPersistedQueries`] if the hash of a persisted query is unknown.
+const PERSISTED_QUERY_NOT_FOUND_MESSAGE: &str = "PersistedQueryNotFound";
+
+/// Creates extensions containing only the given code.
+pub(super) fn code_extensions(code: &str) -> ErrorExtensionValues {
+    let mut extensions = ErrorExtensionValues::default();
+    extensions.set("code", code);
+    extensions
+}
+
+impl ErrorExtensions for DataError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| match self {
+            Self::NoSuchItem => e.set("code", "NOT_FOUND"),
+            // referenced items like meals or images not existing
+            Self::InternalError(error)
+                if error
+                    .as_database_error()
+                    .is_some_and(sqlx::error::DatabaseError::is_foreign_key_violation) =>
+            {
+                e.set("code", "NOT_FOUND");
+            }
+            _ => e.set("code", INTERNAL_ERROR),
+        })
+    }
+}
+
+impl ErrorExtensions for ImageValidationError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| match self {
+            Self::SafeSearchRejectionError(category, level, max_level) => {
+                e.set("code", "IMAGE_REJECTED");
+                e.set("category", category.as_str());
+                e.set("level", *level);
+                e.set("maxLevel", *max_level);
+            }
+            Self::GeminiRejectionError(reason) => {
+                e.set("code", "IMAGE_REJECTED");
+                e.set("reason", reason.as_str());
+            }
+            Self::ImageEncodeFailed(_) => e.set("code", "INVALID_IMAGE"),
+            _ => e.set("code", "IMAGE_VALIDATION_FAILED"),
+        })
+    }
+}
+
+impl ErrorExtensions for CommandError {
+    fn extend(&self) -> async_graphql::Error {
+        let code = match self {
+            Self::DataError(error) => return error.extend(),
+            Self::ImageValidationError(error) => return error.extend(),
+            Self::BadAuth(_) | Self::NoAuth => "UNAUTHENTICATED",
+            Self::NotUploader(_) => "FORBIDDEN",
+            Self::ImagePreprocessingError(_) => "INVALID_IMAGE",
+            Self::ImageStorageError(_) | Self::AdminNotificationError(_) => INTERNAL_ERROR,
+        };
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| e.set("code", code))
+    }
+}
+
+/// Determines the extensions of an error from its source, if it is one of the errors known to have codes.
+fn source_extensions(error: &ServerError) -> Option<ErrorExtensionValues> {
+    let extended = error
+        .source::<CommandError>()
+        .map(ErrorExtensions::extend)
+        .or_else(|| error.source::<DataError>().map(ErrorExtensions::extend))
+        .or_else(|| error.source::<AuthError>().map(ErrorExtensions::extend))
+        .or_else(|| error.source::<UploadError>().map(ErrorExtensions::extend))?;
+    extended.extensions
+}
+
+/// Determines the extensions of an error without extensions.
+///
+/// Errors without a known source but also without any source are raised by async-graphql itself when rejecting a request,
+/// e.g. because it could not be parsed or validated, so they are caused by the client.
+fn fallback_extensions(error: &ServerError) -> ErrorExtensionValues {
+    source_extensions(error).unwrap_or_else(|| {
+        let code = if error.message == PERSISTED_QUERY_NOT_FOUND_MESSAGE {
+            "PERSISTED_QUERY_NOT_FOUND"
+        } else if error.source.is_none() {
+            BAD_REQUEST
+        } else {
+            INTERNAL_ERROR
+        };
+        code_extensions(code)
+    })
+}
+
+/// Extension adding codes to all returned errors which do not have extensions yet.
+pub(super) struct ErrorCodes;
+
+impl ExtensionFactory for ErrorCodes {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(Self)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ErrorCodes {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let mut response = next.run(ctx).await;
+        for error in response
+            .errors
+            .iter_mut()
+            .filter(|error| error.extensions.is_none())
+        {
+            error.extensions = Some(fallback_extensions(error));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::{
+        interface::{
+            api_command::CommandError, image_validation::ImageValidationError,
+            persistent_data::DataError,
+        },
+        util::Uuid,
+    };
+
+    fn extension(error: &impl ErrorExtensions, name: &str) -> Option<Value> {
+        error.extend().extensions.unwrap().get(name).cloned()
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let not_found = CommandError::DataError(DataError::NoSuchItem);
+        assert_eq!(
+            extension(&not_found, "code"),
+            Some(Value::from("NOT_FOUND"))
+        );
+        assert_eq!(
+            extension(&CommandError::NoAuth, "code"),
+            Some(Value::from("UNAUTHENTICATED"))
+        );
+        assert_eq!(
+            extension(&CommandError::NotUploader(Uuid::default()), "code"),
+            Some(Value::from("FORBIDDEN"))
+        );
+
+        let rejected = CommandError::ImageValidationError(
+            ImageValidationError::SafeSearchRejectionError("adult".into(), 4, 2),
+        );
+        assert_eq!(
+            extension(&rejected, "code"),
+            Some(Value::from("IMAGE_REJECTED"))
+        );
+        assert_eq!(extension(&rejected, "category"), Some(Value::from("adult")));
+        assert_eq!(extension(&rejected, "level"), Some(Value::from(4)));
+        assert_eq!(extension(&rejected, "maxLevel"), Some(Value::from(2)));
+    }
+}
@@ -0,0 +1,337 @@
+//! Health api for checking whether the server is alive and ready to handle requests.
+
+use std::{path::PathBuf, sync::Arc};
+
+use axum::{debug_handler, extract::State, response::IntoResponse, routing::get, Json, Router};
+use chrono::{DateTime, Duration, Local};
+use hyper::StatusCode;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::{interface::persistent_data::HealthDataAccess, util::Uuid};
+
+use super::admin::ArcJobControl;
+
+pub(super) type ArcHealthData = Arc<dyn HealthDataAccess>;
+
+/// Prefix of the temporary files written for checking whether the image directory is writable.
+const IMAGE_DIR_CHECK_PREFIX: &str = ".health_check_";
+
+#[derive(Clone)]
+struct HealthState {
+    health_data: ArcHealthData,
+    job_control: ArcJobControl,
+    image_dir: PathBuf,
+    max_parse_age: Duration,
+}
+
+pub(super) fn health_router(
+    health_data: ArcHealthData,
+    job_control: ArcJobControl,
+    image_dir: PathBuf,
+    max_parse_age_hours: u32,
+) -> Router<()> {
+    let state = HealthState {
+        health_data,
+        job_control,
+        image_dir,
+        max_parse_age: Duration::hours(max_parse_age_hours.into()),
+    };
+    Router::new()
+        .route("/live", get(live))
+        .route("/ready", get(ready))
+        .with_state(state)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Alive,
+    Ready,
+    NotReady,
+}
+
+/// Result of checking a single dependency.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Check {
+    status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Ok,
+    Failed,
+}
+
+impl Check {
+    const fn ok() -> Self {
+        Self {
+            status: CheckStatus::Ok,
+            message: None,
+        }
+    }
+
+    const fn ok_with(message: String) -> Self {
+        Self {
+            status: CheckStatus::Ok,
+            message: Some(message),
+        }
+    }
+
+    const fn failed(message: String) -> Self {
+        Self {
+            status: CheckStatus::Failed,
+            message: Some(message),
+        }
+    }
+
+    fn is_ok(&self) -> bool {
+        self.status == CheckStatus::Ok
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Checks {
+    database: Check,
+    scheduler: Check,
+    last_parse: Check,
+    image_dir: Check,
+}
+
+#[derive(Debug, Serialize)]
+struct Liveness {
+    status: Status,
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    status: Status,
+    checks: Checks,
+}
+
+#[debug_handler]
+async fn live() -> Json<Liveness> {
+    Json(Liveness {
+        status: Status::Alive,
+    })
+}
+
+#[debug_handler]
+async fn ready(State(state): State<HealthState>) -> impl IntoResponse {
+    let checks = Checks {
+        database: check_database(state.health_data.as_ref()).await,
+        scheduler: check_scheduler(state.job_control.is_running()),
+        last_parse: check_last_parse(
+            state.health_data.as_ref(),
+            state.max_parse_age,
+            Local::now(),
+        )
+        .await,
+        image_dir: check_image_dir(&state.image_dir).await,
+    };
+
+    let ready = [
+        &checks.database,
+        &checks.scheduler,
+        &checks.last_parse,
+        &checks.image_dir,
+    ]
+    .iter()
+    .all(|check| check.is_ok());
+
+    if ready {
+        (
+            StatusCode::OK,
+            Json(Readiness {
+                status: Status::Ready,
+                checks,
+            }),
+        )
+    } else {
+        warn!("Readiness check failed: {checks:?}");
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(Readiness {
+                status: Status::NotReady,
+                checks,
+            }),
+        )
+    }
+}
+
+// The response is public, so details of failures only get logged.
+
+async fn check_database(health_data: &dyn HealthDataAccess) -> Check {
+    match health_data.check_connection().await {
+        Ok(()) => Check::ok(),
+        Err(e) => {
+            warn!("Database not reachable: {e}");
+            Check::failed("database not reachable".into())
+        }
+    }
+}
+
+fn check_scheduler(running: bool) -> Check {
+    if running {
+        Check::ok()
+    } else {
+        Check::failed("scheduler is not running".into())
+    }
+}
+
+/// Checks whether the meal plan got parsed successfully within `max_age`.
+/// Successful parses are stored in the database, so parses of other instances and before a restart count as well.
+async fn check_last_parse(
+    health_data: &dyn HealthDataAccess,
+    max_age: Duration,
+    now: DateTime<Local>,
+) -> Check {
+    let last_success = match health_data.get_last_parse_success().await {
+        Ok(last_success) => last_success,
+        Err(e) => {
+            warn!("Could not load last successful parse: {e}");
+            return Check::failed("last successful parse unknown".into());
+        }
+    };
+    parse_age_check(last_success, max_age, now)
+}
+
+fn parse_age_check(
+    last_success: Option<DateTime<Local>>,
+    max_age: Duration,
+    now: DateTime<Local>,
+) -> Check {
+    match last_success {
+        None => Check::failed("no successful parse recorded".into()),
+        Some(time) if now - time > max_age => Check::failed(format!(
+            "last successful parse at {time} is older than {} hours",
+            max_age.num_hours()
+        )),
+        Some(time) => Check::ok_with(format!("last successful parse at {time}")),
+    }
+}
+
+/// Checks whether files can be created in the image directory by writing and removing a temporary file.
+async fn check_image_dir(image_dir: &std::path::Path) -> Check {
+    let path = image_dir.join(format!("{IMAGE_DIR_CHECK_PREFIX}{}", Uuid::new_v4()));
+    if let Err(e) = tokio::fs::write(&path, []).await {
+        warn!(
+            "Image directory {} is not writable: {e}",
+            image_dir.display()
+        );
+        return Check::failed("image directory not writable".into());
+    }
+    match tokio::fs::remove_file(&path).await {
+        Ok(()) => Check::ok(),
+        Err(e) => {
+            warn!(
+                "Could not remove file {} from image directory: {e}",
+                path.display()
+            );
+            Check::failed("image directory not writable".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{
+        env::temp_dir,
+        future::IntoFuture,
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        sync::Arc,
+    };
+
+    use chrono::{Duration, Local};
+    use hyper::StatusCode;
+    use serde_json::Value;
+
+    use super::{check_image_dir, health_router, parse_age_check, CheckStatus};
+    use crate::{
+        layer::trigger::api::mock::{HealthDataMock, JobControlMock},
+        util::Uuid,
+    };
+
+    #[test]
+    fn test_parse_age_check() {
+        let max_age = Duration::hours(24);
+        let now = Local::now();
+
+        let check = parse_age_check(Some(now - Duration::hours(2)), max_age, now);
+        assert_eq!(check.status, CheckStatus::Ok);
+
+        let check = parse_age_check(Some(now - Duration::hours(30)), max_age, now);
+        assert_eq!(check.status, CheckStatus::Failed);
+
+        // an instance which never parsed successfully itself must not be ready without any parse at all
+        let check = parse_age_check(None, max_age, now);
+        assert_eq!(check.status, CheckStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_check_image_dir() {
+        let dir = temp_dir().join(format!("health_check_{}", Uuid::new_v4()));
+        assert_eq!(check_image_dir(&dir).await.status, CheckStatus::Failed);
+
+        tokio::fs::create_dir(&dir).await.unwrap();
+        assert_eq!(check_image_dir(&dir).await.status, CheckStatus::Ok);
+        // no files are left behind
+        assert!(std::fs::read_dir(&dir).unwrap().next().is_none());
+        tokio::fs::remove_dir(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_api() {
+        let router = health_router(
+            Arc::new(HealthDataMock),
+            Arc::new(JobControlMock),
+            temp_dir(),
+            48,
+        );
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8084));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let live = reqwest::get(format!("http://{socket}/live")).await.unwrap();
+        assert_eq!(live.status(), StatusCode::OK);
+        let live: Value = live.json().await.unwrap();
+        assert_eq!(live["status"], "alive");
+
+        let ready = reqwest::get(format!("http://{socket}/ready"))
+            .await
+            .unwrap();
+        assert_eq!(ready.status(), StatusCode::OK);
+        let ready: Value = ready.json().await.unwrap();
+        assert_eq!(ready["status"], "ready");
+        for check in ["database", "scheduler", "last_parse", "image_dir"] {
+            assert_eq!(ready["checks"][check]["status"], "ok", "check {check}");
+        }
+
+        let router = health_router(
+            Arc::new(HealthDataMock),
+            Arc::new(JobControlMock),
+            temp_dir().join("does_not_exist"),
+            48,
+        );
+        let socket = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8085));
+        let listener = tokio::net::TcpListener::bind(socket).await.unwrap();
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let ready = reqwest::get(format!("http://{socket}/ready"))
+            .await
+            .unwrap();
+        assert_eq!(ready.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let ready: Value = ready.json().await.unwrap();
+        assert_eq!(ready["status"], "not_ready");
+        assert_eq!(ready["checks"]["image_dir"]["status"], "failed");
+        // no internal details like paths get exposed
+        assert_eq!(
+            ready["checks"]["image_dir"]["message"],
+            "image directory not writable"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Http caching of graphql queries sent via GET.
+//!
+//! Responses to GET requests without authorization header are the same for all callers,
+//! so they can be cached by clients, CDNs and reverse proxies.
+//! Their `ETag` is derived from the time of the last meal plan update, including ratings and images, the current date and the server version,
+//! so cached responses can be revalidated cheaply: if nothing changed, the query is not executed at all.
+//! The date is included as meal statistics, image ranks and the available meal plan days change daily without any update.
+//! Mutations are not allowed via GET.
+
+use std::{sync::Arc, time::Duration};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery},
+    parser::types::{ExecutableDocument, OperationType},
+    Response, ServerError, ServerResult, Variables,
+};
+use chrono::{DateTime, Local};
+use hyper::header::{HeaderValue, CACHE_CONTROL, ETAG, VARY};
+use tracing::warn;
+
+use crate::util::Date;
+
+use super::{error::code_extensions, util::DataBox};
+
+/// `Cache-Control` value of responses which must not be cached.
+const NO_STORE: &str = "no-store";
+
+/// Marks a request as sent via http GET.
+#[derive(Debug, Clone)]
+pub(super) struct GetRequest {
+    /// Whether the response does not depend on the caller and therefore can be cached publicly.
+    pub(super) cacheable: bool,
+    /// Value of the `If-None-Match` header, if any.
+    pub(super) if_none_match: Option<String>,
+}
+
+impl GetRequest {
+    /// Checks whether the `If-None-Match` header matches the given entity tag, meaning the client already has the current response.
+    pub(super) fn matches(&self, etag: &str) -> bool {
+        self.if_none_match.as_deref().is_some_and(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+        })
+    }
+}
+
+/// Entity tag of all cacheable responses on `today` while the meal plan was last updated at `last_update`.
+fn entity_tag(today: Date, last_update: DateTime<Local>) -> String {
+    format!(
+        "\"{}-{today}-{}\"",
+        env!("CARGO_PKG_VERSION"),
+        last_update.timestamp_millis()
+    )
+}
+
+/// Extension rejecting mutations in GET requests and adding cache headers to their responses.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct HttpCache {
+    /// Time for which responses may be used without revalidation.
+    pub(super) max_age: Duration,
+}
+
+impl ExtensionFactory for HttpCache {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(HttpCacheExtension {
+            max_age: self.max_age,
+        })
+    }
+}
+
+struct HttpCacheExtension {
+    max_age: Duration,
+}
+
+#[async_trait::async_trait]
+impl Extension for HttpCacheExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        let has_mutation = document
+            .operations
+            .iter()
+            .any(|(_, operation)| operation.node.ty == OperationType::Mutation);
+        if has_mutation && ctx.data_opt::<GetRequest>().is_some() {
+            let mut error = ServerError::new(
+                "Mutations are not allowed in GET requests, use POST instead.",
+                None,
+            );
+            error.extensions = Some(code_extensions("METHOD_NOT_ALLOWED"));
+            return Err(error);
+        }
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let Some(request) = ctx.data_opt::<GetRequest>() else {
+            return next.run(ctx, operation_name).await;
+        };
+
+        let etag = if request.cacheable {
+            match ctx
+                .data_unchecked::<DataBox>()
+                .get_last_meal_plan_update()
+                .await
+            {
+                Ok(last_update) => Some(entity_tag(Local::now().date_naive(), last_update)),
+                Err(e) => {
+                    warn!("Could not determine last meal plan update for caching: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut response = match &etag {
+            // the client already has the current response, which gets indicated by the returned entity tag
+            Some(etag) if request.matches(etag) => Response::default(),
+            _ => next.run(ctx, operation_name).await,
+        };
+
+        let headers = &mut response.http_headers;
+        headers.insert(VARY, HeaderValue::from_static("Authorization"));
+        match etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) {
+            Some(etag) if response.errors.is_empty() => {
+                headers.insert(ETAG, etag);
+                headers.insert(
+                    CACHE_CONTROL,
+                    format!("public, max-age={}", self.max_age.as_secs())
+                        .parse()
+                        .expect("valid header value"),
+                );
+            }
+            _ => {
+                headers.insert(CACHE_CONTROL, HeaderValue::from_static(NO_STORE));
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use chrono::{Local, TimeZone};
+
+    use crate::util::Date;
+
+    use super::{entity_tag, GetRequest};
+
+    #[test]
+    fn test_if_none_match() {
+        let today = Date::from_ymd_opt(2024, 1, 2).unwrap();
+        let etag = entity_tag(today, Local.timestamp_millis_opt(1000).unwrap());
+        let request = |if_none_match: Option<&str>| GetRequest {
+            cacheable: true,
+            if_none_match: if_none_match.map(Into::into),
+        };
+
+        assert!(!request(None).matches(&etag));
+        assert!(request(Some(&etag)).matches(&etag));
+        assert!(request(Some(&format!("W/{etag}"))).matches(&etag));
+        assert!(request(Some(&format!("\"other\", {etag}"))).matches(&etag));
+        assert!(request(Some("*")).matches(&etag));
+        assert!(!request(Some("\"other\"")).matches(&etag));
+
+        let later = entity_tag(today, Local.timestamp_millis_opt(2000).unwrap());
+        assert!(!request(Some(&etag)).matches(&later));
+
+        // statistics and ranks change at midnight even without any update
+        let tomorrow = entity_tag(
+            today.succ_opt().unwrap(),
+            Local.timestamp_millis_opt(1000).unwrap(),
+        );
+        assert_ne!(etag, tomorrow);
+        assert!(!request(Some(&etag)).matches(&tomorrow));
+    }
+}
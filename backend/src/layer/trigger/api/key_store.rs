@@ -0,0 +1,146 @@
+//! Cache of the api keys valid for authenticating requests, kept up to date with the database.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::interface::persistent_data::{model::ApiKey, AuthDataAccess, Result};
+
+/// Time window the rate limit of an api key applies to.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_mins(1);
+
+/// Valid api keys, reloaded regularly and after changes made through the admin api.
+/// This way, keys created or revoked by other instances or the command line get picked up without a restart.
+///
+/// Additionally, this counts the requests made with each key to enforce their rate limits.
+#[derive(Clone)]
+pub(super) struct ApiKeyStore {
+    auth_data: Arc<dyn AuthDataAccess>,
+    keys: Arc<RwLock<Arc<Vec<ApiKey>>>>,
+    /// Start of the current rate limit window and number of requests in it, per key.
+    usage: Arc<Mutex<HashMap<String, (Instant, u32)>>>,
+}
+
+impl ApiKeyStore {
+    pub(super) fn new(auth_data: Arc<dyn AuthDataAccess>) -> Self {
+        Self {
+            auth_data,
+            keys: Arc::default(),
+            usage: Arc::default(),
+        }
+    }
+
+    /// Counts a request made with `key`.
+    /// Returns whether the request is within the rate limit of the key.
+    pub(super) fn try_acquire(&self, key: &ApiKey) -> bool {
+        let Some(limit) = key.permissions.rate_limit else {
+            return true;
+        };
+
+        let now = Instant::now();
+        let mut usage = self.usage.lock().expect("api key usage lock poisoned");
+        let (window_start, count) = usage.entry(key.key.clone()).or_insert((now, 0));
+        if now.duration_since(*window_start) >= RATE_LIMIT_WINDOW {
+            *window_start = now;
+            *count = 0;
+        }
+
+        let allowed = *count < limit;
+        if allowed {
+            *count += 1;
+        }
+        drop(usage);
+        allowed
+    }
+
+    /// Returns the currently valid api keys.
+    pub(super) fn keys(&self) -> Arc<Vec<ApiKey>> {
+        self.keys.read().expect("api key lock poisoned").clone()
+    }
+
+    /// Loads the valid api keys from the database.
+    pub(super) async fn reload(&self) -> Result<()> {
+        let keys = self.auth_data.get_api_keys().await?;
+        debug!("Loaded {} valid api keys", keys.len());
+        self.usage
+            .lock()
+            .expect("api key usage lock poisoned")
+            .retain(|key, _| keys.iter().any(|k| &k.key == key));
+        *self.keys.write().expect("api key lock poisoned") = Arc::new(keys);
+        Ok(())
+    }
+
+    /// Starts a background task reloading the api keys every `interval`.
+    pub(super) fn spawn_refresh(&self, interval: Duration) -> JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            // the keys got loaded on creation
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.reload().await {
+                    warn!("Could not reload api keys, keeping previous ones: {e}");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        interface::persistent_data::model::{ApiKey, ApiKeyPermissions},
+        layer::{
+            logic::api_key_management::mocks::AuthDatabaseMock,
+            trigger::api::key_store::ApiKeyStore,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_reload() {
+        let store = ApiKeyStore::new(Arc::new(AuthDatabaseMock::with_keys(&["abc", "def"])));
+        assert!(store.keys().is_empty());
+
+        store.reload().await.unwrap();
+        assert_eq!(store.keys().len(), 2);
+
+        store.auth_data.revoke_api_key("abc").await.unwrap();
+        let refresh = store.spawn_refresh(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        refresh.abort();
+
+        assert_eq!(store.keys().len(), 1);
+        assert_eq!(store.keys()[0].key, "def");
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let store = ApiKeyStore::new(Arc::new(AuthDatabaseMock::default()));
+        let mut key = ApiKey {
+            key: "abc".into(),
+            description: String::new(),
+            created_at: chrono::Local::now(),
+            revoked_at: None,
+            permissions: ApiKeyPermissions::default(),
+        };
+        assert!((0..100).all(|_| store.try_acquire(&key)));
+
+        key.permissions.rate_limit = Some(3);
+        let other = ApiKey {
+            key: "def".into(),
+            ..key.clone()
+        };
+        assert!((0..3).all(|_| store.try_acquire(&key)));
+        assert!(!store.try_acquire(&key));
+        assert!(store.try_acquire(&other), "limits are per key");
+    }
+}
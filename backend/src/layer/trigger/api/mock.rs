@@ -1,17 +1,26 @@
-//! This crate contains mocks of [`RequestDataAccess`] and [`Command`] for testing.
+//! This crate contains mocks of [`RequestDataAccess`], [`Command`], [`ApiKeyManagement`] and [`JobControl`] for testing.
 #![allow(missing_docs)]
 
 use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
 use uuid::Uuid;
 
 use crate::interface::persistent_data::model::EnvironmentInfo;
-use crate::util::{Additive, Allergen, Date, FoodType, Price, ReportReason};
+use crate::util::{
+    Additive, Allergen, ClosureReason, Date, ErasureRequester, FoodType, Price, ReportReason,
+};
 use crate::{
     interface::{
+        admin_notification::DataQualityReport,
         api_command::{Command, Result as CommandResult},
+        api_key_management::{ApiKeyError, ApiKeyManagement, Result as ApiKeyResult},
+        job_control::{JobControl, JobError, JobOutcome, JobRun, JobStatus, Result as JobResult},
         persistent_data::{
-            model::{ApiKey, Canteen, Image, Line, Meal, Side},
-            AuthDataAccess, RequestDataAccess, Result as DataResult,
+            model::{
+                ApiKey, ApiKeyPermissions, Canteen, Closure, DataErasure, Image, ImageReport,
+                ImageVote, Line, Meal, MealRating, Pagination, Side, UploadedImage,
+            },
+            AuthDataAccess, HealthDataAccess, RequestDataAccess, Result as DataResult,
         },
     },
     util::NutritionData,
@@ -47,6 +56,24 @@ impl RequestDataAccess for RequestDatabaseMock {
         Ok(vec![canteen1, canteen2, canteen3])
     }
 
+    async fn get_closures(
+        &self,
+        canteen_id: Uuid,
+        from: Date,
+        _to: Date,
+    ) -> DataResult<Vec<Closure>> {
+        Ok(vec![Closure {
+            canteen_id,
+            date: from,
+            reason: ClosureReason::Holiday,
+            note: Some("dummy_note".to_string()),
+        }])
+    }
+
+    async fn has_meal_plan(&self, _canteen_id: Uuid, _date: Date) -> DataResult<bool> {
+        Ok(false)
+    }
+
     async fn get_line(&self, _id: crate::util::Uuid) -> DataResult<Option<Line>> {
         let line = Line {
             id: Uuid::parse_str("993cc4f4-8d32-491a-8e19-e9a7a6b6d31e").expect(INVALID_UUID),
@@ -273,6 +300,10 @@ impl RequestDataAccess for RequestDatabaseMock {
         Ok(vec![Allergen::Pi, Allergen::Hf, Allergen::Gl])
     }
 
+    async fn get_unknown_codes(&self, _food_id: Uuid) -> DataResult<Vec<String>> {
+        Ok(vec!["GEL".into()])
+    }
+
     async fn get_nutrition_data(&self, _food_id: Uuid) -> DataResult<Option<NutritionData>> {
         Ok(Some(NutritionData {
             energy: 1,
@@ -300,8 +331,62 @@ impl RequestDataAccess for RequestDatabaseMock {
             max_rating: 7,
         }))
     }
+
+    async fn get_last_meal_plan_update(&self) -> DataResult<DateTime<Local>> {
+        Ok(MEAL_PLAN_UPDATE.with_timezone(&Local))
+    }
+
+    async fn get_images_of_user(
+        &self,
+        _client_id: Uuid,
+        _page: Pagination,
+    ) -> DataResult<Vec<UploadedImage>> {
+        let image = Image {
+            id: Uuid::parse_str("1aa73d5d-1701-4975-aa3c-1422a8bc10e8").expect(INVALID_UUID),
+            meal_id: Uuid::parse_str("4ab922a0-1622-4813-98a7-954272f74b5c").expect(INVALID_UUID),
+            ..Default::default()
+        };
+        Ok(vec![UploadedImage {
+            image,
+            visible: false,
+        }])
+    }
+
+    async fn get_ratings_of_user(
+        &self,
+        _client_id: Uuid,
+        _page: Pagination,
+    ) -> DataResult<Vec<MealRating>> {
+        Ok(vec![MealRating {
+            meal_id: Uuid::parse_str("4ab922a0-1622-4813-98a7-954272f74b5c").expect(INVALID_UUID),
+            rating: 4,
+        }])
+    }
+
+    async fn get_image_votes_of_user(&self, _client_id: Uuid) -> DataResult<Vec<ImageVote>> {
+        Ok(vec![ImageVote {
+            image_id: Uuid::parse_str("1aa73d5d-1701-4975-aa3c-1422a8bc10e8").expect(INVALID_UUID),
+            upvote: true,
+        }])
+    }
+
+    async fn get_reports_of_user(
+        &self,
+        _client_id: Uuid,
+        _page: Pagination,
+    ) -> DataResult<Vec<ImageReport>> {
+        Ok(vec![ImageReport {
+            image_id: Uuid::parse_str("1aa73d5d-1701-4975-aa3c-1422a8bc10e8").expect(INVALID_UUID),
+            reason: ReportReason::Advert,
+            report_date: Date::default(),
+        }])
+    }
 }
 
+/// Time of the last meal plan update returned by [`RequestDatabaseMock`].
+pub const MEAL_PLAN_UPDATE: DateTime<Utc> =
+    DateTime::from_timestamp_nanos(1_700_000_000_000_000_000);
+
 pub const FAIL_ID: Uuid = Uuid::from_u128(12345);
 pub struct CommandMock;
 
@@ -359,6 +444,10 @@ impl Command for CommandMock {
         Ok(())
     }
 
+    async fn remove_meal_rating(&self, _meal_id: Uuid, _client_id: Uuid) -> CommandResult<()> {
+        Ok(())
+    }
+
     async fn delete_image(&self, image_id: Uuid) -> CommandResult<()> {
         if image_id == FAIL_ID {
             Err(crate::interface::api_command::CommandError::DataError(
@@ -369,25 +458,209 @@ impl Command for CommandMock {
         }
     }
 
+    async fn delete_own_image(&self, image_id: Uuid, _client_id: Uuid) -> CommandResult<()> {
+        self.delete_image(image_id).await
+    }
+
     async fn verify_image(&self, _image_id: Uuid) -> CommandResult<()> {
         Ok(())
     }
+
+    async fn create_data_quality_report(
+        &self,
+        _notify_admin: bool,
+    ) -> CommandResult<DataQualityReport> {
+        let today = Local::now().date_naive();
+        Ok(DataQualityReport {
+            report_date: today,
+            from_date: today,
+            checked_food_count: 0,
+            issues: vec![],
+        })
+    }
+
+    async fn set_canteen_closure(
+        &self,
+        _canteen_id: Uuid,
+        _date: Date,
+        _reason: ClosureReason,
+        _note: Option<String>,
+    ) -> CommandResult<()> {
+        Ok(())
+    }
+
+    async fn remove_canteen_closure(&self, _canteen_id: Uuid, _date: Date) -> CommandResult<bool> {
+        Ok(true)
+    }
+
+    async fn delete_client_data(
+        &self,
+        client_id: Uuid,
+        _requester: ErasureRequester,
+    ) -> CommandResult<DataErasure> {
+        if client_id == FAIL_ID {
+            return Err(crate::interface::api_command::CommandError::DataError(
+                crate::interface::persistent_data::DataError::NoSuchItem,
+            ));
+        }
+        Ok(DataErasure {
+            image_ids: vec![Uuid::default()],
+            meal_ratings: 1,
+            image_votes: 2,
+            image_reports: 3,
+        })
+    }
 }
 
 pub struct AuthDataMock;
 
+fn mock_api_key(key: &str) -> ApiKey {
+    ApiKey {
+        key: key.into(),
+        description: String::new(),
+        created_at: Local::now(),
+        revoked_at: None,
+        permissions: ApiKeyPermissions::default(),
+    }
+}
+
 #[async_trait]
 impl AuthDataAccess for AuthDataMock {
     async fn get_api_keys(&self) -> DataResult<Vec<ApiKey>> {
         Ok(vec![
-            ApiKey {
-                key: "1234567890".into(),
-                description: String::new(),
+            mock_api_key("1234567890"),
+            mock_api_key("YWpzZGg4MnozNzhkMnppZGFzYXNkMiBzYWZzYSBzPGE5MDk4"),
+        ])
+    }
+
+    async fn get_all_api_keys(&self) -> DataResult<Vec<ApiKey>> {
+        self.get_api_keys().await
+    }
+
+    async fn add_api_key(
+        &self,
+        key: &str,
+        description: &str,
+        permissions: &ApiKeyPermissions,
+    ) -> DataResult<ApiKey> {
+        Ok(ApiKey {
+            description: description.into(),
+            permissions: permissions.clone(),
+            ..mock_api_key(key)
+        })
+    }
+
+    async fn revoke_api_key(&self, _key: &str) -> DataResult<bool> {
+        Ok(true)
+    }
+
+    async fn use_nonce(
+        &self,
+        _client_id: Uuid,
+        _nonce: &str,
+        _expires_at: DateTime<Local>,
+    ) -> DataResult<bool> {
+        Ok(true)
+    }
+
+    async fn delete_expired_nonces(&self) -> DataResult<u64> {
+        Ok(0)
+    }
+}
+
+pub struct ApiKeyManagementMock;
+
+#[async_trait]
+impl ApiKeyManagement for ApiKeyManagementMock {
+    async fn create_api_key(
+        &self,
+        description: String,
+        permissions: ApiKeyPermissions,
+    ) -> ApiKeyResult<ApiKey> {
+        Ok(ApiKey {
+            description,
+            permissions,
+            ..mock_api_key("new_generated_key")
+        })
+    }
+
+    async fn list_api_keys(&self) -> ApiKeyResult<Vec<ApiKey>> {
+        Ok(AuthDataMock.get_all_api_keys().await?)
+    }
+
+    async fn describe_api_key(&self, ident: &str) -> ApiKeyResult<ApiKey> {
+        if ident == "1234567890" {
+            Ok(mock_api_key(ident))
+        } else {
+            Err(ApiKeyError::NoSuchKey(ident.into()))
+        }
+    }
+
+    async fn revoke_api_key(&self, ident: &str) -> ApiKeyResult<ApiKey> {
+        Ok(ApiKey {
+            revoked_at: Some(Local::now()),
+            ..self.describe_api_key(ident).await?
+        })
+    }
+}
+
+pub struct HealthDataMock;
+
+#[async_trait]
+impl HealthDataAccess for HealthDataMock {
+    async fn check_connection(&self) -> DataResult<()> {
+        Ok(())
+    }
+
+    async fn get_last_parse_success(&self) -> DataResult<Option<DateTime<Local>>> {
+        Ok(Some(Local::now()))
+    }
+}
+
+pub const JOB_NAME: &str = "job";
+pub const RUNNING_JOB_NAME: &str = "running_job";
+pub struct JobControlMock;
+
+#[async_trait]
+impl JobControl for JobControlMock {
+    async fn get_jobs(&self) -> Vec<JobStatus> {
+        let now = Local::now();
+        vec![
+            JobStatus {
+                name: JOB_NAME.into(),
+                schedule: "0 0 * * * *".into(),
+                running: false,
+                last_run: Some(JobRun {
+                    started_at: now,
+                    duration_secs: 1.0,
+                    retries: 0,
+                    outcome: JobOutcome::Success,
+                }),
+                last_success: Some(now),
+                next_run: Some(now),
+                consecutive_failures: 0,
             },
-            ApiKey {
-                key: "YWpzZGg4MnozNzhkMnppZGFzYXNkMiBzYWZzYSBzPGE5MDk4".into(),
-                description: String::new(),
+            JobStatus {
+                name: RUNNING_JOB_NAME.into(),
+                schedule: "0 0 * * * *".into(),
+                running: true,
+                last_run: None,
+                last_success: None,
+                next_run: Some(now),
+                consecutive_failures: 0,
             },
-        ])
+        ]
+    }
+
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    async fn trigger_job(&self, name: &str) -> JobResult<()> {
+        match name {
+            JOB_NAME => Ok(()),
+            RUNNING_JOB_NAME => Err(JobError::AlreadyRunning(name.into())),
+            _ => Err(JobError::NoSuchJob(name.into())),
+        }
     }
 }
@@ -2,9 +2,16 @@
 
 mod admin;
 pub mod auth;
+mod error;
+mod health;
+mod http_cache;
+mod key_store;
 pub mod mock;
 pub mod mutation;
 pub mod query;
+mod query_limits;
+pub mod rate_limit;
+mod replay;
 pub mod server;
 mod types;
 pub mod util;
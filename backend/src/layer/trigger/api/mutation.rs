@@ -1,7 +1,10 @@
 //! See [`MutationRoot`].
 
-use super::util::{read_and_validate_upload, ApiUtil};
-use crate::util::{ReportReason, Uuid};
+use super::{
+    rate_limit::RateLimitCategory,
+    util::{read_and_validate_upload, ApiUtil},
+};
+use crate::util::{ApiKeyScope, ErasureRequester, ReportReason, Uuid};
 use async_graphql::{Context, Object, Result, Upload};
 
 use tracing::{instrument, trace};
@@ -13,7 +16,7 @@ pub struct MutationRoot;
 #[Object]
 impl MutationRoot {
     /// This mutation adds an image to the specified main dish.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// Images will be visible to other users.
     ///
     /// By uploading an image you agree to license it under the [CC0](https://creativecommons.org/publicdomain/zero/1.0/) license and pledge to have the right to do so.
@@ -30,7 +33,8 @@ impl MutationRoot {
         #[graphql(desc = "Sha512 hash of the uploaded image file. Encoded as Base64.")]
         hash: String,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::UploadImage)?;
+        ctx.check_rate_limit(RateLimitCategory::Upload)?;
 
         trace!("Mutated `addImage`");
         let command = ctx.get_command();
@@ -47,7 +51,7 @@ impl MutationRoot {
     }
 
     /// This mutation either adds a rating to the specified main dish (if no such rating existed), or modifies an existing one.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the main dish does not exist, or any other error occurs in the process, an error message is returned.
     /// If the rating was successfully added or changed, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -57,7 +61,8 @@ impl MutationRoot {
         #[graphql(desc = "Id of the meal to rate to.")] meal_id: Uuid,
         #[graphql(desc = "The new rating of the main dish.")] rating: u32,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Rate)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `setRating`");
         let command = ctx.get_command();
@@ -67,8 +72,29 @@ impl MutationRoot {
         Ok(true)
     }
 
+    /// This mutation removes the rating of the specified main dish.
+    /// The user has to be authenticated with an api key permitting this mutation.
+    /// If the main dish does not exist, or any other error occurs in the process, an error message is returned.
+    /// If the rating was successfully removed, 'true' is returned.
+    #[instrument(skip(self, ctx))]
+    async fn remove_rating(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Id of the meal to remove the rating from.")] meal_id: Uuid,
+    ) -> Result<bool> {
+        ctx.check_authentication(ApiKeyScope::Rate)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
+
+        trace!("Mutated `removeRating`");
+        let command = ctx.get_command();
+        let client_id = ctx.get_client_id()?;
+
+        command.remove_meal_rating(meal_id, client_id).await?;
+        Ok(true)
+    }
+
     /// This mutation adds an upvote to the specified image.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the image does not exist, or any other error occurs in the process, an error message is returned.
     /// If the upvote was successfully added, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -77,7 +103,8 @@ impl MutationRoot {
         ctx: &Context<'_>,
         #[graphql(desc = "Id of the image to add the upvote to.")] image_id: Uuid,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Vote)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `addUpvote`");
         let command = ctx.get_command();
@@ -88,7 +115,7 @@ impl MutationRoot {
     }
 
     /// This mutation removes the upvote from the specified image.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the image does not exist, or any other error occurs in the process, an error message is returned.
     /// If the upvote was successfully removed, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -97,7 +124,8 @@ impl MutationRoot {
         ctx: &Context<'_>,
         #[graphql(desc = "Id of the image to remove the upvote from.")] image_id: Uuid,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Vote)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `removeUpvote`");
         let command = ctx.get_command();
@@ -108,7 +136,7 @@ impl MutationRoot {
     }
 
     /// This mutation adds a downvote to the specified image.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the image does not exist, or any other error occurs in the process, an error message is returned.
     /// If the downvote was successfully added, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -117,7 +145,8 @@ impl MutationRoot {
         ctx: &Context<'_>,
         #[graphql(desc = "Id of the image to add the downvote to.")] image_id: Uuid,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Vote)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `addDownvote`");
         let command = ctx.get_command();
@@ -128,7 +157,7 @@ impl MutationRoot {
     }
 
     /// This mutation removes the downvote from the specified image.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the image does not exist, or any other error occurs in the process, an error message is returned.
     /// If the downvote was successfully removed, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -137,7 +166,8 @@ impl MutationRoot {
         ctx: &Context<'_>,
         #[graphql(desc = "Id of the image to remove the downvote from.")] image_id: Uuid,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Vote)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `removeDownvote`");
         let command = ctx.get_command();
@@ -148,7 +178,7 @@ impl MutationRoot {
     }
 
     /// This mutation adds a report to the specified image.
-    /// The user has to be authenticated.
+    /// The user has to be authenticated with an api key permitting this mutation.
     /// If the image does not exist, or any other error occurs in the process, an error message is returned.
     /// If the report was successfully added, 'true' is returned.
     #[instrument(skip(self, ctx))]
@@ -158,7 +188,8 @@ impl MutationRoot {
         #[graphql(desc = "Id of the image to report.")] image_id: Uuid,
         #[graphql(desc = "The reason for reporting the image.")] reason: ReportReason,
     ) -> Result<bool> {
-        ctx.check_authentication()?;
+        ctx.check_authentication(ApiKeyScope::Report)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
 
         trace!("Mutated `reportImage`");
         let command = ctx.get_command();
@@ -167,4 +198,44 @@ impl MutationRoot {
         command.report_image(image_id, reason, client_id).await?;
         Ok(true)
     }
+
+    /// This mutation deletes the specified image, which must have been uploaded with the same client id.
+    /// The user has to be authenticated with an api key permitting uploading images.
+    /// If the image does not exist, was uploaded by another client, or any other error occurs in the process, an error message is returned.
+    /// If the image was successfully deleted, 'true' is returned.
+    #[instrument(skip(self, ctx))]
+    async fn delete_image(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Id of the image to delete.")] image_id: Uuid,
+    ) -> Result<bool> {
+        ctx.check_authentication(ApiKeyScope::UploadImage)?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
+
+        trace!("Mutated `deleteImage`");
+        let command = ctx.get_command();
+        let client_id = ctx.get_client_id()?;
+
+        command.delete_own_image(image_id, client_id).await?;
+        Ok(true)
+    }
+
+    /// This mutation deletes all data linked to the client id, that is all ratings, image votes, image reports and uploaded images.
+    /// The user has to be authenticated with any api key, independent of the mutations it permits.
+    /// This can not be undone. If any error occurs in the process, an error message is returned.
+    /// If the data was successfully deleted, 'true' is returned.
+    #[instrument(skip(self, ctx))]
+    async fn delete_my_data(&self, ctx: &Context<'_>) -> Result<bool> {
+        ctx.check_authenticated()?;
+        ctx.check_rate_limit(RateLimitCategory::Mutation)?;
+
+        trace!("Mutated `deleteMyData`");
+        let command = ctx.get_command();
+        let client_id = ctx.get_client_id()?;
+
+        command
+            .delete_client_data(client_id, ErasureRequester::Client)
+            .await?;
+        Ok(true)
+    }
 }
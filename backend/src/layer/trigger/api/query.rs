@@ -1,13 +1,26 @@
 //! See [`QueryRoot`].
 use async_graphql::{Context, Object, Result};
+use futures::try_join;
 use tracing::{instrument, trace};
 
-use crate::util::{Date, Uuid};
+use crate::{
+    interface::persistent_data::model::Pagination,
+    util::{Date, Uuid},
+};
 
 use super::{
-    types::auth_info::AuthInfo, types::canteen::Canteen, types::meal::Meal, util::ApiUtil,
+    query_limits::{LIST_FACTOR, QUERY_COST},
+    types::auth_info::AuthInfo,
+    types::canteen::Canteen,
+    types::meal::Meal,
+    types::personal_data::{MyData, MyImage, MyRating, MyReport},
+    util::ApiUtil,
 };
 
+/// Number of elements returned by paginated queries if no limit is specified.
+/// Paginated queries return at most 100 elements at once.
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
 /// Class implementing `GraphQL`s root queries.
 #[derive(Debug)]
 pub struct QueryRoot;
@@ -70,6 +83,122 @@ impl QueryRoot {
         env!("CARGO_PKG_VERSION").into()
     }
 
+    /// This query returns all data stored in relation to the client id provided in the `Authorization` header,
+    /// including all uploaded image files. This allows users to see which data is stored about them.
+    #[instrument(skip(self, ctx))]
+    async fn get_my_data(&self, ctx: &Context<'_>) -> Result<MyData> {
+        trace!("Queried `getMyData`");
+        let data = ctx.get_data_access();
+        let client_id = ctx.get_client_id()?;
+        let (ratings, image_votes, reports, images) = try_join!(
+            data.get_ratings_of_user(client_id, Pagination::default()),
+            data.get_image_votes_of_user(client_id),
+            data.get_reports_of_user(client_id, Pagination::default()),
+            data.get_images_of_user(client_id, Pagination::default()),
+        )?;
+        Ok(MyData {
+            client_id,
+            ratings: ratings.into_iter().map(Into::into).collect(),
+            image_votes: image_votes.into_iter().map(Into::into).collect(),
+            reports: reports.into_iter().map(Into::into).collect(),
+            images: images.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// This query returns the ratings of meals by the client id provided in the `Authorization` header.
+    /// The ratings are paginated, at most `limit` ratings are returned after skipping the first `offset` ones.
+    #[instrument(skip(self, ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
+    async fn my_ratings(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(
+            desc = "Maximum number of ratings to return.",
+            default_with = "DEFAULT_PAGE_SIZE",
+            validator(minimum = 1, maximum = 100)
+        )]
+        limit: u32,
+        #[graphql(desc = "Number of ratings to skip.", default)] offset: u32,
+    ) -> Result<Vec<MyRating>> {
+        trace!("Queried `myRatings`");
+        let data = ctx.get_data_access();
+        let client_id = ctx.get_client_id()?;
+        let page = Pagination {
+            limit: Some(limit),
+            offset,
+        };
+        let ratings = data
+            .get_ratings_of_user(client_id, page)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(ratings)
+    }
+
+    /// This query returns the images uploaded by the client id provided in the `Authorization` header, newest first.
+    /// This includes images hidden because of reports, so their moderation status can be shown to their uploader.
+    /// The images are paginated, at most `limit` images are returned after skipping the first `offset` ones.
+    #[instrument(skip(self, ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
+    async fn my_images(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(
+            desc = "Maximum number of images to return.",
+            default_with = "DEFAULT_PAGE_SIZE",
+            validator(minimum = 1, maximum = 100)
+        )]
+        limit: u32,
+        #[graphql(desc = "Number of images to skip.", default)] offset: u32,
+    ) -> Result<Vec<MyImage>> {
+        trace!("Queried `myImages`");
+        let data = ctx.get_data_access();
+        let client_id = ctx.get_client_id()?;
+        let page = Pagination {
+            limit: Some(limit),
+            offset,
+        };
+        let images = data
+            .get_images_of_user(client_id, page)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(images)
+    }
+
+    /// This query returns the reports of images by the client id provided in the `Authorization` header, newest first.
+    /// The reports are paginated, at most `limit` reports are returned after skipping the first `offset` ones.
+    #[instrument(skip(self, ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
+    async fn my_reports(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(
+            desc = "Maximum number of reports to return.",
+            default_with = "DEFAULT_PAGE_SIZE",
+            validator(minimum = 1, maximum = 100)
+        )]
+        limit: u32,
+        #[graphql(desc = "Number of reports to skip.", default)] offset: u32,
+    ) -> Result<Vec<MyReport>> {
+        trace!("Queried `myReports`");
+        let data = ctx.get_data_access();
+        let client_id = ctx.get_client_id()?;
+        let page = Pagination {
+            limit: Some(limit),
+            offset,
+        };
+        let reports = data
+            .get_reports_of_user(client_id, page)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(reports)
+    }
+
     /// This query returns the in the `Authorization` request header provided authentication information.
     /// It is intended for debugging purposes to check whether these information got passed correctly.
     /// Additionally, there is information whether authentication was successful.
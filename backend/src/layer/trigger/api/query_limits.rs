@@ -0,0 +1,117 @@
+//! Limits on the depth and complexity of graphql queries.
+//!
+//! As types like canteens, lines and meals reference each other, queries could otherwise be nested arbitrarily deep,
+//! causing a huge number of database queries for a single request.
+//! The complexity of a query is the sum of the costs of all its fields, where each field costs one by default.
+//! Fields requiring additional database queries are annotated with higher costs, see [`QUERY_COST`] and [`LIST_FACTOR`].
+
+use std::sync::Arc;
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextValidation},
+    ErrorExtensions, ServerError, ValidationResult,
+};
+use thiserror::Error;
+
+/// Additional cost of a field requiring a separate database query for each parent object.
+pub(super) const QUERY_COST: usize = 5;
+/// Expected number of elements of a list field, by which the complexity of its children gets multiplied.
+pub(super) const LIST_FACTOR: usize = 5;
+
+/// Error indicating a query exceeds its limits.
+#[derive(Debug, Error)]
+pub(super) enum QueryLimitError {
+    /// The query is nested deeper than allowed.
+    #[error("Query has a depth of {depth}, exceeding the limit of {limit}.")]
+    TooDeep {
+        /// Depth of the query.
+        depth: usize,
+        /// Maximum allowed depth.
+        limit: usize,
+    },
+    /// The query is more complex than allowed.
+    #[error("Query has a complexity of {complexity}, exceeding the limit of {limit}.")]
+    TooComplex {
+        /// Complexity of the query.
+        complexity: usize,
+        /// Maximum allowed complexity.
+        limit: usize,
+    },
+}
+
+impl ErrorExtensions for QueryLimitError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| match self {
+            Self::TooDeep { depth, limit } => {
+                e.set("code", "QUERY_TOO_DEEP");
+                e.set("depth", *depth);
+                e.set("limit", *limit);
+            }
+            Self::TooComplex { complexity, limit } => {
+                e.set("code", "QUERY_TOO_COMPLEX");
+                e.set("complexity", *complexity);
+                e.set("limit", *limit);
+            }
+        })
+    }
+}
+
+impl From<QueryLimitError> for ServerError {
+    fn from(error: QueryLimitError) -> Self {
+        let mut server_error = Self::new(error.to_string(), None);
+        server_error.extensions = error.extend().extensions;
+        server_error
+    }
+}
+
+/// Maximum depth and complexity of accepted queries.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct QueryLimits {
+    pub(super) max_depth: usize,
+    pub(super) max_complexity: usize,
+}
+
+impl QueryLimits {
+    /// Checks the depth and complexity determined while validating a query.
+    /// # Errors
+    /// if any limit is exceeded
+    const fn check(&self, result: &ValidationResult) -> Result<(), QueryLimitError> {
+        if result.depth > self.max_depth {
+            return Err(QueryLimitError::TooDeep {
+                depth: result.depth,
+                limit: self.max_depth,
+            });
+        }
+        if result.complexity > self.max_complexity {
+            return Err(QueryLimitError::TooComplex {
+                complexity: result.complexity,
+                limit: self.max_complexity,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl ExtensionFactory for QueryLimits {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryLimitsExtension(*self))
+    }
+}
+
+/// Extension rejecting queries exceeding the [`QueryLimits`] during validation, before any resolver is run.
+struct QueryLimitsExtension(QueryLimits);
+
+#[async_trait::async_trait]
+impl Extension for QueryLimitsExtension {
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+        self.0
+            .check(&result)
+            .map_err(|e| vec![ServerError::from(e)])?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,326 @@
+//! Rate limiting of graphql requests per caller using token buckets.
+//!
+//! Each caller, identified by its ip address and client id, has its own bucket per [`RateLimitCategory`].
+//! A bucket holds up to the configured number of requests per minute and refills continuously.
+//! This way, a single abusive caller can not starve all others.
+//!
+//! Api keys are not limited here, as a single key is shared by all users of an app.
+//! Keys with their own rate limit are limited when authenticating, see [`super::key_store::ApiKeyStore`].
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    net::IpAddr,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_graphql::ErrorExtensions;
+use hyper::HeaderMap;
+use thiserror::Error;
+
+use crate::util::Uuid;
+
+/// Time in which an empty bucket gets refilled completely.
+const REFILL_PERIOD: Duration = Duration::from_mins(1);
+
+/// Kind of operation a rate limit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitCategory {
+    /// Any graphql request.
+    Request,
+    /// A single mutation, except uploads.
+    Mutation,
+    /// A single image upload.
+    Upload,
+}
+
+impl Display for RateLimitCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Request => "request",
+            Self::Mutation => "mutation",
+            Self::Upload => "upload",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Identity of a caller whose requests are limited.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum Caller {
+    /// Ip address the request originates from.
+    Ip(IpAddr),
+    /// Client id provided in the authorization header.
+    Client(Uuid),
+}
+
+/// Error indicating a caller exceeded a rate limit.
+#[derive(Debug, Error)]
+#[error("Too many {category} requests, please retry after {} seconds.", self.retry_after_secs())]
+pub(super) struct RateLimitError {
+    category: RateLimitCategory,
+    retry_after: Duration,
+}
+
+impl RateLimitError {
+    /// Seconds after which the request can be retried, rounded up.
+    pub(super) fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0)
+    }
+}
+
+impl ErrorExtensions for RateLimitError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| {
+            e.set("code", "RATE_LIMITED");
+            e.set("retryAfter", self.retry_after_secs());
+        })
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Default)]
+struct Buckets {
+    buckets: HashMap<(RateLimitCategory, Caller), TokenBucket>,
+    last_pruned: Option<Instant>,
+}
+
+/// Limits of requests per minute for each [`RateLimitCategory`], `None` meaning unlimited.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RateLimits {
+    pub(super) requests: Option<NonZeroU32>,
+    pub(super) mutations: Option<NonZeroU32>,
+    pub(super) uploads: Option<NonZeroU32>,
+}
+
+/// Token bucket rate limiter shared by all requests.
+#[derive(Clone)]
+pub(super) struct RateLimiter {
+    limits: RateLimits,
+    ip_header: Option<String>,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter.
+    /// If `ip_header` is given, the ip address of callers is read from that header (set by a reverse proxy) if present.
+    pub(super) fn new(limits: RateLimits, ip_header: Option<String>) -> Self {
+        Self {
+            limits,
+            ip_header,
+            buckets: Arc::default(),
+        }
+    }
+
+    /// Determines the ip address of the caller, either from the configured header or the address of the connection.
+    /// If the header contains multiple addresses, the last one, added by the closest proxy, is used.
+    pub(super) fn caller_ip(&self, headers: &HeaderMap, peer: IpAddr) -> IpAddr {
+        self.ip_header
+            .as_ref()
+            .and_then(|header| headers.get(header))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok())
+            .unwrap_or(peer)
+    }
+
+    /// Takes a token from the bucket of each caller for the given category.
+    /// # Errors
+    /// if any of the callers has no token left, no tokens are taken then.
+    pub(super) fn check(
+        &self,
+        category: RateLimitCategory,
+        callers: &[Caller],
+    ) -> Result<(), RateLimitError> {
+        self.check_at(category, callers, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        category: RateLimitCategory,
+        callers: &[Caller],
+        now: Instant,
+    ) -> Result<(), RateLimitError> {
+        let limit = match category {
+            RateLimitCategory::Request => self.limits.requests,
+            RateLimitCategory::Mutation => self.limits.mutations,
+            RateLimitCategory::Upload => self.limits.uploads,
+        };
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+        let capacity = f64::from(limit.get());
+        let refill_per_sec = capacity / REFILL_PERIOD.as_secs_f64();
+
+        let mut buckets = self.buckets.lock().expect("rate limit lock poisoned");
+        // buckets unused for a whole period are full again and can be forgotten
+        if buckets
+            .last_pruned
+            .is_none_or(|pruned| now.duration_since(pruned) >= REFILL_PERIOD)
+        {
+            buckets
+                .buckets
+                .retain(|_, bucket| now.duration_since(bucket.updated) < REFILL_PERIOD);
+            buckets.last_pruned = Some(now);
+        }
+
+        let mut min_tokens = capacity;
+        for caller in callers {
+            let bucket = buckets
+                .buckets
+                .entry((category, caller.clone()))
+                .or_insert(TokenBucket {
+                    tokens: capacity,
+                    updated: now,
+                });
+            let refilled = now.duration_since(bucket.updated).as_secs_f64() * refill_per_sec;
+            bucket.tokens = (bucket.tokens + refilled).min(capacity);
+            bucket.updated = now;
+            min_tokens = min_tokens.min(bucket.tokens);
+        }
+
+        if min_tokens < 1.0 {
+            return Err(RateLimitError {
+                category,
+                retry_after: Duration::from_secs_f64((1.0 - min_tokens) / refill_per_sec),
+            });
+        }
+
+        for caller in callers {
+            if let Some(bucket) = buckets.buckets.get_mut(&(category, caller.clone())) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        drop(buckets);
+        Ok(())
+    }
+}
+
+/// Rate limiter together with the callers of the current request, available to resolvers for limiting single mutations.
+pub(super) struct RequestRateLimit {
+    pub(super) limiter: RateLimiter,
+    pub(super) callers: Vec<Caller>,
+}
+
+impl RequestRateLimit {
+    /// Takes a token for the given category from all callers of the request.
+    /// # Errors
+    /// if any caller exceeded its limit
+    pub(super) fn check(&self, category: RateLimitCategory) -> Result<(), RateLimitError> {
+        self.limiter.check(category, &self.callers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        num::NonZeroU32,
+        time::{Duration, Instant},
+    };
+
+    use hyper::HeaderMap;
+
+    use crate::util::Uuid;
+
+    use super::{Caller, RateLimitCategory, RateLimiter, RateLimits};
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(
+            RateLimits {
+                requests: None,
+                mutations: NonZeroU32::new(3),
+                uploads: NonZeroU32::new(1),
+            },
+            Some("x-forwarded-for".into()),
+        )
+    }
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = limiter();
+        let ip = Caller::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let client = Caller::Client(Uuid::default());
+        let other_client = Uuid::from_u128(1);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter
+                .check_at(
+                    RateLimitCategory::Mutation,
+                    &[ip.clone(), client.clone()],
+                    now
+                )
+                .is_ok());
+        }
+        let err = limiter
+            .check_at(RateLimitCategory::Mutation, std::slice::from_ref(&ip), now)
+            .unwrap_err();
+        assert_eq!(err.retry_after_secs(), 20);
+
+        // other categories and callers are independent
+        assert!(limiter
+            .check_at(RateLimitCategory::Upload, std::slice::from_ref(&ip), now)
+            .is_ok());
+        assert!(limiter
+            .check_at(RateLimitCategory::Request, std::slice::from_ref(&ip), now)
+            .is_ok());
+        assert!(limiter
+            .check_at(
+                RateLimitCategory::Mutation,
+                &[Caller::Client(other_client)],
+                now
+            )
+            .is_ok());
+
+        // limited if any caller has no tokens left
+        assert!(limiter
+            .check_at(
+                RateLimitCategory::Mutation,
+                &[Caller::Client(other_client), client],
+                now
+            )
+            .is_err());
+
+        let later = now + Duration::from_secs(20);
+        assert!(limiter
+            .check_at(
+                RateLimitCategory::Mutation,
+                std::slice::from_ref(&ip),
+                later
+            )
+            .is_ok());
+        assert!(limiter
+            .check_at(RateLimitCategory::Mutation, &[ip], later)
+            .is_err());
+    }
+
+    #[test]
+    fn test_caller_ip() {
+        let limiter = limiter();
+        let peer = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.caller_ip(&headers, peer), peer);
+
+        headers.insert("x-forwarded-for", "10.0.0.1, 192.168.1.2".parse().unwrap());
+        assert_eq!(
+            limiter.caller_ip(&headers, peer),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2))
+        );
+
+        headers.insert("x-forwarded-for", "garbage".parse().unwrap());
+        assert_eq!(limiter.caller_ip(&headers, peer), peer);
+
+        let without_header = RateLimiter::new(limiter.limits, None);
+        headers.insert("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        assert_eq!(without_header.caller_ip(&headers, peer), peer);
+    }
+}
@@ -0,0 +1,191 @@
+//! Protection against replaying captured authenticated requests.
+//! For more details, see <https://github.com/kronos-et-al/MensaApp/blob/main/doc/ApiAuth.md>.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{Local, Utc};
+use tracing::{debug, warn};
+
+use crate::{interface::persistent_data::AuthDataAccess, util::Uuid};
+
+use super::auth::AuthFailReason;
+
+/// Checks the signed timestamp and nonce of authenticated requests.
+/// Requests are only accepted if their timestamp lies within the allowed clock skew and their nonce was not used before by the same client.
+///
+/// Used nonces are stored in the database, so they are known to all instances and survive restarts.
+/// They only need to be remembered until the timestamp of a request using them would no longer be accepted anyways.
+#[derive(Clone)]
+pub(super) struct ReplayGuard {
+    auth_data: Arc<dyn AuthDataAccess>,
+    max_clock_skew: Duration,
+    allow_legacy: bool,
+    /// Time expired nonces were last deleted from the database.
+    last_pruned: Arc<Mutex<Option<Instant>>>,
+}
+
+impl ReplayGuard {
+    /// Creates a new guard accepting timestamps deviating up to `max_clock_skew` from the server time.
+    /// If `allow_legacy` is set, requests without timestamp and nonce, as sent by older app versions, are accepted as well.
+    pub(super) fn new(
+        auth_data: Arc<dyn AuthDataAccess>,
+        max_clock_skew: Duration,
+        allow_legacy: bool,
+    ) -> Self {
+        Self {
+            auth_data,
+            max_clock_skew,
+            allow_legacy,
+            last_pruned: Arc::default(),
+        }
+    }
+
+    /// Checks whether a request of `client_id` with the given timestamp (in seconds since the unix epoch) and nonce is no replay.
+    /// The nonce is remembered afterwards, so it can not be used again.
+    pub(super) async fn check(
+        &self,
+        client_id: Uuid,
+        timestamp: Option<i64>,
+        nonce: Option<&str>,
+    ) -> Result<(), AuthFailReason> {
+        let (timestamp, nonce) = match (timestamp, nonce) {
+            (Some(timestamp), Some(nonce)) => (timestamp, nonce),
+            (None, None) if self.allow_legacy => return Ok(()),
+            _ => return Err(AuthFailReason::MissingTimestampOrNonce),
+        };
+
+        let skew = Utc::now().timestamp().abs_diff(timestamp);
+        if skew > self.max_clock_skew.as_secs() {
+            return Err(AuthFailReason::TimestampOutOfRange(skew));
+        }
+
+        self.delete_expired_nonces().await;
+
+        let expires_at = Local::now() + 2 * self.max_clock_skew;
+        match self.auth_data.use_nonce(client_id, nonce, expires_at).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AuthFailReason::NonceReused),
+            Err(e) => {
+                warn!("Could not check nonce of request: {e}");
+                Err(AuthFailReason::NonceCheckFailed)
+            }
+        }
+    }
+
+    /// Deletes expired nonces from the database, at most once per allowed clock skew.
+    async fn delete_expired_nonces(&self) {
+        {
+            let mut last_pruned = self.last_pruned.lock().expect("nonce prune lock poisoned");
+            if last_pruned.is_some_and(|pruned| pruned.elapsed() < self.max_clock_skew) {
+                return;
+            }
+            *last_pruned = Some(Instant::now());
+        }
+
+        match self.auth_data.delete_expired_nonces().await {
+            Ok(deleted) => debug!("Deleted {deleted} expired nonces"),
+            Err(e) => warn!("Could not delete expired nonces: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{sync::Arc, time::Duration};
+
+    use chrono::Utc;
+
+    use crate::{
+        layer::{
+            logic::api_key_management::mocks::AuthDatabaseMock, trigger::api::auth::AuthFailReason,
+        },
+        util::Uuid,
+    };
+
+    use super::ReplayGuard;
+
+    fn guard(allow_legacy: bool) -> ReplayGuard {
+        ReplayGuard::new(
+            Arc::new(AuthDatabaseMock::default()),
+            Duration::from_mins(5),
+            allow_legacy,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_nonce_reuse() {
+        let guard = guard(false);
+        let client = Uuid::default();
+        let now = Utc::now().timestamp();
+
+        assert!(guard.check(client, Some(now), Some("nonce")).await.is_ok());
+        assert!(matches!(
+            guard.check(client, Some(now), Some("nonce")).await,
+            Err(AuthFailReason::NonceReused)
+        ));
+        // another instance sharing the same database knows the nonce as well
+        let other_instance = ReplayGuard {
+            last_pruned: Arc::default(),
+            ..guard.clone()
+        };
+        assert!(matches!(
+            other_instance.check(client, Some(now), Some("nonce")).await,
+            Err(AuthFailReason::NonceReused)
+        ));
+        assert!(
+            guard
+                .check(Uuid::from_u128(1), Some(now), Some("nonce"))
+                .await
+                .is_ok(),
+            "nonces are per client"
+        );
+        assert!(guard.check(client, Some(now), Some("other")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew() {
+        let guard = guard(false);
+        let client = Uuid::default();
+        let now = Utc::now().timestamp();
+
+        assert!(guard
+            .check(client, Some(now - 200), Some("a"))
+            .await
+            .is_ok());
+        assert!(guard
+            .check(client, Some(now + 200), Some("b"))
+            .await
+            .is_ok());
+        assert!(matches!(
+            guard.check(client, Some(now - 400), Some("c")).await,
+            Err(AuthFailReason::TimestampOutOfRange(_))
+        ));
+        assert!(matches!(
+            guard.check(client, Some(now + 400), Some("d")).await,
+            Err(AuthFailReason::TimestampOutOfRange(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_mode() {
+        let strict = guard(false);
+        let legacy = guard(true);
+        let client = Uuid::default();
+        let now = Utc::now().timestamp();
+
+        assert!(matches!(
+            strict.check(client, None, None).await,
+            Err(AuthFailReason::MissingTimestampOrNonce)
+        ));
+        assert!(legacy.check(client, None, None).await.is_ok());
+        assert!(legacy.check(client, None, None).await.is_ok());
+        assert!(matches!(
+            legacy.check(client, Some(now), None).await,
+            Err(AuthFailReason::MissingTimestampOrNonce)
+        ));
+    }
+}
@@ -4,57 +4,97 @@ use std::{
     fmt::Display,
     future::{Future, IntoFuture},
     mem,
-    net::{Ipv6Addr, SocketAddrV6},
-    num::NonZeroU64,
+    net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6},
+    num::{NonZeroU32, NonZeroU64},
     path::PathBuf,
     pin::Pin,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_graphql::{
-    extensions::Tracing,
+    extensions::{
+        apollo_persisted_queries::{ApolloPersistedQueries, LruCacheStorage},
+        Tracing,
+    },
     http::{playground_source, GraphQLPlaygroundConfig},
-    EmptySubscription, Schema,
+    parser::{
+        parse_query,
+        types::{DocumentOperations, OperationType},
+    },
+    EmptySubscription, ErrorExtensions, Pos, Schema,
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{rejection::GraphQLRejection, GraphQLRequest, GraphQLResponse};
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::DefaultBodyLimit,
+    extract::{ConnectInfo, DefaultBodyLimit, RawQuery, Request},
     handler::Handler,
-    middleware,
+    middleware::{self, Next},
     response::{self, IntoResponse},
     routing::get,
     BoxError, Extension, Router,
 };
 
-use hyper::StatusCode;
-use tokio::sync::Notify;
+use hyper::{
+    header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER},
+    HeaderMap, Method, StatusCode,
+};
+use opentelemetry::global;
+use opentelemetry_http::HeaderExtractor;
+use tokio::{sync::Notify, task::JoinHandle};
 use tower::{buffer::BufferLayer, limit::RateLimitLayer, ServiceBuilder};
 use tower_http::services::ServeDir;
 use tracing::{debug, info, info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::{
     interface::{
         api_command::Command,
-        persistent_data::{model::ApiKey, AuthDataAccess, RequestDataAccess},
+        api_key_management::ApiKeyManagement,
+        job_control::JobControl,
+        persistent_data::{AuthDataAccess, HealthDataAccess, RequestDataAccess},
     },
     layer::trigger::api::{
-        admin::{admin_router, ArcCommand},
-        auth::auth_middleware,
+        admin::{admin_router, ArcApiKeyManagement, ArcCommand, ArcJobControl},
+        auth::{auth_middleware, AuthState},
+        error::ErrorCodes,
+        health::{health_router, ArcHealthData},
+        http_cache::{GetRequest, HttpCache},
+        key_store::ApiKeyStore,
+        query_limits::QueryLimits,
+        rate_limit::{Caller, RateLimitCategory, RateLimiter, RateLimits, RequestRateLimit},
+        replay::ReplayGuard,
     },
-    util::{local_to_global_url, IMAGE_BASE_PATH},
+    util::{local_to_global_url, metrics, IMAGE_BASE_PATH},
 };
 
 use super::{
     auth::AuthInfo,
     mutation::MutationRoot,
     query::QueryRoot,
-    util::{CommandBox, DataBox},
+    util::{CommandBox, DataBox, ImageDir},
 };
 
 type GraphQLSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
 
+/// Names of the operations sent by the app, which are used as metric labels.
+/// Other operation names are chosen freely by callers and would create an unbounded number of time series.
+const KNOWN_OPERATIONS: &[&str] = &[
+    "AddDownvote",
+    "AddUpvote",
+    "GetCanteenDate",
+    "GetDefaultCanteen",
+    "GetMeal",
+    "GetMealPlanForDay",
+    "LinkImage",
+    "RemoveDownvote",
+    "RemoveUpvote",
+    "ReportImage",
+    "UpdateRating",
+];
+/// Operation label used in metrics for requests whose operation can not be determined.
+const OTHER_OPERATION: &str = "other";
+
 /// Information necessary to create a [`ApiServerInfo`].
 pub struct ApiServerInfo {
     /// Port under which the server should run.
@@ -67,6 +107,30 @@ pub struct ApiServerInfo {
     pub max_body_size: u64,
     /// Api key for accessing the admin api
     pub admin_key: String,
+    /// Maximum age of the last successful meal plan parse in hours before the server is no longer considered ready.
+    pub max_parse_age_hours: u32,
+    /// Interval in which the valid api keys get reloaded from the database.
+    pub api_key_refresh_interval: Duration,
+    /// Maximum deviation of the timestamp of authenticated requests from the server time.
+    pub max_clock_skew: Duration,
+    /// Whether authenticated requests without timestamp and nonce, as sent by older app versions, are accepted.
+    pub allow_legacy_auth: bool,
+    /// Max number of graphql requests per minute for each caller, `None` means unlimited.
+    pub request_rate_limit: Option<NonZeroU32>,
+    /// Max number of mutations per minute for each caller, `None` means unlimited.
+    pub mutation_rate_limit: Option<NonZeroU32>,
+    /// Max number of image uploads per minute for each caller, `None` means unlimited.
+    pub upload_rate_limit: Option<NonZeroU32>,
+    /// Header containing the ip address of callers, e.g. `X-Forwarded-For` when running behind a reverse proxy.
+    pub rate_limit_ip_header: Option<String>,
+    /// Maximum nesting depth of graphql queries.
+    pub max_query_depth: usize,
+    /// Maximum complexity of graphql queries, see [`query_limits`](super::query_limits).
+    pub max_query_complexity: usize,
+    /// Number of persisted queries to remember, `0` disables persisted queries.
+    pub persisted_query_cache_size: usize,
+    /// Time for which responses to GET requests may be cached without revalidation.
+    pub cache_max_age: Duration,
 }
 
 enum State {
@@ -91,8 +155,13 @@ pub struct ApiServer {
     server_info: ApiServerInfo,
     schema: GraphQLSchema,
     state: State,
-    api_keys: Vec<ApiKey>,
+    api_key_store: ApiKeyStore,
+    replay_guard: ReplayGuard,
+    key_refresh: Option<JoinHandle<()>>,
     command_copy: Arc<dyn Command + Send + Sync>,
+    key_management: ArcApiKeyManagement,
+    health_data: ArcHealthData,
+    job_control: ArcJobControl,
 }
 
 impl ApiServer {
@@ -103,22 +172,63 @@ impl ApiServer {
         server_info: ApiServerInfo,
         data_access: impl RequestDataAccess + 'static,
         command: impl Command + 'static,
-        auth: impl AuthDataAccess,
+        auth: impl AuthDataAccess + 'static,
+        key_management: impl ApiKeyManagement + 'static,
+        health_data: impl HealthDataAccess + 'static,
+        job_control: impl JobControl + 'static,
     ) -> Self {
         let command_arc = Arc::new(command);
-        let schema: GraphQLSchema = construct_schema(data_access, command_arc.clone());
+        let limits = QueryLimits {
+            max_depth: server_info.max_query_depth,
+            max_complexity: server_info.max_query_complexity,
+        };
+        let schema: GraphQLSchema = construct_schema(
+            data_access,
+            command_arc.clone(),
+            limits,
+            HttpCache {
+                max_age: server_info.cache_max_age,
+            },
+            server_info.persisted_query_cache_size,
+            server_info.image_dir.clone(),
+        );
+        let auth: Arc<dyn AuthDataAccess> = Arc::new(auth);
+        let api_key_store = ApiKeyStore::new(auth.clone());
+        api_key_store
+            .reload()
+            .await
+            .expect("could not get api keys from database");
+        let replay_guard = ReplayGuard::new(
+            auth,
+            server_info.max_clock_skew,
+            server_info.allow_legacy_auth,
+        );
         Self {
             server_info,
             schema,
             state: State::Created,
-            api_keys: auth
-                .get_api_keys()
-                .await
-                .expect("could not get api keys from database"),
+            api_key_store,
+            replay_guard,
+            key_refresh: None,
             command_copy: command_arc,
+            key_management: Arc::new(key_management),
+            health_data: Arc::new(health_data),
+            job_control: Arc::new(job_control),
         }
     }
 
+    /// Creates the rate limiter for graphql requests per caller.
+    fn rate_limiter(&self) -> RateLimiter {
+        RateLimiter::new(
+            RateLimits {
+                requests: self.server_info.request_rate_limit,
+                mutations: self.server_info.mutation_rate_limit,
+                uploads: self.server_info.upload_rate_limit,
+            },
+            self.server_info.rate_limit_ip_header.clone(),
+        )
+    }
+
     /// Starts the GraphQL-Server. It will be running in the background until [`Self::shutdown()`] is called.
     ///
     /// # Panics
@@ -136,8 +246,18 @@ impl ApiServer {
             .try_into()
             .expect("max body size should fit in usize");
 
-        let auth =
-            middleware::from_fn_with_state((max_body_size, self.api_keys.clone()), auth_middleware);
+        let auth = middleware::from_fn_with_state(
+            AuthState {
+                body_limit: max_body_size,
+                api_keys: self.api_key_store.clone(),
+                replay_guard: self.replay_guard.clone(),
+            },
+            auth_middleware,
+        );
+        self.key_refresh = Some(
+            self.api_key_store
+                .spawn_refresh(self.server_info.api_key_refresh_interval),
+        );
 
         let rate_limit = ServiceBuilder::new()
             .layer(HandleErrorLayer::new(|err: BoxError| async move {
@@ -157,15 +277,31 @@ impl ApiServer {
         let admin_router = admin_router(
             self.server_info.admin_key.clone(),
             self.command_copy.clone() as ArcCommand,
+            self.job_control.clone(),
+            self.key_management.clone(),
+            self.api_key_store.clone(),
+        );
+
+        let health_router = health_router(
+            self.health_data.clone(),
+            self.job_control.clone(),
+            self.server_info.image_dir.clone(),
+            self.server_info.max_parse_age_hours,
         );
 
         let app = Router::new()
             .route(
                 "/",
-                get(graphql_playground).post(graphql_handler.layer(auth)),
+                get(graphql_handler
+                    .layer(auth.clone())
+                    .layer(middleware::from_fn(playground_middleware)))
+                .post(graphql_handler.layer(auth)),
             )
             .layer(Extension(self.schema.clone()))
+            .layer(Extension(self.rate_limiter()))
+            .route("/metrics", get(metrics_handler))
             .nest("/admin", admin_router)
+            .nest("/health", health_router)
             .nest_service(IMAGE_BASE_PATH, ServeDir::new(&self.server_info.image_dir))
             .layer(rate_limit)
             .layer(DefaultBodyLimit::max(max_body_size));
@@ -180,7 +316,10 @@ impl ApiServer {
         let listener = tokio::net::TcpListener::bind(socket)
             .await
             .expect("bind to tcp socket");
-        let server = axum::serve(listener, app);
+        let server = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        );
 
         let shutdown_notify = Arc::new(Notify::new());
         let shutdown_notify_sender = shutdown_notify.clone();
@@ -216,6 +355,9 @@ impl ApiServer {
             State::Running(s) => s,
         };
 
+        if let Some(key_refresh) = self.key_refresh.take() {
+            key_refresh.abort();
+        }
         shutdown.await;
         info!("Graphql server shutdown complete.");
     }
@@ -225,15 +367,36 @@ impl ApiServer {
 pub(super) fn construct_schema(
     data_access: impl RequestDataAccess + 'static,
     command: impl Command + 'static,
+    limits: QueryLimits,
+    http_cache: HttpCache,
+    persisted_query_cache_size: usize,
+    image_dir: PathBuf,
 ) -> GraphQLSchema {
     let data_access_box: DataBox = Box::new(data_access);
     let command_box: CommandBox = Box::new(command);
 
-    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+    let mut schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
         .data(data_access_box)
         .data(command_box)
+        .data(ImageDir(image_dir))
         .extension(Tracing)
-        .finish()
+        .extension(ErrorCodes)
+        .extension(limits)
+        .extension(http_cache);
+    if persisted_query_cache_size > 0 {
+        schema = schema.extension(ApolloPersistedQueries::new(LruCacheStorage::new(
+            persisted_query_cache_size,
+        )));
+    }
+    schema.finish()
+}
+
+#[allow(clippy::unused_async)]
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::encode(),
+    )
 }
 
 #[allow(clippy::unused_async)]
@@ -241,22 +404,80 @@ async fn graphql_playground() -> impl IntoResponse {
     response::Html(playground_source(GraphQLPlaygroundConfig::new("/")))
 }
 
+/// Serves the graphql playground for GET requests without query.
+async fn playground_middleware(
+    RawQuery(query): RawQuery,
+    request: Request,
+    next: Next,
+) -> response::Response {
+    if query.is_none() {
+        graphql_playground().await.into_response()
+    } else {
+        next.run(request).await
+    }
+}
+
 #[axum::debug_handler]
 async fn graphql_handler(
     Extension(auth_info): Extension<AuthInfo>,
     Extension(schema): Extension<GraphQLSchema>,
-    request: GraphQLRequest,
-) -> GraphQLResponse {
-    let request = request.into_inner().data(auth_info.clone() as AuthInfo);
+    Extension(rate_limiter): Extension<RateLimiter>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    method: Method,
+    headers: HeaderMap,
+    request: Result<GraphQLRequest, GraphQLRejection>,
+) -> response::Response {
+    let callers = rate_limit_callers(&auth_info, rate_limiter.caller_ip(&headers, peer.ip()));
+    if let Err(e) = rate_limiter.check(RateLimitCategory::Request, &callers) {
+        debug!("Rejected graphql request: {e}");
+        let mut response = async_graphql::Response::from_errors(vec![e
+            .extend()
+            .into_server_error(Pos::default())]);
+        response
+            .http_headers
+            .insert(RETRY_AFTER, e.retry_after_secs().into());
+        return GraphQLResponse::from(response).into_response();
+    }
+
+    let request = match request {
+        Ok(request) => request.into_inner(),
+        Err(rejection) => return rejection.into_response(),
+    };
+    let get_request = (method == Method::GET).then(|| GetRequest {
+        cacheable: !headers.contains_key(AUTHORIZATION),
+        if_none_match: headers
+            .get(IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .map(Into::into),
+    });
+    let mut request = request
+        .data(auth_info.clone() as AuthInfo)
+        .data(RequestRateLimit {
+            limiter: rate_limiter,
+            callers,
+        });
+    if let Some(get_request) = get_request.clone() {
+        request = request.data(get_request);
+    }
+    let operation = operation_label(&request);
+    let start = Instant::now();
 
     let span = info_span!(
         "incoming graphql request",
         variables = %request.variables,
         auth_info = %auth_info
     );
+    // continue traces of clients
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(&headers))
+    });
+    if let Err(e) = span.set_parent(parent) {
+        debug!("Could not continue trace of request: {e}");
+    }
 
     async {
         let response = schema.execute(request).await;
+        metrics::record_graphql_request(operation, response.is_ok(), start.elapsed());
         if response.is_err() {
             debug!(
                 "Error handling request: {}",
@@ -268,35 +489,96 @@ async fn graphql_handler(
                     .join("\n")
             );
         }
-        response.into()
+        let not_modified = get_request.is_some_and(|get_request| {
+            response
+                .http_headers
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .is_some_and(|etag| get_request.matches(etag))
+        });
+        if not_modified {
+            (StatusCode::NOT_MODIFIED, response.http_headers).into_response()
+        } else {
+            GraphQLResponse::from(response).into_response()
+        }
     }
     .instrument(span)
     .await
 }
 
+/// Label of the operation of a request for metrics.
+/// This is the operation name if it is one of the [`KNOWN_OPERATIONS`], otherwise the operation type like `query` or `mutation`.
+fn operation_label(request: &async_graphql::Request) -> &'static str {
+    if let Some(known) = request
+        .operation_name
+        .as_deref()
+        .and_then(|name| KNOWN_OPERATIONS.iter().find(|known| **known == name))
+    {
+        return known;
+    }
+
+    let Ok(document) = parse_query(&request.query) else {
+        return OTHER_OPERATION;
+    };
+    let operation = match (document.operations, request.operation_name.as_deref()) {
+        (DocumentOperations::Single(operation), _) => Some(operation),
+        (DocumentOperations::Multiple(mut operations), Some(name)) => operations.remove(name),
+        (DocumentOperations::Multiple(_), None) => None,
+    };
+    match operation.map(|operation| operation.node.ty) {
+        Some(OperationType::Query) => "query",
+        Some(OperationType::Mutation) => "mutation",
+        Some(OperationType::Subscription) => "subscription",
+        None => OTHER_OPERATION,
+    }
+}
+
+/// Callers a request is attributed to for rate limiting.
+/// The api key is not one of them, as all users of the app share the same key.
+fn rate_limit_callers(auth_info: &AuthInfo, ip: IpAddr) -> Vec<Caller> {
+    let mut callers = vec![Caller::Ip(ip)];
+    callers.extend(auth_info.client_id.map(Caller::Client));
+    callers
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used)]
 
-    use std::{env::temp_dir, io::Cursor, path::PathBuf};
+    use std::{
+        env::temp_dir,
+        io::Cursor,
+        net::{IpAddr, Ipv4Addr},
+        num::NonZeroU32,
+        path::PathBuf,
+        time::Duration,
+    };
 
     use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
     use base64::{engine::general_purpose, Engine};
     use hmac::{Hmac, Mac};
     use image::{DynamicImage, ImageBuffer, ImageFormat, ImageReader};
-    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+    use reqwest::{
+        header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER},
+        StatusCode,
+    };
     use serial_test::serial;
     use sha2::{Digest, Sha512};
 
     use crate::{
         layer::trigger::api::{
-            mock::{AuthDataMock, CommandMock, RequestDatabaseMock},
+            mock::{
+                ApiKeyManagementMock, AuthDataMock, CommandMock, HealthDataMock, JobControlMock,
+                RequestDatabaseMock,
+            },
             server::ApiServer,
         },
         util::{ImageResource, Uuid},
     };
 
-    use super::{ApiServerInfo, IMAGE_BASE_PATH};
+    use super::{
+        operation_label, rate_limit_callers, ApiServerInfo, AuthInfo, Caller, IMAGE_BASE_PATH,
+    };
 
     const TEST_PORT: u16 = 12345;
     const BODY_SIZE: u64 = 10 << 20;
@@ -308,8 +590,29 @@ mod tests {
             rate_limit: None,
             max_body_size: BODY_SIZE,
             admin_key: "admin".into(),
+            max_parse_age_hours: 48,
+            api_key_refresh_interval: Duration::from_mins(1),
+            max_clock_skew: Duration::from_mins(5),
+            allow_legacy_auth: true,
+            request_rate_limit: None,
+            mutation_rate_limit: None,
+            upload_rate_limit: None,
+            rate_limit_ip_header: None,
+            max_query_depth: 10,
+            max_query_complexity: 10_000,
+            persisted_query_cache_size: 100,
+            cache_max_age: Duration::from_mins(1),
         };
-        ApiServer::new(info, RequestDatabaseMock, CommandMock, AuthDataMock).await
+        ApiServer::new(
+            info,
+            RequestDatabaseMock,
+            CommandMock,
+            AuthDataMock,
+            ApiKeyManagementMock,
+            HealthDataMock,
+            JobControlMock,
+        )
+        .await
     }
 
     async fn get_test_server_with_images(image_dir: PathBuf) -> ApiServer {
@@ -319,8 +622,87 @@ mod tests {
             rate_limit: None,
             max_body_size: BODY_SIZE,
             admin_key: "admin".into(),
+            max_parse_age_hours: 48,
+            api_key_refresh_interval: Duration::from_mins(1),
+            max_clock_skew: Duration::from_mins(5),
+            allow_legacy_auth: true,
+            request_rate_limit: None,
+            mutation_rate_limit: None,
+            upload_rate_limit: None,
+            rate_limit_ip_header: None,
+            max_query_depth: 10,
+            max_query_complexity: 10_000,
+            persisted_query_cache_size: 100,
+            cache_max_age: Duration::from_mins(1),
+        };
+        ApiServer::new(
+            info,
+            RequestDatabaseMock,
+            CommandMock,
+            AuthDataMock,
+            ApiKeyManagementMock,
+            HealthDataMock,
+            JobControlMock,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_request_rate_limit() {
+        let info = ApiServerInfo {
+            port: TEST_PORT,
+            image_dir: temp_dir(),
+            rate_limit: None,
+            max_body_size: BODY_SIZE,
+            admin_key: "admin".into(),
+            max_parse_age_hours: 48,
+            api_key_refresh_interval: Duration::from_mins(1),
+            max_clock_skew: Duration::from_mins(5),
+            allow_legacy_auth: true,
+            request_rate_limit: NonZeroU32::new(2),
+            mutation_rate_limit: None,
+            upload_rate_limit: None,
+            rate_limit_ip_header: None,
+            max_query_depth: 10,
+            max_query_complexity: 10_000,
+            persisted_query_cache_size: 100,
+            cache_max_age: Duration::from_mins(1),
+        };
+        let mut server = ApiServer::new(
+            info,
+            RequestDatabaseMock,
+            CommandMock,
+            AuthDataMock,
+            ApiKeyManagementMock,
+            HealthDataMock,
+            JobControlMock,
+        )
+        .await;
+        server.start().await;
+
+        let client = reqwest::Client::new();
+        let request = || {
+            client
+                .post(format!("http://localhost:{TEST_PORT}"))
+                .header(CONTENT_TYPE, "application/json")
+                .body(r#"{"query": "{apiVersion}"}"#)
+                .send()
         };
-        ApiServer::new(info, RequestDatabaseMock, CommandMock, AuthDataMock).await
+
+        for _ in 0..2 {
+            let resp = request().await.unwrap().json::<serde_json::Value>().await;
+            assert!(resp.unwrap()["errors"].is_null());
+        }
+
+        let resp = request().await.unwrap();
+        assert!(resp.headers().contains_key(RETRY_AFTER));
+        let resp = resp.json::<serde_json::Value>().await.unwrap();
+        let extensions = &resp["errors"][0]["extensions"];
+        assert_eq!(extensions["code"], "RATE_LIMITED");
+        assert!(extensions["retryAfter"].as_u64().unwrap() > 0);
+
+        server.shutdown().await;
     }
 
     #[tokio::test]
@@ -363,6 +745,118 @@ mod tests {
         server.shutdown().await;
     }
 
+    #[tokio::test]
+    #[serial]
+    /// Test whether queries can be sent via GET and get cached.
+    async fn test_graphql_get() {
+        let mut server = get_test_server().await;
+        server.start().await;
+
+        let url = format!("http://localhost:{TEST_PORT}/?query=%7BapiVersion%7D");
+        let client = reqwest::Client::new();
+        let resp = client.get(&url).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[CACHE_CONTROL], "public, max-age=60");
+        let etag = resp.headers()[ETAG].clone();
+        assert!(resp.text().await.unwrap().contains("apiVersion"));
+
+        let resp = client
+            .get(&url)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers()[ETAG], etag);
+
+        // responses to authorized requests may contain personal data
+        let resp = client
+            .get(&url)
+            .header(IF_NONE_MATCH, etag)
+            .header(
+                AUTHORIZATION,
+                "Mensa MWQ3NWQzODAtY2YwNy00ZWRiLTkwNDYtYTJkOTgxYmMyMTlkOmFiYzoxMjM=",
+            )
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[CACHE_CONTROL], "no-store");
+        assert!(!resp.headers().contains_key(ETAG));
+
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    /// Test whether graphql requests are exported as metrics.
+    async fn test_metrics() {
+        let mut server = get_test_server().await;
+        server.start().await;
+
+        let known_request = r#"
+        {
+            "query": "query GetDefaultCanteen {apiVersion}",
+            "operationName": "GetDefaultCanteen"
+        }
+        "#;
+        let unknown_request = r#"
+        {
+            "query": "query metricsVersion {apiVersion}",
+            "operationName": "metricsVersion"
+        }
+        "#;
+
+        let client = reqwest::Client::new();
+        for request in [known_request, unknown_request] {
+            client
+                .post(format!("http://localhost:{TEST_PORT}"))
+                .header(
+                    AUTHORIZATION,
+                    "Mensa MWQ3NWQzODAtY2YwNy00ZWRiLTkwNDYtYTJkOTgxYmMyMTlkOmFiYzoxMjM=",
+                )
+                .body(request)
+                .send()
+                .await
+                .unwrap();
+        }
+
+        let metrics = reqwest::get(format!("http://localhost:{TEST_PORT}/metrics"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(metrics.contains(
+            r#"mensa_graphql_requests_total{operation="GetDefaultCanteen",result="success"} 1"#
+        ));
+        assert!(
+            metrics.contains(r#"mensa_graphql_requests_total{operation="query",result="success"}"#)
+        );
+        // operation names chosen by callers must not create new time series
+        assert!(!metrics.contains("metricsVersion"));
+
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    /// Test whether the health endpoints are available without authentication.
+    async fn test_health() {
+        let mut server = get_test_server().await;
+        server.start().await;
+
+        for endpoint in ["live", "ready"] {
+            let status = reqwest::get(format!("http://localhost:{TEST_PORT}/health/{endpoint}"))
+                .await
+                .unwrap()
+                .status();
+            assert_eq!(status, reqwest::StatusCode::OK, "endpoint {endpoint}");
+        }
+
+        server.shutdown().await;
+    }
+
     #[tokio::test]
     #[serial]
     /// Test whether the graphql playground is served.
@@ -528,8 +1022,29 @@ mod tests {
             rate_limit: None,
             max_body_size: 1 << 10,
             admin_key: "admin".into(),
+            max_parse_age_hours: 48,
+            api_key_refresh_interval: Duration::from_mins(1),
+            max_clock_skew: Duration::from_mins(5),
+            allow_legacy_auth: true,
+            request_rate_limit: None,
+            mutation_rate_limit: None,
+            upload_rate_limit: None,
+            rate_limit_ip_header: None,
+            max_query_depth: 10,
+            max_query_complexity: 10_000,
+            persisted_query_cache_size: 100,
+            cache_max_age: Duration::from_mins(1),
         };
-        let mut server = ApiServer::new(info, RequestDatabaseMock, CommandMock, AuthDataMock).await;
+        let mut server = ApiServer::new(
+            info,
+            RequestDatabaseMock,
+            CommandMock,
+            AuthDataMock,
+            ApiKeyManagementMock,
+            HealthDataMock,
+            JobControlMock,
+        )
+        .await;
 
         server.start().await;
 
@@ -577,4 +1092,50 @@ mod tests {
 
         server.shutdown().await;
     }
+
+    #[test]
+    fn test_rate_limit_callers() {
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let client_id = Uuid::default();
+        let auth_info = AuthInfo {
+            client_id: Some(client_id),
+            authenticated: Ok(()),
+            api_ident: "shared key".into(),
+            hash: String::new(),
+            scopes: vec![],
+        };
+        // the api key is shared by all app users and must not be limited as a whole
+        assert_eq!(
+            rate_limit_callers(&auth_info, ip),
+            vec![Caller::Ip(ip), Caller::Client(client_id)]
+        );
+
+        let anonymous = AuthInfo {
+            client_id: None,
+            ..auth_info
+        };
+        assert_eq!(rate_limit_callers(&anonymous, ip), vec![Caller::Ip(ip)]);
+    }
+
+    #[test]
+    fn test_operation_label() {
+        let known =
+            async_graphql::Request::new("query GetMeal { apiVersion }").operation_name("GetMeal");
+        assert_eq!(operation_label(&known), "GetMeal");
+
+        let unknown =
+            async_graphql::Request::new("mutation a { deleteMyData } query b { apiVersion }")
+                .operation_name("a");
+        assert_eq!(operation_label(&unknown), "mutation");
+
+        let anonymous = async_graphql::Request::new("{ apiVersion }");
+        assert_eq!(operation_label(&anonymous), "query");
+
+        let ambiguous =
+            async_graphql::Request::new("query a { apiVersion } query b { apiVersion }");
+        assert_eq!(operation_label(&ambiguous), "other");
+
+        let invalid = async_graphql::Request::new("{").operation_name("GetMeal2");
+        assert_eq!(operation_label(&invalid), "other");
+    }
 }
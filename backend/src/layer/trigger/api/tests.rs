@@ -3,20 +3,34 @@
 
 use super::auth::AuthInfo;
 use crate::layer::trigger::api::auth::AuthFailReason;
+use crate::layer::trigger::api::http_cache::{GetRequest, HttpCache};
 use crate::layer::trigger::api::mutation::MutationRoot;
 use crate::layer::trigger::api::query::QueryRoot;
+use crate::layer::trigger::api::query_limits::QueryLimits;
 use crate::layer::trigger::api::server::construct_schema;
 use crate::layer::trigger::api::util::{CommandBox, DataBox};
-use crate::util::Uuid;
+use crate::util::{ApiKeyScope, Uuid};
 use async_graphql::{EmptySubscription, Request, Schema, UploadValue, Variables};
 use serde_json::json;
-use sha2::{Digest, Sha512};
+use sha2::{Digest, Sha256, Sha512};
+use std::{env::temp_dir, time::Duration};
 use tempfile::tempdir;
 use tokio::io::AsyncWriteExt;
 
 use super::mock::{CommandMock, RequestDatabaseMock};
 use base64::engine::Engine;
 
+const QUERY_LIMITS: QueryLimits = QueryLimits {
+    max_depth: 10,
+    max_complexity: 10_000,
+};
+
+const HTTP_CACHE: HttpCache = HttpCache {
+    max_age: Duration::from_mins(1),
+};
+
+const PERSISTED_QUERY_CACHE_SIZE: usize = 100;
+
 const FULL_REQUEST_STRING: &str = r#"
     {
         getCanteens {
@@ -127,9 +141,17 @@ async fn test_gql_request(request: &'static str) {
         api_ident: String::new(),
         authenticated: Ok(()),
         hash: String::new(),
+        scopes: ApiKeyScope::ALL.to_vec(),
     });
 
-    let schema = construct_schema(RequestDatabaseMock, CommandMock);
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
     let response = schema.execute(request).await;
     assert!(response.is_ok(), "request returned {:?}", response.errors);
 }
@@ -162,6 +184,7 @@ async fn test_add_image() {
             api_ident: String::new(),
             authenticated: Ok(()),
             hash: String::new(),
+            scopes: ApiKeyScope::ALL.to_vec(),
         })
         .variables(Variables::from_json(json!( {
           "hash": hash_base64,
@@ -176,7 +199,14 @@ async fn test_add_image() {
         },
     );
 
-    let schema = construct_schema(RequestDatabaseMock, CommandMock);
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
     let response = schema.execute(request).await;
     assert!(response.is_ok(), "request returned {:?}", response.errors);
 }
@@ -192,6 +222,106 @@ async fn test_set_rating() {
     test_gql_request(request).await;
 }
 
+#[tokio::test]
+async fn test_remove_rating() {
+    let request = r#"
+    mutation {
+        removeRating(mealId: "00000000-0000-0000-0000-000000000000")
+    }
+    "#;
+    test_gql_request(request).await;
+}
+
+#[tokio::test]
+async fn test_missing_scope() {
+    let request = r#"
+    mutation {
+        setRating(mealId: "00000000-0000-0000-0000-000000000000", rating:2)
+    }
+    "#;
+    let request = Request::from(request).data(AuthInfo {
+        client_id: Some(Uuid::default()),
+        api_ident: String::new(),
+        authenticated: Ok(()),
+        hash: String::new(),
+        scopes: vec![ApiKeyScope::Vote],
+    });
+
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0].message.contains("`Rate` scope"),
+        "unexpected error: {:?}",
+        response.errors
+    );
+}
+
+#[tokio::test]
+async fn test_error_codes() {
+    let request = r#"
+    mutation {
+        setRating(mealId: "00000000-0000-0000-0000-000000000000", rating:2)
+    }
+    "#;
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let extension = |response: &async_graphql::Response, name: &str| {
+        response.errors[0]
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get(name))
+            .cloned()
+    };
+
+    let unauthenticated = Request::from(request).data(AuthInfo {
+        client_id: Some(Uuid::default()),
+        api_ident: "abc".into(),
+        authenticated: Err(AuthFailReason::InvalidApiKey),
+        hash: String::new(),
+        scopes: vec![],
+    });
+    let response = schema.execute(unauthenticated).await;
+    assert_eq!(
+        extension(&response, "code"),
+        Some(async_graphql::Value::from("UNAUTHENTICATED"))
+    );
+    assert_eq!(
+        extension(&response, "reason"),
+        Some(async_graphql::Value::from("INVALID_API_KEY"))
+    );
+
+    let missing_scope = Request::from(request).data(AuthInfo {
+        client_id: Some(Uuid::default()),
+        api_ident: "abc".into(),
+        authenticated: Ok(()),
+        hash: String::new(),
+        scopes: vec![ApiKeyScope::Vote],
+    });
+    let response = schema.execute(missing_scope).await;
+    assert_eq!(
+        extension(&response, "code"),
+        Some(async_graphql::Value::from("FORBIDDEN"))
+    );
+    assert_eq!(
+        extension(&response, "scope"),
+        Some(async_graphql::Value::Enum(async_graphql::Name::new("RATE")))
+    );
+}
+
 #[tokio::test]
 async fn test_image_votes() {
     let request = r#"
@@ -215,6 +345,46 @@ async fn test_report_image() {
     test_gql_request(request).await;
 }
 
+#[tokio::test]
+async fn test_delete_image() {
+    let request = r#"
+        mutation {
+            deleteImage(imageId:"1d75d380-cf07-4edb-9046-a2d981bc219d")
+        }
+    "#;
+    test_gql_request(request).await;
+}
+
+#[tokio::test]
+async fn test_delete_my_data() {
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let request = |authenticated| {
+        Request::from("mutation { deleteMyData }").data(AuthInfo {
+            client_id: Some(Uuid::default()),
+            api_ident: String::new(),
+            authenticated,
+            hash: String::new(),
+            scopes: vec![],
+        })
+    };
+
+    // no scope required
+    let response = schema.execute(request(Ok(()))).await;
+    assert!(response.is_ok(), "request returned {:?}", response.errors);
+
+    let response = schema
+        .execute(request(Err(AuthFailReason::MissingApiIdentOrHash)))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+}
+
 // ---------------------- queries -----------------------
 
 #[tokio::test]
@@ -340,6 +510,26 @@ async fn test_get_specific_canteen() {
     test_gql_request(request).await;
 }
 
+#[tokio::test]
+async fn test_get_canteen_closures() {
+    let request = r#"
+    {
+        getCanteens {
+          closures(from: "2000-01-01", to: "2000-01-31") {
+            date
+            reason
+            note
+          }
+          closure(date: "2000-01-01") {
+            reason
+          }
+          mealPlanStatus(date: "2000-01-01")
+        }
+      }
+    "#;
+    test_gql_request(request).await;
+}
+
 #[tokio::test]
 async fn test_get_specific_meal() {
     let request = r#"
@@ -377,13 +567,155 @@ async fn test_get_auth_info_empty() {
         api_ident: String::new(),
         authenticated: Err(AuthFailReason::MissingApiIdentOrHash),
         hash: String::new(),
+        scopes: vec![],
     });
 
-    let schema = construct_schema(RequestDatabaseMock, CommandMock);
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
     let response = schema.execute(request).await;
     assert!(response.is_ok(), "request returned {:?}", response.errors);
 }
 
+#[tokio::test]
+async fn test_get_my_data() {
+    let request = r"
+    {
+        getMyData {
+          clientId
+          ratings { mealId rating }
+          imageVotes { imageId upvote }
+          reports { imageId reason reportDate }
+          images { id mealId url uploadDate approved visible reportCount upvotes downvotes file }
+        }
+    }
+    ";
+    let request = Request::from(request).data(AuthInfo {
+        client_id: Some(Uuid::default()),
+        api_ident: String::new(),
+        authenticated: Err(AuthFailReason::MissingApiIdentOrHash),
+        hash: String::new(),
+        scopes: vec![],
+    });
+
+    let dir = tempdir().unwrap();
+    let image = include_bytes!("../../logic/api_command/tests/test.jpg");
+    tokio::fs::write(
+        dir.path().join("1aa73d5d-1701-4975-aa3c-1422a8bc10e8.jpg"),
+        image,
+    )
+    .await
+    .unwrap();
+
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        dir.path().to_owned(),
+    );
+    let response = schema.execute(request).await;
+    assert!(response.is_ok(), "request returned {:?}", response.errors);
+
+    let data = response.data.into_json().unwrap();
+    assert_eq!(data["getMyData"]["clientId"], json!(Uuid::default()));
+    assert_eq!(data["getMyData"]["reports"][0]["reason"], "ADVERT");
+    assert_eq!(data["getMyData"]["images"][0]["visible"], false);
+    assert_eq!(
+        data["getMyData"]["images"][0]["file"],
+        base64::prelude::BASE64_STANDARD.encode(image)
+    );
+}
+
+#[tokio::test]
+async fn test_get_my_data_without_client_id() {
+    let request = Request::from("{ getMyData { clientId } }").data(AuthInfo {
+        client_id: None,
+        api_ident: String::new(),
+        authenticated: Err(AuthFailReason::MissingApiIdentOrHash),
+        hash: String::new(),
+        scopes: vec![],
+    });
+
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn test_my_contributions() {
+    let request = r"
+    {
+        myRatings { mealId rating }
+        myImages(limit: 10, offset: 10) { id visible approved reportCount }
+        myReports(offset: 1) { imageId reason reportDate }
+    }
+    ";
+    test_gql_request(request).await;
+}
+
+#[tokio::test]
+async fn test_my_ratings_page_too_large() {
+    let request = Request::from("{ myRatings(limit: 1000) { mealId } }").data(AuthInfo {
+        client_id: Some(Uuid::default()),
+        api_ident: String::new(),
+        authenticated: Err(AuthFailReason::MissingApiIdentOrHash),
+        hash: String::new(),
+        scopes: vec![],
+    });
+
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(
+        response.errors[0].extensions.as_ref().unwrap().get("code"),
+        Some(&async_graphql::Value::from("BAD_REQUEST"))
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_request_error_codes() {
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+
+    for request in ["{ getCanteens { id ", "{ getCanteens { unknownField } }"] {
+        let response = schema.execute(request).await;
+        assert_eq!(response.errors.len(), 1, "errors: {:?}", response.errors);
+        assert_eq!(
+            response.errors[0].extensions.as_ref().unwrap().get("code"),
+            Some(&async_graphql::Value::from("BAD_REQUEST")),
+            "unexpected error for {request}: {:?}",
+            response.errors
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_recursive_line_canteen_ok() {
     let request = r"
@@ -423,6 +755,155 @@ async fn test_recursive_meal_line_ok() {
     test_gql_request(request).await;
 }
 
+#[tokio::test]
+async fn test_query_too_deep() {
+    let request = Request::from(
+        r#"
+    {
+      getCanteens {
+        lines {
+          meals(date: "2000-01-01") {
+            line {
+              canteen {
+                lines {
+                  meals(date: "2000-01-01") {
+                    line {
+                      canteen {
+                        lines {
+                          id
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    "#,
+    );
+
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+    let error = &response.errors[0];
+    assert_eq!(
+        error.message,
+        "Query has a depth of 11, exceeding the limit of 10."
+    );
+    assert_eq!(
+        error.extensions.as_ref().unwrap().get("code"),
+        Some(&async_graphql::Value::from("QUERY_TOO_DEEP"))
+    );
+}
+
+#[tokio::test]
+async fn test_query_too_complex() {
+    let request = Request::from(FULL_REQUEST_STRING);
+
+    let limits = QueryLimits {
+        max_depth: 10,
+        max_complexity: 1000,
+    };
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        limits,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+    let error = &response.errors[0];
+    assert!(
+        error.message.contains("exceeding the limit of 1000"),
+        "unexpected error: {error:?}"
+    );
+    assert_eq!(
+        error.extensions.as_ref().unwrap().get("code"),
+        Some(&async_graphql::Value::from("QUERY_TOO_COMPLEX"))
+    );
+}
+
+#[tokio::test]
+async fn test_persisted_query() {
+    let query = "{ getCanteens { id name } }";
+    let hash = format!("{:x}", Sha256::digest(query));
+    let persisted_request = |query: &str| {
+        let mut request = Request::new(query);
+        request.extensions.insert(
+            "persistedQuery".into(),
+            async_graphql::Value::from_json(json!({ "version": 1, "sha256Hash": hash })).unwrap(),
+        );
+        request
+    };
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+
+    let response = schema.execute(persisted_request("")).await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "PersistedQueryNotFound");
+    assert_eq!(
+        response.errors[0].extensions.as_ref().unwrap().get("code"),
+        Some(&async_graphql::Value::from("PERSISTED_QUERY_NOT_FOUND"))
+    );
+
+    let response = schema.execute(persisted_request(query)).await;
+    assert!(response.is_ok(), "request returned {:?}", response.errors);
+
+    let response = schema.execute(persisted_request("")).await;
+    assert!(response.is_ok(), "request returned {:?}", response.errors);
+    assert!(response.data.to_string().contains("dummy_canteen_1"));
+}
+
+#[tokio::test]
+async fn test_mutation_in_get_request() {
+    let request =
+        Request::new(r#"mutation { addUpvote(imageId: "1d75d380-cf07-4edb-9046-a2d981bc219d") }"#)
+            .data(GetRequest {
+                cacheable: true,
+                if_none_match: None,
+            });
+    let schema = construct_schema(
+        RequestDatabaseMock,
+        CommandMock,
+        QUERY_LIMITS,
+        HTTP_CACHE,
+        PERSISTED_QUERY_CACHE_SIZE,
+        temp_dir(),
+    );
+
+    let response = schema.execute(request).await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0]
+            .message
+            .contains("not allowed in GET requests"),
+        "unexpected error: {:?}",
+        response.errors
+    );
+    assert_eq!(
+        response.errors[0].extensions.as_ref().unwrap().get("code"),
+        Some(&async_graphql::Value::from("METHOD_NOT_ALLOWED"))
+    );
+}
+
 #[tokio::test]
 async fn test_get_auth_info() {
     let request = r"
@@ -442,6 +923,7 @@ async fn test_get_auth_info() {
         api_ident: "abc".into(),
         hash: "123".into(),
         authenticated: Ok(()),
+        scopes: vec![ApiKeyScope::Rate],
     };
 
     let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
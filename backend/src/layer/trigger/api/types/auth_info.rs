@@ -1,6 +1,6 @@
 use async_graphql::SimpleObject;
 
-use crate::util::Uuid;
+use crate::util::{ApiKeyScope, Uuid};
 
 /// Object containing all information about whether the current request was authenticated.
 /// For more informatiun about request authentication, see <https://github.com/kronos-et-al/MensaApp/blob/main/doc/ApiAuth.md>.
@@ -16,6 +16,8 @@ pub(in super::super) struct AuthInfo {
     pub(crate) hash: String,
     /// Error message when not authenticated.
     pub(crate) auth_error: Option<String>,
+    /// Mutations the provided api key permits, empty if not authenticated.
+    pub(crate) scopes: Vec<ApiKeyScope>,
 }
 
 impl From<super::super::auth::AuthInfo> for AuthInfo {
@@ -26,6 +28,7 @@ impl From<super::super::auth::AuthInfo> for AuthInfo {
             authenticated: value.authenticated.is_ok(),
             client_id: value.client_id,
             hash: value.hash,
+            scopes: value.scopes,
         }
     }
 }
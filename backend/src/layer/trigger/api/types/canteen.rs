@@ -1,8 +1,18 @@
-use crate::{interface::persistent_data::model, layer::trigger::api::util::ApiUtil, util::Uuid};
+use crate::{
+    interface::persistent_data::model,
+    layer::trigger::api::{
+        query_limits::{LIST_FACTOR, QUERY_COST},
+        util::ApiUtil,
+    },
+    util::{Date, Uuid},
+};
 use async_graphql::{ComplexObject, Context, Result, SimpleObject};
 use tracing::instrument;
 
-use super::line::Line;
+use super::{
+    closure::{Closure, MealPlanStatus},
+    line::Line,
+};
 
 #[derive(SimpleObject, Debug)]
 #[graphql(complex)]
@@ -17,6 +27,7 @@ pub(in super::super) struct Canteen {
 impl Canteen {
     /// Provides the lines of the canteen.
     #[instrument(skip(ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
     async fn lines(&self, ctx: &Context<'_>) -> Result<Vec<Line>> {
         let data = ctx.get_data_access();
         let lines = data
@@ -27,6 +38,46 @@ impl Canteen {
             .collect();
         Ok(lines)
     }
+
+    /// Provides all days between `from` and `to` (both inclusive) on which the canteen is closed.
+    #[instrument(skip(ctx))]
+    async fn closures(&self, ctx: &Context<'_>, from: Date, to: Date) -> Result<Vec<Closure>> {
+        let data = ctx.get_data_access();
+        let closures = data
+            .get_closures(self.id, from, to)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(closures)
+    }
+
+    /// Provides the closure of the canteen on a given day, if it is closed. Requires a date.
+    #[instrument(skip(ctx))]
+    async fn closure(&self, ctx: &Context<'_>, date: Date) -> Result<Option<Closure>> {
+        let data = ctx.get_data_access();
+        let closure = data
+            .get_closures(self.id, date, date)
+            .await?
+            .into_iter()
+            .next()
+            .map(Into::into);
+        Ok(closure)
+    }
+
+    /// Provides whether the meal plan of the canteen is available on a given day, the canteen is closed or no meal plan is published. Requires a date.
+    #[instrument(skip(ctx))]
+    async fn meal_plan_status(&self, ctx: &Context<'_>, date: Date) -> Result<MealPlanStatus> {
+        let data = ctx.get_data_access();
+        let status = if !data.get_closures(self.id, date, date).await?.is_empty() {
+            MealPlanStatus::Closed
+        } else if data.has_meal_plan(self.id, date).await? {
+            MealPlanStatus::Available
+        } else {
+            MealPlanStatus::NotPublished
+        };
+        Ok(status)
+    }
 }
 
 impl From<model::Canteen> for Canteen {
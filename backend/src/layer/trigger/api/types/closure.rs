@@ -0,0 +1,38 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::{
+    interface::persistent_data::model,
+    util::{ClosureReason, Date},
+};
+
+/// Information about a day on which a canteen is closed.
+#[derive(SimpleObject, Debug)]
+pub(in super::super) struct Closure {
+    /// The day the canteen is closed at.
+    date: Date,
+    /// The reason the canteen is closed for.
+    reason: ClosureReason,
+    /// An optional note further describing the closure.
+    note: Option<String>,
+}
+
+/// The state of the meal plan of a canteen at a particular day.
+#[derive(Enum, Debug, Copy, Clone, Eq, PartialEq)]
+pub(in super::super) enum MealPlanStatus {
+    /// Dishes are planned to be served.
+    Available,
+    /// The canteen is closed, see the corresponding closure for a reason.
+    Closed,
+    /// No meal plan is published for this day (yet).
+    NotPublished,
+}
+
+impl From<model::Closure> for Closure {
+    fn from(value: model::Closure) -> Self {
+        Self {
+            date: value.date,
+            reason: value.reason,
+            note: value.note,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     interface::persistent_data::model,
-    layer::trigger::api::util::ApiUtil,
+    layer::trigger::api::{
+        query_limits::{LIST_FACTOR, QUERY_COST},
+        util::ApiUtil,
+    },
     util::{Date, Uuid},
 };
 use async_graphql::{ComplexObject, Context, Result, SimpleObject};
@@ -34,6 +37,7 @@ impl Line {
 
     /// Provides the meals offered at this line on a given day. Requires a date.
     #[instrument(skip(ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
     async fn meals(&self, ctx: &Context<'_>, date: Date) -> Result<Option<Vec<Meal>>> {
         let data_access = ctx.get_data_access();
         let meals = data_access
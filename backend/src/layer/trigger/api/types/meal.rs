@@ -1,3 +1,4 @@
+use crate::layer::trigger::api::query_limits::{LIST_FACTOR, QUERY_COST};
 use crate::layer::trigger::api::util::ApiUtil;
 use crate::util::FoodType;
 use crate::{
@@ -52,8 +53,17 @@ impl Meal {
         Ok(additives)
     }
 
+    /// Provides the raw allergen and additive codes of this meal which could not be recognized.
+    #[instrument(skip(ctx))]
+    async fn unknown_codes(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let data_access = ctx.get_data_access();
+        let codes = data_access.get_unknown_codes(self.id).await?;
+        Ok(codes)
+    }
+
     /// Provides the images belonging to this meal
     #[instrument(skip(ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
     async fn images(&self, ctx: &Context<'_>) -> Result<Vec<Image>> {
         let data_access = ctx.get_data_access();
         let client_id = ctx.get_client_id().ok();
@@ -68,6 +78,7 @@ impl Meal {
 
     /// Provides the sides belonging to this meal.
     #[instrument(skip(ctx))]
+    #[graphql(complexity = "QUERY_COST + LIST_FACTOR * child_complexity")]
     async fn sides(&self, ctx: &Context<'_>) -> Result<Vec<Side>> {
         let data_access = ctx.get_data_access();
         let sides = data_access
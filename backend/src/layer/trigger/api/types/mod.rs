@@ -1,8 +1,10 @@
 pub(super) mod additional_data;
 pub(super) mod auth_info;
 pub(super) mod canteen;
+pub(super) mod closure;
 pub(super) mod image;
 pub(super) mod line;
 pub(super) mod meal;
+pub(super) mod personal_data;
 pub(super) mod price;
 pub(super) mod side;
@@ -0,0 +1,139 @@
+use std::io::ErrorKind;
+
+use crate::{
+    interface::persistent_data::model,
+    layer::trigger::api::{query_limits::QUERY_COST, util::ApiUtil},
+    util::{image_id_to_url, Date, ReportReason, Uuid, IMAGE_EXTENSION},
+};
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use base64::{engine::general_purpose, Engine};
+use tracing::instrument;
+
+/// All data stored in relation to a client id.
+#[derive(SimpleObject, Debug)]
+pub(in super::super) struct MyData {
+    /// The client id all this data belongs to.
+    pub(in super::super) client_id: Uuid,
+    /// All ratings of meals by the client.
+    pub(in super::super) ratings: Vec<MyRating>,
+    /// All votes of images by the client.
+    pub(in super::super) image_votes: Vec<MyImageVote>,
+    /// All reports of images by the client.
+    pub(in super::super) reports: Vec<MyReport>,
+    /// All images uploaded by the client, including hidden ones.
+    pub(in super::super) images: Vec<MyImage>,
+}
+
+/// A rating of a meal by the client.
+#[derive(SimpleObject, Debug)]
+pub(in super::super) struct MyRating {
+    /// The id of the rated meal.
+    meal_id: Uuid,
+    /// The rating, between 1 and 5.
+    rating: u32,
+}
+
+impl From<model::MealRating> for MyRating {
+    fn from(value: model::MealRating) -> Self {
+        Self {
+            meal_id: value.meal_id,
+            rating: value.rating,
+        }
+    }
+}
+
+/// A vote of an image by the client.
+#[derive(SimpleObject, Debug)]
+pub(in super::super) struct MyImageVote {
+    /// The id of the voted image.
+    image_id: Uuid,
+    /// Whether the image got upvoted (`true`) or downvoted (`false`).
+    upvote: bool,
+}
+
+impl From<model::ImageVote> for MyImageVote {
+    fn from(value: model::ImageVote) -> Self {
+        Self {
+            image_id: value.image_id,
+            upvote: value.upvote,
+        }
+    }
+}
+
+/// A report of an image by the client.
+#[derive(SimpleObject, Debug)]
+pub(in super::super) struct MyReport {
+    /// The id of the reported image.
+    image_id: Uuid,
+    /// The reason the image got reported for.
+    reason: ReportReason,
+    /// The date of the report.
+    report_date: Date,
+}
+
+impl From<model::ImageReport> for MyReport {
+    fn from(value: model::ImageReport) -> Self {
+        Self {
+            image_id: value.image_id,
+            reason: value.reason,
+            report_date: value.report_date,
+        }
+    }
+}
+
+/// An image uploaded by the client, including its moderation status.
+#[derive(SimpleObject, Debug)]
+#[graphql(complex)]
+pub(in super::super) struct MyImage {
+    /// The id of the image.
+    id: Uuid,
+    /// The id of the meal the image belongs to.
+    meal_id: Uuid,
+    /// The url of the image.
+    url: String,
+    /// The date the image got uploaded.
+    upload_date: Date,
+    /// Whether an administrator approved the image.
+    approved: bool,
+    /// Whether the image is shown to other users. Images get hidden when they got reported too often.
+    visible: bool,
+    /// The amount of open reports of the image.
+    report_count: u32,
+    /// The amount of users, who upvoted the image.
+    upvotes: u32,
+    /// The amount of users, who downvoted the image.
+    downvotes: u32,
+}
+
+#[ComplexObject]
+impl MyImage {
+    /// The image file as base64 encoded jpeg, or null if the file does not exist anymore.
+    #[instrument(skip(ctx))]
+    #[graphql(complexity = "QUERY_COST")]
+    async fn file(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let mut path = ctx.get_image_dir().join(self.id.to_string());
+        path.set_extension(IMAGE_EXTENSION);
+        match tokio::fs::read(path).await {
+            Ok(file) => Ok(Some(general_purpose::STANDARD.encode(file))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl From<model::UploadedImage> for MyImage {
+    fn from(value: model::UploadedImage) -> Self {
+        let image = value.image;
+        Self {
+            id: image.id,
+            meal_id: image.meal_id,
+            url: image_id_to_url(image.id),
+            upload_date: image.upload_date,
+            approved: image.approved,
+            visible: value.visible,
+            report_count: image.report_count,
+            upvotes: image.upvotes,
+            downvotes: image.downvotes,
+        }
+    }
+}
@@ -41,6 +41,14 @@ impl Side {
         Ok(additives)
     }
 
+    /// Provides the raw allergen and additive codes of this side which could not be recognized.
+    #[instrument(skip(ctx))]
+    async fn unknown_codes(&self, ctx: &Context<'_>) -> Result<Vec<String>> {
+        let data_access = ctx.get_data_access();
+        let codes = data_access.get_unknown_codes(self.id).await?;
+        Ok(codes)
+    }
+
     /// Provides the environment information of this meal.
     #[instrument(skip(ctx))]
     async fn environment_info(&self, ctx: &Context<'_>) -> Result<Option<EnvironmentInfo>> {
@@ -1,5 +1,7 @@
 //! Module containing some helper functions like for working inside the graphql context and processing authentication headers.
-use async_graphql::{Context, UploadValue};
+use std::path::{Path, PathBuf};
+
+use async_graphql::{Context, ErrorExtensions, UploadValue};
 use base64::{engine::general_purpose, Engine};
 use futures::AsyncReadExt;
 use sha2::{Digest, Sha512};
@@ -7,15 +9,20 @@ use thiserror::Error;
 
 use crate::{
     interface::{api_command::Command, persistent_data::RequestDataAccess},
-    util::Uuid,
+    util::{ApiKeyScope, Uuid},
 };
 
-use super::auth::{self, AuthInfo};
+use super::{
+    auth::{self, AuthInfo},
+    rate_limit::{RateLimitCategory, RequestRateLimit},
+};
 
 /// Type for storing the data access class inside the graphql context.
 pub type DataBox = Box<dyn RequestDataAccess + Sync + Send + 'static>;
 /// Type for storing the command implementations inside the graphql context.
 pub type CommandBox = Box<dyn Command + Sync + Send + 'static>;
+/// Type for storing the directory containing all image files inside the graphql context.
+pub struct ImageDir(pub PathBuf);
 
 /// Utility trait with convenience methods for accessing data stored inside the graphql context.
 pub trait ApiUtil {
@@ -24,13 +31,27 @@ pub trait ApiUtil {
     /// Returns access to the datastore.
     fn get_data_access(&self) -> &(dyn RequestDataAccess + Sync + Send);
 
+    /// Returns the directory containing all image files.
+    fn get_image_dir(&self) -> &Path;
+
     /// Returns all information about the authentication status of this request.
     fn get_auth_info(&self) -> &AuthInfo;
 
-    /// Returns whether this request is authenticated correctly.
+    /// Returns whether this request is authenticated correctly, independent of the scopes of its api key.
     /// # Errors
     /// if no valid authentication present
-    fn check_authentication(&self) -> auth::AuthResult<()>;
+    fn check_authenticated(&self) -> auth::AuthResult<()>;
+
+    /// Returns whether this request is authenticated correctly and its api key permits mutations of the given scope.
+    /// # Errors
+    /// if no valid authentication present or the api key lacks the scope
+    fn check_authentication(&self, scope: ApiKeyScope) -> auth::AuthResult<()>;
+
+    /// Takes a token of the given rate limit category from all callers of this request.
+    /// Requests not passing through the web server are not limited.
+    /// # Errors
+    /// if the rate limit of any caller is exceeded, including when to retry
+    fn check_rate_limit(&self, category: RateLimitCategory) -> async_graphql::Result<()>;
 
     /// Gets the provided client id, if any.
     /// # Errors
@@ -47,19 +68,38 @@ impl<'a> ApiUtil for Context<'a> {
         self.data_unchecked::<DataBox>().as_ref()
     }
 
+    fn get_image_dir(&self) -> &Path {
+        &self.data_unchecked::<ImageDir>().0
+    }
+
     fn get_auth_info(&self) -> &AuthInfo {
         self.data_unchecked::<AuthInfo>()
     }
 
-    fn check_authentication(&self) -> auth::AuthResult<()> {
-        if self.data_unchecked::<AuthInfo>().authenticated.is_ok() {
+    fn check_authenticated(&self) -> auth::AuthResult<()> {
+        let auth_info = self.get_auth_info();
+        if auth_info.authenticated.is_err() {
+            Err(auth::AuthError::MissingOrInvalidAuth(auth_info.clone()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_authentication(&self, scope: ApiKeyScope) -> auth::AuthResult<()> {
+        self.check_authenticated()?;
+        if self.get_auth_info().scopes.contains(&scope) {
             Ok(())
         } else {
-            let auth_info = self.get_auth_info();
-            Err(auth::AuthError::MissingOrInvalidAuth(auth_info.clone()))
+            Err(auth::AuthError::MissingScope(scope))
         }
     }
 
+    fn check_rate_limit(&self, category: RateLimitCategory) -> async_graphql::Result<()> {
+        self.data_opt::<RequestRateLimit>()
+            .map_or(Ok(()), |limit| limit.check(category))
+            .map_err(|e| e.extend())
+    }
+
     fn get_client_id(&self) -> auth::AuthResult<Uuid> {
         self.data_unchecked::<AuthInfo>()
             .client_id
@@ -112,6 +152,16 @@ pub enum UploadError {
     InvalidHash(String, String),
 }
 
+impl ErrorExtensions for UploadError {
+    fn extend(&self) -> async_graphql::Error {
+        let code = match self {
+            Self::IoError(_) => "INVALID_UPLOAD",
+            Self::HashNotBase64 | Self::InvalidHash(..) => "INVALID_HASH",
+        };
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| e.set("code", code))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used)]
@@ -0,0 +1,582 @@
+//! Module containing the registry of all scheduled jobs, see [`JobRegistry`].
+use std::{
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Local};
+use croner::Cron;
+use tokio_cron_scheduler::JobScheduler;
+use tracing::{info, info_span, warn, Instrument};
+use uuid::Uuid;
+
+use crate::interface::{
+    admin_notification::{AdminNotification, JobFailureInfo},
+    job_control::{JobControl, JobError, JobOutcome, JobRun, JobStatus, Result},
+    persistent_data::JobLockDataAccess,
+};
+
+use super::scheduler::RetryPolicy;
+
+/// Shared access to the locks coordinating jobs between multiple instances.
+pub(super) type ArcJobLocks = Arc<dyn JobLockDataAccess>;
+
+/// Components shared by all jobs when running.
+#[derive(Clone)]
+pub(super) struct JobContext {
+    /// Locks coordinating jobs between multiple instances.
+    pub(super) locks: ArcJobLocks,
+    /// Notification of administrators when jobs fail repeatedly.
+    pub(super) admin_notification: Arc<dyn AdminNotification>,
+    /// Number of consecutive failed runs after which administrators get notified. `0` disables notifications.
+    pub(super) failure_notification_threshold: u32,
+}
+
+/// Maximum delay between the time a job is scheduled at and it actually getting started by the scheduler.
+const MAX_SCHEDULING_DELAY: Duration = Duration::minutes(1);
+
+type JobFuture = Pin<Box<dyn Future<Output = std::result::Result<(), String>> + Send>>;
+
+/// Action to execute when a job runs. Errors are described by a message.
+pub(super) type JobAction = Box<dyn Fn() -> JobFuture + Send + Sync>;
+
+#[derive(Default)]
+struct JobState {
+    running: bool,
+    last_run: Option<JobRun>,
+    last_success: Option<DateTime<Local>>,
+    consecutive_failures: u32,
+}
+
+/// A named job which can be run by schedule or manually and keeps track of its runs.
+pub(super) struct ScheduledJob {
+    name: &'static str,
+    schedule: String,
+    action: JobAction,
+    retry_policy: RetryPolicy,
+    state: Mutex<JobState>,
+}
+
+impl ScheduledJob {
+    pub(super) fn new(name: &'static str, schedule: String, action: JobAction) -> Self {
+        Self {
+            name,
+            schedule,
+            action,
+            retry_policy: RetryPolicy::default(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Sets how the job gets retried when failing.
+    pub(super) const fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub(super) const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Creates a job running `action` on the shared `target`, describing errors by their [`Display`] representation.
+    pub(super) fn for_target<T, F, E>(
+        name: &'static str,
+        schedule: String,
+        target: Arc<T>,
+        action: impl Fn(Arc<T>) -> F + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Send + Sync + 'static,
+        F: Future<Output = std::result::Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        Self::new(
+            name,
+            schedule,
+            Box::new(move || {
+                let result = action(target.clone());
+                Box::pin(async move { result.await.map_err(|e| e.to_string()) })
+            }),
+        )
+    }
+
+    pub(super) fn schedule(&self) -> &str {
+        &self.schedule
+    }
+
+    /// Determines the time of the latest scheduled run at or before `now`.
+    /// This is the same for all instances triggered by the same tick.
+    pub(super) fn scheduled_time(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let cron = Cron::new(&self.schedule)
+            .with_seconds_required()
+            .with_dom_and_dow()
+            .parse()
+            .ok()?;
+        cron.iter_from(now - MAX_SCHEDULING_DELAY)
+            .take_while(|time| *time <= now)
+            .last()
+    }
+
+    fn state(&self) -> MutexGuard<'_, JobState> {
+        self.state
+            .lock()
+            .expect("job state mutex should not be poisoned")
+    }
+
+    /// Runs the job if it is not already running on this or another instance and records the outcome.
+    /// Runs scheduled at `scheduled_at` are only performed by a single instance.
+    /// Failed runs are retried according to the job's [`RetryPolicy`] while holding the lock.
+    /// Administrators get notified once the configured number of consecutive runs failed.
+    pub(super) async fn run(&self, context: &JobContext, scheduled_at: Option<DateTime<Local>>) {
+        {
+            let mut state = self.state();
+            if state.running {
+                warn!("Skipped run of job '{}' as it is still running.", self.name);
+                return;
+            }
+            state.running = true;
+        }
+
+        let started_at = Local::now();
+        let start = Instant::now();
+        let mut retries = 0;
+
+        let outcome = match context.locks.try_lock_job(self.name, scheduled_at).await {
+            Ok(Some(lock)) => {
+                let outcome = loop {
+                    let outcome = self.run_action(start).await;
+                    if outcome == JobOutcome::Success || retries >= self.retry_policy.max_retries {
+                        break outcome;
+                    }
+                    let backoff = self.retry_policy.backoff(retries);
+                    retries += 1;
+                    info!(
+                        "Retrying job '{}' in {backoff:?} (retry {retries} of {}).",
+                        self.name, self.retry_policy.max_retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                };
+                if let Err(e) = lock.release().await {
+                    warn!("Could not release lock of job '{}': {e}", self.name);
+                }
+                outcome
+            }
+            Ok(None) => {
+                info!(
+                    "Skipped run of job '{}' as it is handled by another instance.",
+                    self.name
+                );
+                self.state().running = false;
+                return;
+            }
+            Err(e) => {
+                warn!("Could not acquire lock for job '{}': {e}", self.name);
+                JobOutcome::Failure {
+                    message: format!("could not acquire lock: {e}"),
+                }
+            }
+        };
+
+        let failure = {
+            let mut state = self.state();
+            let failure = match &outcome {
+                JobOutcome::Success => {
+                    state.last_success = Some(started_at);
+                    state.consecutive_failures = 0;
+                    None
+                }
+                JobOutcome::Failure { message } => {
+                    state.consecutive_failures += 1;
+                    (state.consecutive_failures == context.failure_notification_threshold).then(
+                        || JobFailureInfo {
+                            job_name: self.name.to_string(),
+                            consecutive_failures: state.consecutive_failures,
+                            last_error: message.clone(),
+                            failed_at: Local::now(),
+                        },
+                    )
+                }
+            };
+            state.last_run = Some(JobRun {
+                started_at,
+                duration_secs: start.elapsed().as_secs_f64(),
+                retries,
+                outcome,
+            });
+            state.running = false;
+            failure
+        };
+
+        if let Some(info) = failure {
+            warn!(
+                "Job '{}' failed {} times in a row, notifying administrator.",
+                self.name, info.consecutive_failures
+            );
+            context
+                .admin_notification
+                .notify_admin_job_failure(info)
+                .await;
+        }
+    }
+
+    async fn run_action(&self, start: Instant) -> JobOutcome {
+        info!("Started job '{}'.", self.name);
+        match (self.action)()
+            .instrument(info_span!("job", name = self.name))
+            .await
+        {
+            Ok(()) => {
+                info!("Finished job '{}' in {:?}.", self.name, start.elapsed());
+                JobOutcome::Success
+            }
+            Err(message) => {
+                warn!(
+                    "Job '{}' failed after {:?}: {message}",
+                    self.name,
+                    start.elapsed()
+                );
+                JobOutcome::Failure { message }
+            }
+        }
+    }
+}
+
+/// Registry of all scheduled jobs providing their status and allowing them to be triggered manually.
+#[derive(Clone)]
+pub struct JobRegistry {
+    scheduler: JobScheduler,
+    context: JobContext,
+    jobs: Arc<Vec<(Uuid, Arc<ScheduledJob>)>>,
+    running: Arc<AtomicBool>,
+}
+
+impl JobRegistry {
+    pub(super) fn new(
+        scheduler: JobScheduler,
+        context: JobContext,
+        jobs: Vec<(Uuid, Arc<ScheduledJob>)>,
+    ) -> Self {
+        Self {
+            scheduler,
+            context,
+            jobs: Arc::new(jobs),
+            running: Arc::default(),
+        }
+    }
+
+    /// Records whether the scheduler starting the jobs is running.
+    pub(super) fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::SeqCst);
+    }
+
+    fn find_job(&self, name: &str) -> Result<Arc<ScheduledJob>> {
+        self.jobs
+            .iter()
+            .find(|(_, job)| job.name == name)
+            .map(|(_, job)| job.clone())
+            .ok_or_else(|| JobError::NoSuchJob(name.to_string()))
+    }
+}
+
+#[async_trait]
+impl JobControl for JobRegistry {
+    async fn get_jobs(&self) -> Vec<JobStatus> {
+        let mut status = Vec::with_capacity(self.jobs.len());
+        for (id, job) in self.jobs.iter() {
+            let next_run = self
+                .scheduler
+                .clone()
+                .next_tick_for_job(*id)
+                .await
+                .ok()
+                .flatten()
+                .map(|time| time.with_timezone(&Local));
+            let state = job.state();
+            status.push(JobStatus {
+                name: job.name.to_string(),
+                schedule: job.schedule.clone(),
+                running: state.running,
+                last_run: state.last_run.clone(),
+                last_success: state.last_success,
+                next_run,
+                consecutive_failures: state.consecutive_failures,
+            });
+        }
+        status
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    async fn trigger_job(&self, name: &str) -> Result<()> {
+        let job = self.find_job(name)?;
+        if job.state().running {
+            return Err(JobError::AlreadyRunning(name.to_string()));
+        }
+        info!("Manually triggered job '{name}'.");
+        let context = self.context.clone();
+        tokio::spawn(async move { job.run(&context, None).await });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use chrono::{Local, TimeZone};
+    use tokio_cron_scheduler::JobScheduler;
+    use uuid::Uuid;
+
+    use super::{JobContext, JobRegistry, ScheduledJob};
+    use crate::{
+        interface::job_control::{JobControl, JobError, JobOutcome},
+        layer::trigger::scheduling::{
+            mocks::{JobFailureNotificationMock, JobLockMock},
+            scheduler::RetryPolicy,
+        },
+    };
+
+    fn context(locks: JobLockMock, notification: JobFailureNotificationMock) -> JobContext {
+        JobContext {
+            locks: Arc::new(locks),
+            admin_notification: Arc::new(notification),
+            failure_notification_threshold: 2,
+        }
+    }
+
+    /// Job failing the first `failures` attempts, counting all attempts in `attempts`.
+    fn flaky_job(failures: u32, attempts: Arc<AtomicU32>) -> ScheduledJob {
+        ScheduledJob::new(
+            "flaky",
+            "0 0 * * * *".into(),
+            Box::new(move || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    if attempt < failures {
+                        Err(format!("attempt {attempt} failed"))
+                    } else {
+                        Ok(())
+                    }
+                })
+            }),
+        )
+    }
+
+    const RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(15),
+    };
+
+    fn job(name: &'static str, fail: bool) -> Arc<ScheduledJob> {
+        Arc::new(ScheduledJob::new(
+            name,
+            "0 0 * * * *".into(),
+            Box::new(move || {
+                Box::pin(async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    if fail {
+                        Err("failed".into())
+                    } else {
+                        Ok(())
+                    }
+                })
+            }),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_run_records_outcome() {
+        let success = job("success", false);
+        let failure = job("failure", true);
+        let context = context(
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        );
+        success.run(&context, None).await;
+        failure.run(&context, None).await;
+
+        let registry = JobRegistry::new(
+            JobScheduler::new().await.unwrap(),
+            context,
+            vec![(Uuid::new_v4(), success), (Uuid::new_v4(), failure)],
+        );
+        let jobs = registry.get_jobs().await;
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].name, "success");
+        assert!(!jobs[0].running);
+        assert_eq!(
+            jobs[0].last_run.as_ref().unwrap().outcome,
+            JobOutcome::Success
+        );
+        assert!(jobs[0].last_success.is_some());
+
+        assert_eq!(
+            jobs[1].last_run.as_ref().unwrap().outcome,
+            JobOutcome::Failure {
+                message: "failed".into()
+            }
+        );
+        assert!(jobs[1].last_success.is_none());
+        assert_eq!(jobs[1].consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_job() {
+        let registry = JobRegistry::new(
+            JobScheduler::new().await.unwrap(),
+            context(
+                JobLockMock::default(),
+                JobFailureNotificationMock::default(),
+            ),
+            vec![(Uuid::new_v4(), job("job", false))],
+        );
+
+        assert!(matches!(
+            registry.trigger_job("unknown").await,
+            Err(JobError::NoSuchJob(_))
+        ));
+
+        registry.trigger_job("job").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(registry.get_jobs().await[0].running);
+        assert!(matches!(
+            registry.trigger_job("job").await,
+            Err(JobError::AlreadyRunning(_))
+        ));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let status = &registry.get_jobs().await[0];
+        assert!(!status.running);
+        assert_eq!(
+            status.last_run.as_ref().unwrap().outcome,
+            JobOutcome::Success
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_locked_elsewhere() {
+        let locks = JobLockMock::default();
+        locks.lock_elsewhere("job");
+
+        let job = job("job", false);
+        job.run(&context(locks, JobFailureNotificationMock::default()), None)
+            .await;
+
+        assert!(!job.state().running);
+        assert!(job.state().last_run.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_scheduled_once() {
+        let context = context(
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        );
+        let scheduled_at = Some(Local.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap());
+
+        let first = job("job", false);
+        first.run(&context, scheduled_at).await;
+        let first_run = first.state().last_run.clone().unwrap();
+
+        // a second instance with the same job
+        let second = job("job", false);
+        second.run(&context, scheduled_at).await;
+        assert!(second.state().last_run.is_none());
+
+        // manual runs are still possible
+        first.run(&context, None).await;
+        assert_ne!(first.state().last_run.clone().unwrap(), first_run);
+    }
+
+    #[test]
+    fn test_scheduled_time() {
+        let job = job("job", false);
+        let now = Local.with_ymd_and_hms(2024, 1, 1, 13, 0, 20).unwrap();
+        assert_eq!(
+            job.scheduled_time(now),
+            Some(Local.with_ymd_and_hms(2024, 1, 1, 13, 0, 0).unwrap())
+        );
+
+        let now = Local.with_ymd_and_hms(2024, 1, 1, 13, 30, 0).unwrap();
+        assert_eq!(job.scheduled_time(now), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_retries() {
+        let attempts = Arc::new(AtomicU32::default());
+        let job = flaky_job(2, attempts.clone()).with_retry_policy(RETRY_POLICY);
+        let notification = JobFailureNotificationMock::default();
+        job.run(&context(JobLockMock::default(), notification.clone()), None)
+            .await;
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        let state = job.state();
+        let last_run = state.last_run.as_ref().unwrap();
+        assert_eq!(last_run.outcome, JobOutcome::Success);
+        assert_eq!(last_run.retries, 2);
+        // waited for the backoff in between
+        assert!(last_run.duration_secs >= 0.025);
+        assert_eq!(state.consecutive_failures, 0);
+        drop(state);
+        assert!(notification.get_failures().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_notifies_repeated_failures() {
+        let attempts = Arc::new(AtomicU32::default());
+        let job = flaky_job(u32::MAX, attempts.clone()).with_retry_policy(RETRY_POLICY);
+        let notification = JobFailureNotificationMock::default();
+        let context = context(JobLockMock::default(), notification.clone());
+
+        job.run(&context, None).await;
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+        assert_eq!(job.state().last_run.as_ref().unwrap().retries, 3);
+        assert!(notification.get_failures().is_empty());
+
+        // notified exactly once when reaching the threshold
+        job.run(&context, None).await;
+        job.run(&context, None).await;
+        let failures = notification.get_failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].job_name, "flaky");
+        assert_eq!(failures[0].consecutive_failures, 2);
+        assert_eq!(failures[0].last_error, "attempt 7 failed");
+        assert_eq!(job.state().consecutive_failures, 3);
+    }
+
+    #[tokio::test]
+    async fn test_run_without_retry_policy() {
+        let attempts = Arc::new(AtomicU32::default());
+        let job = flaky_job(1, attempts.clone());
+        job.run(
+            &context(
+                JobLockMock::default(),
+                JobFailureNotificationMock::default(),
+            ),
+            None,
+        )
+        .await;
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(job.state().last_run.as_ref().unwrap().retries, 0);
+        assert_eq!(job.state().consecutive_failures, 1);
+    }
+}
@@ -1,11 +1,29 @@
-//! This crate contains mocks of [`MensaParseScheduling`] and [`ImageReviewScheduling`] for testing.
+//! This crate contains mocks of [`MensaParseScheduling`], [`MaintenanceScheduling`], [`JobLockDataAccess`] and [`AdminNotification`] for testing.
 #![cfg(test)]
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use tracing::debug;
 
-use crate::interface::mealplan_management::MensaParseScheduling;
+use crate::interface::{
+    admin_notification::{
+        AdminNotification, DataQualityReport, ImageReportInfo, JobFailureInfo,
+        Result as MailResult, UnknownCodeInfo,
+    },
+    maintenance::{MaintenanceScheduling, Result as MaintenanceResult},
+    mealplan_management::MensaParseScheduling,
+    mensa_parser::ParseError,
+    persistent_data::{JobLock, JobLockDataAccess, Result as DataResult},
+};
+use crate::util::Uuid;
+
+use super::scheduler::{
+    EXPIRE_REPORTS_JOB, ORPHANED_IMAGES_JOB, PURGE_DATA_JOB, REFRESH_STATISTICS_JOB,
+};
 
 /// Mock for [`MensaParseScheduling`].
 #[derive(Default, Clone)]
@@ -44,24 +62,198 @@ impl MensaParseMock {
 impl MensaParseScheduling for MensaParseMock {
     /// Initiate the parsing procedure of the canteen-website.
     /// Only parse meals of the current date.
-    async fn start_update_parsing(&self) {
+    async fn start_update_parsing(&self) -> Result<(), ParseError> {
         debug!("start_update_parsing");
         *self
             .update_calls
             .lock()
             .expect("failed to lock mutex for `update_calls` counter") += 1;
-
-        return;
+        Ok(())
     }
 
     /// Initiate the parsing procedure of the canteen-website.
     /// Only parse meals for the next four weeks.
-    async fn start_full_parsing(&self) {
+    async fn start_full_parsing(&self) -> Result<(), ParseError> {
         debug!("start_full_parsing");
         *self
             .full_calls
             .lock()
             .expect("failed to lock mutex for `full_calls` counter") += 1;
-        return;
+        Ok(())
+    }
+}
+
+/// Mock for [`MaintenanceScheduling`].
+#[derive(Default, Clone)]
+pub struct MaintenanceMock {
+    calls: Arc<Mutex<HashMap<&'static str, u32>>>,
+}
+
+impl MaintenanceMock {
+    /// Returns the number of calls to the task run by the job of the given name.
+    ///
+    /// # Panics
+    /// if the mutex could not be acquired
+    #[must_use]
+    pub fn get_calls(&self, job: &str) -> u32 {
+        self.calls
+            .lock()
+            .expect("failed to lock mutex for `calls` counter")
+            .get(job)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn count(&self, job: &'static str) {
+        debug!("{job}");
+        *self
+            .calls
+            .lock()
+            .expect("failed to lock mutex for `calls` counter")
+            .entry(job)
+            .or_default() += 1;
+    }
+}
+
+#[async_trait]
+impl MaintenanceScheduling for MaintenanceMock {
+    async fn expire_image_reports(&self) -> MaintenanceResult<()> {
+        self.count(EXPIRE_REPORTS_JOB);
+        Ok(())
+    }
+
+    async fn remove_orphaned_images(&self) -> MaintenanceResult<()> {
+        self.count(ORPHANED_IMAGES_JOB);
+        Ok(())
+    }
+
+    async fn refresh_statistics(&self) -> MaintenanceResult<()> {
+        self.count(REFRESH_STATISTICS_JOB);
+        Ok(())
+    }
+
+    async fn purge_old_data(&self) -> MaintenanceResult<()> {
+        self.count(PURGE_DATA_JOB);
+        Ok(())
+    }
+}
+
+type ClaimedRuns = HashSet<(String, DateTime<Local>)>;
+
+/// Mock for [`JobLockDataAccess`], simulating locks shared between instances.
+#[derive(Default, Clone)]
+pub struct JobLockMock {
+    locked: Arc<Mutex<HashSet<String>>>,
+    claimed: Arc<Mutex<ClaimedRuns>>,
+}
+
+impl JobLockMock {
+    /// Marks the given job as running on another instance.
+    ///
+    /// # Panics
+    /// if the mutex could not be acquired
+    pub fn lock_elsewhere(&self, job: &str) {
+        self.locked
+            .lock()
+            .expect("failed to lock mutex for locked jobs")
+            .insert(job.into());
+    }
+}
+
+#[async_trait]
+impl JobLockDataAccess for JobLockMock {
+    async fn try_lock_job(
+        &self,
+        job: &str,
+        scheduled_at: Option<DateTime<Local>>,
+    ) -> DataResult<Option<Box<dyn JobLock>>> {
+        let mut locked = self
+            .locked
+            .lock()
+            .expect("failed to lock mutex for locked jobs");
+        if locked.contains(job) {
+            return Ok(None);
+        }
+        if let Some(scheduled_at) = scheduled_at {
+            let newly_claimed = self
+                .claimed
+                .lock()
+                .expect("failed to lock mutex for claimed runs")
+                .insert((job.into(), scheduled_at));
+            if !newly_claimed {
+                return Ok(None);
+            }
+        }
+        locked.insert(job.into());
+        drop(locked);
+        Ok(Some(Box::new(JobLockGuardMock {
+            job: job.into(),
+            locked: self.locked.clone(),
+        })))
+    }
+}
+
+struct JobLockGuardMock {
+    job: String,
+    locked: Arc<Mutex<HashSet<String>>>,
+}
+
+#[async_trait]
+impl JobLock for JobLockGuardMock {
+    async fn release(self: Box<Self>) -> DataResult<()> {
+        self.locked
+            .lock()
+            .expect("failed to lock mutex for locked jobs")
+            .remove(&self.job);
+        Ok(())
+    }
+}
+
+/// Mock for [`AdminNotification`], recording notifications about failed jobs.
+#[derive(Default, Clone)]
+pub struct JobFailureNotificationMock {
+    failures: Arc<Mutex<Vec<JobFailureInfo>>>,
+}
+
+impl JobFailureNotificationMock {
+    /// Returns all notifications about failed jobs sent so far.
+    ///
+    /// # Panics
+    /// if the mutex could not be acquired
+    #[must_use]
+    pub fn get_failures(&self) -> Vec<JobFailureInfo> {
+        self.failures
+            .lock()
+            .expect("failed to lock mutex for job failures")
+            .clone()
+    }
+}
+
+#[async_trait]
+impl AdminNotification for JobFailureNotificationMock {
+    async fn notify_admin_image_report(&self, _info: ImageReportInfo) {}
+
+    async fn notify_admin_image_verified(&self, _image_id: Uuid) -> MailResult<()> {
+        Ok(())
+    }
+
+    async fn notify_admin_image_deleted(&self, _image_id: Uuid) -> MailResult<()> {
+        Ok(())
+    }
+
+    async fn notify_admin_data_quality_report(
+        &self,
+        _report: &DataQualityReport,
+    ) -> MailResult<()> {
+        Ok(())
+    }
+
+    async fn notify_admin_unknown_codes(&self, _codes: Vec<UnknownCodeInfo>) {}
+
+    async fn notify_admin_job_failure(&self, info: JobFailureInfo) {
+        self.failures
+            .lock()
+            .expect("failed to lock mutex for job failures")
+            .push(info);
     }
 }
@@ -1,3 +1,4 @@
 //! This component triggers regular events such as querying the current menu.
+pub mod job_registry;
 pub mod mocks;
 pub mod scheduler;
@@ -1,11 +1,29 @@
 //! Module containing the scheduler to schedule regular actions.
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use crate::interface::mealplan_management::MensaParseScheduling;
+use crate::interface::{
+    admin_notification::AdminNotification, maintenance::MaintenanceScheduling,
+    mealplan_management::MensaParseScheduling, persistent_data::JobLockDataAccess,
+};
 
 use tokio::sync::Notify;
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{info, info_span, Instrument};
+use tracing::info;
+
+use super::job_registry::{JobContext, JobRegistry, ScheduledJob};
+
+/// Name of the job parsing the current day's meal plan.
+pub const UPDATE_PARSING_JOB: &str = "update_parsing";
+/// Name of the job parsing all available meal plan data.
+pub const FULL_PARSING_JOB: &str = "full_parsing";
+/// Name of the job removing expired image reports.
+pub const EXPIRE_REPORTS_JOB: &str = "expire_image_reports";
+/// Name of the job removing image files not belonging to any image.
+pub const ORPHANED_IMAGES_JOB: &str = "remove_orphaned_images";
+/// Name of the job refreshing the database statistics.
+pub const REFRESH_STATISTICS_JOB: &str = "refresh_statistics";
+/// Name of the job removing meal plan data past the retention period.
+pub const PURGE_DATA_JOB: &str = "purge_old_data";
 
 /// Structure containing [cron](https://cron.help/)-like schedules for running actions regularly.
 ///
@@ -15,6 +33,39 @@ pub struct ScheduleInfo {
     pub update_parse_schedule: String,
     /// Cron-like schedule for running the meal plan update process for all available meal plan data, see [`MensaParseScheduling`].
     pub full_parse_schedule: String,
+    /// Cron-like schedule for removing expired image reports, see [`MaintenanceScheduling`].
+    pub expire_reports_schedule: String,
+    /// Cron-like schedule for removing image files not belonging to any image, see [`MaintenanceScheduling`].
+    pub orphaned_images_schedule: String,
+    /// Cron-like schedule for refreshing the database statistics, see [`MaintenanceScheduling`].
+    pub refresh_statistics_schedule: String,
+    /// Cron-like schedule for removing meal plan data past the retention period, see [`MaintenanceScheduling`].
+    pub purge_data_schedule: String,
+    /// Policies for retrying failed jobs, by job name. Jobs without policy are not retried.
+    pub retry_policies: HashMap<String, RetryPolicy>,
+    /// Number of consecutive failed runs of a job after which an administrator gets notified. `0` disables notifications.
+    pub failure_notification_threshold: u32,
+}
+
+/// Policy for retrying a failed job with exponential backoff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first failed attempt.
+    pub max_retries: u32,
+    /// Time to wait before the first retry. It doubles with every further retry.
+    pub initial_backoff: Duration,
+    /// Maximum time to wait between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns the time to wait before retrying after `retry` retries have already been made.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -26,7 +77,8 @@ enum State {
 
 /// Class fro planning regular events.
 pub struct Scheduler {
-    scheduler: JobScheduler,
+    cron: JobScheduler,
+    registry: JobRegistry,
     state: State,
 }
 
@@ -40,68 +92,109 @@ impl Scheduler {
     pub async fn new(
         info: ScheduleInfo,
         parse_scheduling: impl MensaParseScheduling + 'static,
+        maintenance: impl MaintenanceScheduling + 'static,
+        job_locks: impl JobLockDataAccess + 'static,
+        admin_notification: impl AdminNotification + 'static,
     ) -> Self {
         let scheduler = JobScheduler::new()
             .await
             .expect("cannot initialize scheduler");
 
+        let timezone = chrono::Local::now().timezone();
+        let mut jobs = Vec::new();
+
         // === mensa parsing ===
 
         let mensa_parse = Arc::new(parse_scheduling);
-        let timezone = chrono::Local::now().timezone();
 
-        // mensa update parsing
-        let mensa_parse_update = mensa_parse.clone();
-        let update_parse_job =
-            Job::new_async_tz(&info.update_parse_schedule, timezone, move |_, _| {
-                let mensa_parse = mensa_parse_update.clone();
-                Box::pin(
-                    async move {
-                        info!("Started mensa update parsing.");
-                        let start: Instant = Instant::now();
-
-                        mensa_parse.start_update_parsing().await;
-
-                        info!("Finished mensa update parsing in {:?}.", start.elapsed());
-                    }
-                    .instrument(info_span!("update_parsing")),
-                )
+        jobs.push(ScheduledJob::for_target(
+            UPDATE_PARSING_JOB,
+            info.update_parse_schedule,
+            mensa_parse.clone(),
+            |mensa_parse| async move { mensa_parse.start_update_parsing().await },
+        ));
+        jobs.push(ScheduledJob::for_target(
+            FULL_PARSING_JOB,
+            info.full_parse_schedule,
+            mensa_parse,
+            |mensa_parse| async move { mensa_parse.start_full_parsing().await },
+        ));
+
+        // === maintenance ===
+
+        let maintenance = Arc::new(maintenance);
+
+        jobs.push(ScheduledJob::for_target(
+            EXPIRE_REPORTS_JOB,
+            info.expire_reports_schedule,
+            maintenance.clone(),
+            |maintenance| async move { maintenance.expire_image_reports().await },
+        ));
+        jobs.push(ScheduledJob::for_target(
+            ORPHANED_IMAGES_JOB,
+            info.orphaned_images_schedule,
+            maintenance.clone(),
+            |maintenance| async move { maintenance.remove_orphaned_images().await },
+        ));
+        jobs.push(ScheduledJob::for_target(
+            REFRESH_STATISTICS_JOB,
+            info.refresh_statistics_schedule,
+            maintenance.clone(),
+            |maintenance| async move { maintenance.refresh_statistics().await },
+        ));
+        jobs.push(ScheduledJob::for_target(
+            PURGE_DATA_JOB,
+            info.purge_data_schedule,
+            maintenance,
+            |maintenance| async move { maintenance.purge_old_data().await },
+        ));
+
+        let context = JobContext {
+            locks: Arc::new(job_locks),
+            admin_notification: Arc::new(admin_notification),
+            failure_notification_threshold: info.failure_notification_threshold,
+        };
+        let mut registered = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            let retry_policy = info
+                .retry_policies
+                .get(job.name())
+                .copied()
+                .unwrap_or_default();
+            let job = Arc::new(job.with_retry_policy(retry_policy));
+            let job_handle = job.clone();
+            let job_context = context.clone();
+            let cron_job = Job::new_async_tz(job.schedule(), timezone, move |_, _| {
+                let job = job_handle.clone();
+                let context = job_context.clone();
+                Box::pin(async move {
+                    let scheduled_at = job.scheduled_time(chrono::Local::now());
+                    job.run(&context, scheduled_at).await;
+                })
             })
-            .expect("could not create schedule for image reviewing");
+            .expect("could not create schedule for job");
+            let id = scheduler
+                .add(cron_job)
+                .await
+                .expect("could not add job to scheduler");
+            registered.push((id, job));
+        }
 
-        scheduler
-            .add(update_parse_job)
-            .await
-            .expect("could not add job for update parsing to scheduler");
-
-        // mensa full parsing
-        let full_parse_job = Job::new_async_tz(&info.full_parse_schedule, timezone, move |_, _| {
-            let mensa_parse = mensa_parse.clone();
-            Box::pin(
-                async move {
-                    info!("Started mensa full parsing.");
-                    let start: Instant = Instant::now();
-
-                    mensa_parse.start_full_parsing().await;
-
-                    info!("Finished mensa full parsing in {:?}.", start.elapsed());
-                }
-                .instrument(info_span!("full_parsing")),
-            )
-        })
-        .expect("could not create schedule for image reviewing");
-
-        scheduler
-            .add(full_parse_job)
-            .await
-            .expect("could not add job for full parsing to scheduler");
+        let registry = JobRegistry::new(scheduler.clone(), context, registered);
 
         Self {
-            scheduler,
+            cron: scheduler,
+            registry,
             state: State::Created,
         }
     }
 
+    /// Returns a handle to the registry of all scheduled jobs for querying their status and triggering them manually.
+    #[must_use]
+    pub fn job_control(&self) -> JobRegistry {
+        self.registry.clone()
+    }
+
     /// Starts the scheduler. It runs in the background until it is stopped with [`Self::shutdown()`].
     ///
     /// # Panics
@@ -112,11 +205,12 @@ impl Scheduler {
             State::Created,
             "scheduler should only be started once"
         );
-        self.scheduler
+        self.cron
             .start()
             .await
             .expect("scheduler should only be started once");
         self.state = State::Running;
+        self.registry.set_running(true);
         info!("Started scheduler.");
     }
 
@@ -130,15 +224,16 @@ impl Scheduler {
             State::Running,
             "scheduler should be started and not shut down"
         );
+        self.registry.set_running(false);
         let shutdown_finished = Arc::new(Notify::new());
 
         let shutdown_sender = shutdown_finished.clone();
-        self.scheduler.set_shutdown_handler(Box::new(move || {
+        self.cron.set_shutdown_handler(Box::new(move || {
             let shutdown_sender = shutdown_sender.clone();
             Box::pin(async move { shutdown_sender.notify_one() })
         }));
 
-        self.scheduler
+        self.cron
             .shutdown()
             .await
             .expect("could not shut down scheduler");
@@ -152,20 +247,48 @@ impl Scheduler {
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
-
-    use crate::layer::trigger::scheduling::mocks::MensaParseMock;
+    #![allow(clippy::unwrap_used)]
+    use crate::{
+        interface::job_control::{JobControl, JobOutcome},
+        layer::trigger::scheduling::mocks::{
+            JobFailureNotificationMock, JobLockMock, MaintenanceMock, MensaParseMock,
+        },
+    };
 
     use super::*;
+
+    const NIGHTLY: &str = "0 0 3 * * *";
+
+    fn schedule_info(full_parse_schedule: &str, update_parse_schedule: &str) -> ScheduleInfo {
+        ScheduleInfo {
+            full_parse_schedule: full_parse_schedule.into(),
+            update_parse_schedule: update_parse_schedule.into(),
+            expire_reports_schedule: NIGHTLY.into(),
+            orphaned_images_schedule: NIGHTLY.into(),
+            refresh_statistics_schedule: NIGHTLY.into(),
+            purge_data_schedule: NIGHTLY.into(),
+            retry_policies: HashMap::new(),
+            failure_notification_threshold: 3,
+        }
+    }
+
     #[tokio::test]
     async fn test_scheduling() {
         let info = ScheduleInfo {
-            full_parse_schedule: "*/1 * * * * *".into(),
-            update_parse_schedule: "*/2 * * * * *".into(),
+            purge_data_schedule: "*/5 * * * * *".into(),
+            ..schedule_info("*/1 * * * * *", "*/2 * * * * *")
         };
         let mensa_parser = MensaParseMock::default();
+        let maintenance = MaintenanceMock::default();
 
-        let mut scheduler = Scheduler::new(info, mensa_parser.clone()).await;
+        let mut scheduler = Scheduler::new(
+            info,
+            mensa_parser.clone(),
+            maintenance.clone(),
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        )
+        .await;
 
         scheduler.start().await;
 
@@ -184,18 +307,119 @@ mod tests {
             "update parse was not called right amount: {}",
             mensa_parser.get_update_calls()
         );
+        assert!(
+            (1..=3).contains(&maintenance.get_calls(PURGE_DATA_JOB)),
+            "data purging was not called right amount: {}",
+            maintenance.get_calls(PURGE_DATA_JOB)
+        );
+        assert_eq!(maintenance.get_calls(EXPIRE_REPORTS_JOB), 0);
+    }
+
+    #[tokio::test]
+    async fn test_job_control() {
+        let info = schedule_info("0 0 4 * * *", "0 */15 * * * *");
+        let mensa_parser = MensaParseMock::default();
+        let maintenance = MaintenanceMock::default();
+
+        let mut scheduler = Scheduler::new(
+            info,
+            mensa_parser.clone(),
+            maintenance.clone(),
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        )
+        .await;
+        let job_control = scheduler.job_control();
+        assert!(!job_control.is_running());
+        scheduler.start().await;
+        assert!(job_control.is_running());
+
+        let jobs = job_control.get_jobs().await;
+        let names: Vec<_> = jobs.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                UPDATE_PARSING_JOB,
+                FULL_PARSING_JOB,
+                EXPIRE_REPORTS_JOB,
+                ORPHANED_IMAGES_JOB,
+                REFRESH_STATISTICS_JOB,
+                PURGE_DATA_JOB
+            ]
+        );
+        assert!(jobs.iter().all(|job| job.next_run.is_some()));
+        assert!(jobs.iter().all(|job| job.last_run.is_none()));
+
+        job_control.trigger_job(FULL_PARSING_JOB).await.unwrap();
+        job_control.trigger_job(ORPHANED_IMAGES_JOB).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(mensa_parser.get_full_calls(), 1);
+        assert_eq!(mensa_parser.get_update_calls(), 0);
+        assert_eq!(maintenance.get_calls(ORPHANED_IMAGES_JOB), 1);
+        assert_eq!(maintenance.get_calls(REFRESH_STATISTICS_JOB), 0);
+
+        let full_parsing = &job_control.get_jobs().await[1];
+        assert_eq!(
+            full_parsing.last_run.as_ref().map(|run| &run.outcome),
+            Some(&JobOutcome::Success)
+        );
+        assert!(full_parsing.last_success.is_some());
+
+        scheduler.shutdown().await;
+        assert!(!job_control.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_multiple_instances() {
+        let locks = JobLockMock::default();
+        let mensa_parser = MensaParseMock::default();
+
+        let mut schedulers = Vec::new();
+        for _ in 0..2 {
+            let info = schedule_info("*/1 * * * * *", "*/2 * * * * *");
+            schedulers.push(
+                Scheduler::new(
+                    info,
+                    mensa_parser.clone(),
+                    MaintenanceMock::default(),
+                    locks.clone(),
+                    JobFailureNotificationMock::default(),
+                )
+                .await,
+            );
+        }
+        for scheduler in &mut schedulers {
+            scheduler.start().await;
+        }
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        for scheduler in &mut schedulers {
+            scheduler.shutdown().await;
+        }
+
+        // each run only happens on one of both instances
+        assert!(
+            (4..=6).contains(&mensa_parser.get_full_calls()),
+            "full parse was not called right amount: {}",
+            mensa_parser.get_full_calls()
+        );
     }
 
     #[tokio::test]
     #[should_panic = "scheduler should only be started once"]
     async fn test_double_start() {
-        let info = ScheduleInfo {
-            full_parse_schedule: "*/1 * * * * *".into(),
-            update_parse_schedule: "*/2 * * * * *".into(),
-        };
+        let info = schedule_info("*/1 * * * * *", "*/2 * * * * *");
         let mensa_parser = MensaParseMock::default();
 
-        let mut scheduler = Scheduler::new(info, mensa_parser.clone()).await;
+        let mut scheduler = Scheduler::new(
+            info,
+            mensa_parser,
+            MaintenanceMock::default(),
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        )
+        .await;
         scheduler.start().await;
         scheduler.start().await;
         scheduler.shutdown().await;
@@ -204,13 +428,31 @@ mod tests {
     #[tokio::test]
     #[should_panic = "scheduler should be started and not shut down"]
     async fn test_not_running() {
-        let info = ScheduleInfo {
-            full_parse_schedule: "*/1 * * * * *".into(),
-            update_parse_schedule: "*/2 * * * * *".into(),
-        };
+        let info = schedule_info("*/1 * * * * *", "*/2 * * * * *");
         let mensa_parser = MensaParseMock::default();
 
-        let mut scheduler = Scheduler::new(info, mensa_parser.clone()).await;
+        let mut scheduler = Scheduler::new(
+            info,
+            mensa_parser,
+            MaintenanceMock::default(),
+            JobLockMock::default(),
+            JobFailureNotificationMock::default(),
+        )
+        .await;
         scheduler.shutdown().await;
     }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(100),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(30));
+        assert_eq!(policy.backoff(1), Duration::from_mins(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(100));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(100));
+        assert_eq!(RetryPolicy::default().backoff(3), Duration::ZERO);
+    }
 }
@@ -1,6 +1,6 @@
-use mensa_app_backend::startup::server::ServerError;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<(), ServerError> {
+async fn main() -> ExitCode {
     mensa_app_backend::Server::run().await
 }
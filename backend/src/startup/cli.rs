@@ -1,17 +1,46 @@
 //! Module containing code for command line-only actions.
 
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveTime};
+use clap::{Parser, Subcommand};
 use colored::Colorize;
 use futures::StreamExt;
 use hyper::{header::CONTENT_TYPE, Method};
 use thiserror::Error;
 use tracing::{info, warn};
+use uuid::Uuid;
 
 use crate::{
-    interface::image_storage::{self, ImageStorage},
+    interface::{
+        admin_notification::{AdminNotification, MailError},
+        api_command::{Command, CommandError},
+        api_key_management::{ApiKeyError, ApiKeyManagement},
+        image_storage::{self, ImageStorage},
+        mealplan_management::MensaParseScheduling,
+        mensa_parser::{MealplanParser, ParseError},
+        persistent_data::{
+            model::{ApiKey, ApiKeyPermissions, API_KEY_IDENT_LENGTH},
+            CommandDataAccess, DataError,
+        },
+    },
     layer::{
-        data::file_handler::FileHandler,
-        logic::api_command::image_preprocessing::{ImagePreprocessingError, ImagePreprocessor},
+        data::{
+            database::factory::DataAccessFactory, file_handler::FileHandler,
+            image_validation::google_api_handler::GoogleApiHandler, mail::mail_sender::MailSender,
+            mealplan_archive::MealplanArchive, swka_parser::swka_parse_manager::SwKaParseManager,
+        },
+        logic::{
+            api_command::{
+                command_handler::CommandHandler,
+                data_quality::check_data_quality,
+                image_preprocessing::{ImagePreprocessingError, ImagePreprocessor},
+            },
+            api_key_management::api_key_manager::ApiKeyManager,
+            mealplan_management::meal_plan_manager::MealPlanManager,
+        },
     },
+    util::{ApiKeyScope, Date, ErasureRequester},
 };
 
 use super::{config::ConfigReader, server::ServerError};
@@ -34,78 +63,403 @@ pub enum SubcommandError {
     /// Error while storing image locally.
     #[error("could not save image")]
     ImageStorageError(#[from] image_storage::ImageError),
+    /// Error while accessing data through the data access layer.
+    #[error("error while accessing data: {0}")]
+    DataError(#[from] DataError),
+    /// Error while sending an email.
+    #[error("error while sending mail: {0}")]
+    MailError(#[from] MailError),
+    /// Error while serializing output.
+    #[error("error while serializing output: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// Error while parsing, exporting or importing meal plans.
+    #[error("error while processing meal plans: {0}")]
+    ParseError(#[from] ParseError),
+    /// The configuration contains invalid or missing values.
+    #[error("the configuration has {0} problem(s)")]
+    InvalidConfig(usize),
+    /// Error while managing api keys.
+    #[error("error while managing api keys: {0}")]
+    ApiKeyError(#[from] ApiKeyError),
+    /// Error while running a command of the logic layer.
+    #[error("error while running command: {0}")]
+    CommandError(#[from] CommandError),
 }
 
-/// Command arguments to show the help page.
-pub const HELP: &[&str] = &["--help", "-h", "-?"];
-/// Command argument to run database migrations.
-pub const MIGRATE: &str = "--migrate";
+impl SubcommandError {
+    /// Returns the exit code the binary should terminate with because of this error.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::ServerError(error) => error.exit_code(),
+            Self::InvalidConfig(_) => EXIT_CONFIG,
+            Self::DatabaseError(_)
+            | Self::DataError(_)
+            | Self::ApiKeyError(ApiKeyError::DataError(_))
+            | Self::CommandError(CommandError::DataError(_)) => EXIT_DATABASE,
+            Self::ParseError(_) => EXIT_PARSE,
+            Self::ReqwestError(_)
+            | Self::ImagePreprocessError(_)
+            | Self::ImageStorageError(_)
+            | Self::MailError(_)
+            | Self::JsonError(_)
+            | Self::ApiKeyError(_)
+            | Self::CommandError(_) => EXIT_FAILURE,
+        }
+    }
+}
 
-/// Command arguments to igrate images from image hoster (flickr) to local storage.
-pub const MIGRATE_IMAGES: &str = "--migrate-images";
+/// Exit code when a command completed successfully.
+pub const EXIT_SUCCESS: u8 = 0;
+/// Exit code when a command failed for a reason not covered by a more specific exit code.
+pub const EXIT_FAILURE: u8 = 1;
+/// Exit code when the command line arguments are invalid.
+pub const EXIT_USAGE: u8 = 2;
+/// Exit code when the configuration is missing or invalid.
+pub const EXIT_CONFIG: u8 = 3;
+/// Exit code when the database could not be accessed.
+pub const EXIT_DATABASE: u8 = 4;
+/// Exit code when meal plans could not be parsed, exported or imported.
+pub const EXIT_PARSE: u8 = 5;
 
-/// Prints information about the binary and shows available commands.
-pub fn print_help() {
-    const COMMAND_WIDTH: usize = 20;
-    println!(
-        "{}",
-        "==================================================".bright_black()
-    );
-    println!(
-        "{}",
-        format!("   MensaApp Backend v{} 🥘  ", env!("CARGO_PKG_VERSION")).green()
-    );
-    println!(
-        "{}",
-        "==================================================".bright_black()
-    );
-    println!("This binary runs the backend to for the mensa app,");
-    println!("including a graphql server.");
-    println!("For more information, ");
-    println!("see {}", env!("CARGO_PKG_REPOSITORY"));
-    println!();
-    println!(
-        "{}",
-        format!("Licensed under the {} license.", env!("CARGO_PKG_LICENSE")).italic()
-    );
-    println!();
-    println!();
-    println!("{}", "Available commands:".blue());
-    println!(
-        "{:<COMMAND_WIDTH$} {}",
-        "help".bold(),
-        HELP.join(" ").as_str().bright_black()
-    );
-    println!("          shows this page");
-    println!();
-    println!(
-        "{:<COMMAND_WIDTH$} {}",
-        "migrate".bold(),
-        MIGRATE.bright_black()
-    );
-    println!("          runs the database migrations");
-    println!("          before continuing like normal");
-    println!();
-    println!(
-        "{:<COMMAND_WIDTH$} {}",
-        "migrate images".bold(),
-        MIGRATE_IMAGES.bright_black()
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  other error
+  2  invalid command line arguments
+  3  missing or invalid configuration
+  4  database not reachable or database error
+  5  meal plans could not be parsed, exported or imported";
+
+/// This binary runs the backend for the mensa app, including a graphql server.
+///
+/// Configuration is read from environment variables and an optional config file.
+/// For more information, see <https://github.com/kronos-et-al/MensaApp>.
+#[derive(Debug, Parser)]
+#[command(version, after_help = EXIT_CODES_HELP)]
+pub struct Cli {
+    /// Command to run. Without a command, the server is started.
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Commands the binary can run.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum CliCommand {
+    /// Runs the server. This is the default if no command is given.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Serve {
+        /// Runs the database migrations before starting the server.
+        #[arg(long)]
+        migrate: bool,
+    },
+    /// Runs the database migrations and exits.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Migrate,
+    /// Migrates images from the image hoster to local storage.
+    #[command(after_help = EXIT_CODES_HELP)]
+    MigrateImages,
+    /// Parses the meal plans once and stores them in the database.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Parse {
+        /// Parses the meal plans of the upcoming weeks instead of only today's.
+        #[arg(long)]
+        full: bool,
+    },
+    /// Manages the api keys used for authenticating clients.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Apikey {
+        /// Api key operation to run.
+        #[command(subcommand)]
+        command: ApiKeyCommand,
+    },
+    /// Parses the meal plans and writes them to a json archive instead of the database.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Export {
+        /// File to write the archive to.
+        file: PathBuf,
+        /// Only exports the meal plans of this day (`YYYY-MM-DD`) instead of the upcoming weeks.
+        #[arg(long)]
+        date: Option<Date>,
+    },
+    /// Stores the meal plans of a json archive created by `export` in the database.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Import {
+        /// Archive file to read.
+        file: PathBuf,
+    },
+    /// Checks the upcoming meal plans for anomalies and prints the report as json.
+    #[command(after_help = EXIT_CODES_HELP)]
+    DataQualityReport {
+        /// Additionally sends the report to the administrator.
+        #[arg(long)]
+        mail: bool,
+    },
+    /// Deletes all ratings, image votes, image reports and images linked to a client id, e.g. on a request via mail.
+    #[command(after_help = EXIT_CODES_HELP)]
+    DeleteClientData {
+        /// Client id whose data gets deleted.
+        client_id: Uuid,
+    },
+    /// Prints the effective configuration with secrets redacted and lists invalid values.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Config,
+}
+
+impl Default for CliCommand {
+    fn default() -> Self {
+        Self::Serve { migrate: false }
+    }
+}
+
+/// Operations for managing api keys.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum ApiKeyCommand {
+    /// Lists all api keys, including revoked ones. Only the identifiers of the keys are shown.
+    List,
+    /// Generates a new api key and prints it. The key can not be shown again later.
+    Create {
+        /// Description of the key, e.g. which app it belongs to.
+        description: String,
+        /// Mutation the key may perform, can be given multiple times. Without any, all mutations are permitted.
+        #[arg(long = "scope", value_enum, conflicts_with = "read_only")]
+        scopes: Vec<ApiKeyScope>,
+        /// Do not permit any mutations, the key can only be used for reading.
+        #[arg(long)]
+        read_only: bool,
+        /// Date (YYYY-MM-DD) or RFC 3339 time after which the key can no longer be used.
+        #[arg(long, value_parser = parse_expiry)]
+        expires: Option<DateTime<Local>>,
+        /// Maximum number of authenticated requests per minute.
+        #[arg(long)]
+        rate_limit: Option<u32>,
+    },
+    /// Shows details about the api key starting with `ident`.
+    Describe {
+        /// Identifier of the key, the first characters of the key.
+        ident: String,
+    },
+    /// Revokes the api key starting with `ident`, so it can no longer be used.
+    Revoke {
+        /// Identifier of the key, the first characters of the key.
+        ident: String,
+    },
+}
+
+/// Parses a date, meaning the start of that day in local time, or an RFC 3339 time.
+fn parse_expiry(value: &str) -> Result<DateTime<Local>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Local));
+    }
+    value
+        .parse::<Date>()
+        .ok()
+        .and_then(|date| {
+            date.and_time(NaiveTime::MIN)
+                .and_local_timezone(Local)
+                .earliest()
+        })
+        .ok_or_else(|| format!("`{value}` is neither a date (YYYY-MM-DD) nor an RFC 3339 time"))
+}
+
+/// Prints the effective configuration including defaults and where each value comes from. Secrets are redacted.
+/// Afterwards, all invalid or missing values are listed.
+/// # Errors
+/// if the configuration contains invalid or missing values
+pub async fn print_config(config: &ConfigReader) -> Result<(), SubcommandError> {
+    let errors = config.validate().await;
+    let values = config.effective_config();
+
+    let width = values.keys().map(String::len).max().unwrap_or_default();
+    for (var, value) in values {
+        println!(
+            "{var:<width$} = {} {}",
+            value.value.as_deref().unwrap_or("-"),
+            format!("({})", value.source).bright_black()
+        );
+    }
+
+    if !errors.is_empty() {
+        println!();
+        println!("{}", "Problems found:".yellow());
+        for error in &errors {
+            println!("- {}", error.to_string().yellow());
+        }
+        return Err(SubcommandError::InvalidConfig(errors.len()));
+    }
+    Ok(())
+}
+
+/// Runs the database migrations.
+/// # Errors
+/// - invalid database config
+/// - error connecting to the database or running the migrations
+pub async fn migrate(config: &ConfigReader) -> Result<(), SubcommandError> {
+    DataAccessFactory::new(config.read_database_info().map_err(Box::new)?, true).await?;
+    Ok(())
+}
+
+/// Parses the meal plans of today or, if `full` is set, of the upcoming weeks and stores them in the database.
+/// # Errors
+/// - invalid database, mail or parser config
+/// - error while parsing the meal plans
+pub async fn parse(config: &ConfigReader, full: bool) -> Result<(), SubcommandError> {
+    let parser = SwKaParseManager::new(config.read_swka_info().map_err(Box::new)?)?;
+    store_mealplans(config, parser, full).await
+}
+
+/// Parses the meal plans of the given day or, if none is given, of the upcoming weeks and writes them to the archive `file`.
+/// # Errors
+/// - invalid parser config
+/// - error while parsing the meal plans or writing the archive
+pub async fn export(
+    config: &ConfigReader,
+    file: &Path,
+    date: Option<Date>,
+) -> Result<(), SubcommandError> {
+    let parser = SwKaParseManager::new(config.read_swka_info().map_err(Box::new)?)?;
+    let mealplans = match date {
+        Some(date) => vec![(date, parser.parse(date).await?)],
+        None => parser.parse_all().await?,
+    };
+    info!(
+        "Exporting meal plans of {} days to {}",
+        mealplans.len(),
+        file.display()
     );
-    println!("          migrates images from hoster");
-    println!("          to local storage");
-    println!();
+    MealplanArchive::new(file).write(mealplans).await?;
+    Ok(())
+}
+
+/// Stores all meal plans from the archive `file` in the database.
+/// # Errors
+/// - invalid database or mail config
+/// - error reading the archive
+pub async fn import(config: &ConfigReader, file: &Path) -> Result<(), SubcommandError> {
+    info!("Importing meal plans from {}", file.display());
+    store_mealplans(config, MealplanArchive::new(file), true).await
+}
+
+async fn store_mealplans(
+    config: &ConfigReader,
+    parser: impl MealplanParser + 'static,
+    full: bool,
+) -> Result<(), SubcommandError> {
+    let factory =
+        DataAccessFactory::new(config.read_database_info().map_err(Box::new)?, false).await?;
+    let mail = MailSender::new(config.read_mail_info().map_err(Box::new)?)?;
+    let manager = MealPlanManager::new(factory.get_mealplan_management_data_access(), parser, mail);
+
+    if full {
+        manager.start_full_parsing().await?;
+    } else {
+        manager.start_update_parsing().await?;
+    }
+    Ok(())
+}
+
+/// Runs an operation for managing api keys.
+/// Running servers pick up the changes on their next api key refresh.
+/// # Errors
+/// - invalid database config
+/// - error accessing the database
+/// - no single key is identified by the given identifier
+pub async fn api_key(config: &ConfigReader, command: ApiKeyCommand) -> Result<(), SubcommandError> {
+    let factory =
+        DataAccessFactory::new(config.read_database_info().map_err(Box::new)?, false).await?;
+    let manager = ApiKeyManager::new(factory.get_auth_data_access());
+
+    match command {
+        ApiKeyCommand::List => {
+            for key in manager.list_api_keys().await? {
+                println!(
+                    "{:<API_KEY_IDENT_LENGTH$}  {}  {:<7}  {}",
+                    key.ident(),
+                    key.created_at.format("%Y-%m-%d %H:%M"),
+                    api_key_status(&key),
+                    key.description
+                );
+            }
+        }
+        ApiKeyCommand::Create {
+            description,
+            scopes,
+            read_only,
+            expires,
+            rate_limit,
+        } => {
+            let scopes = if read_only || !scopes.is_empty() {
+                scopes
+            } else {
+                ApiKeyScope::ALL.to_vec()
+            };
+            let permissions = ApiKeyPermissions {
+                scopes,
+                expires_at: expires,
+                rate_limit,
+            };
+            let key = manager.create_api_key(description, permissions).await?;
+            println!("{}", key.key);
+            eprintln!(
+                "{}",
+                "Store this key securely, it can not be shown again.".yellow()
+            );
+        }
+        ApiKeyCommand::Describe { ident } => {
+            print_api_key(&manager.describe_api_key(&ident).await?);
+        }
+        ApiKeyCommand::Revoke { ident } => {
+            print_api_key(&manager.revoke_api_key(&ident).await?);
+        }
+    }
+    Ok(())
+}
+
+fn api_key_status(key: &ApiKey) -> &'static str {
+    if key.revoked_at.is_some() {
+        "revoked"
+    } else if key.is_expired_at(Local::now()) {
+        "expired"
+    } else {
+        "active"
+    }
+}
+
+fn print_api_key(key: &ApiKey) {
+    println!("ident:       {}", key.ident());
+    println!("description: {}", key.description);
+    println!("created at:  {}", key.created_at);
+    println!("status:      {}", api_key_status(key));
+    if let Some(revoked_at) = key.revoked_at {
+        println!("revoked at:  {revoked_at}");
+    }
+    let scopes = &key.permissions.scopes;
+    if scopes.is_empty() {
+        println!("scopes:      - (read-only)");
+    } else {
+        let scopes = scopes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        println!("scopes:      {}", scopes.join(", "));
+    }
+    if let Some(expires_at) = key.permissions.expires_at {
+        println!("expires at:  {expires_at}");
+    }
+    if let Some(rate_limit) = key.permissions.rate_limit {
+        println!("rate limit:  {rate_limit} requests per minute");
+    }
 }
 
 /// migrates images from image hoster to local storage.
 /// # Errors
 /// - invalid file config
 /// - invalid database config
+/// - invalid image preprocessing config
 /// # Panics
 /// never
 pub async fn migrate_images(config: &ConfigReader) -> Result<(), SubcommandError> {
     info!("Starting image migration...");
 
-    let image_preprocessing = ImagePreprocessor::new(config.read_image_preprocessing_info());
+    let image_preprocessing =
+        ImagePreprocessor::new(config.read_image_preprocessing_info().map_err(Box::new)?);
     let file_handler = FileHandler::new(config.read_file_handler_info().await.map_err(Box::new)?);
 
     let database_config = config.read_database_info().map_err(Box::new)?;
@@ -165,23 +519,242 @@ pub async fn migrate_images(config: &ConfigReader) -> Result<(), SubcommandError
     Ok(())
 }
 
+/// Creates a data quality report of the upcoming meal plans and prints it as json.
+/// If `send_mail` is set, the report also gets sent to the administrator.
+/// # Errors
+/// - invalid database config
+/// - invalid mail config, if a mail should be sent
+/// - error accessing the database or sending the mail
+pub async fn data_quality_report(
+    config: &ConfigReader,
+    send_mail: bool,
+) -> Result<(), SubcommandError> {
+    let factory =
+        DataAccessFactory::new(config.read_database_info().map_err(Box::new)?, false).await?;
+
+    let today = chrono::Local::now().date_naive();
+    let foods = factory
+        .get_command_data_access()
+        .get_planned_foods(today)
+        .await?;
+    let report = check_data_quality(&foods, today, today);
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    info!(
+        "Found {} issues in {} planned foods.",
+        report.issues.len(),
+        report.checked_food_count
+    );
+
+    if send_mail {
+        let mail = MailSender::new(config.read_mail_info().map_err(Box::new)?)?;
+        mail.notify_admin_data_quality_report(&report).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes all data linked to `client_id` and prints how many entries were deleted.
+/// The erasure gets recorded in the audit trail as requested by an administrator.
+/// # Errors
+/// - invalid database, mail, file or image validation config
+/// - error accessing the database
+pub async fn delete_client_data(
+    config: &ConfigReader,
+    client_id: Uuid,
+) -> Result<(), SubcommandError> {
+    let factory =
+        DataAccessFactory::new(config.read_database_info().map_err(Box::new)?, false).await?;
+    let mail = MailSender::new(config.read_mail_info().map_err(Box::new)?)?;
+    let file_handler = FileHandler::new(config.read_file_handler_info().await.map_err(Box::new)?);
+    let google_vision =
+        GoogleApiHandler::new(config.get_image_validation_info().await.map_err(Box::new)?)
+            .map_err(CommandError::from)?;
+    let command = CommandHandler::new(
+        config.read_image_preprocessing_info().map_err(Box::new)?,
+        factory.get_command_data_access(),
+        mail,
+        file_handler,
+        google_vision,
+    )?;
+
+    let erasure = command
+        .delete_client_data(client_id, ErasureRequester::Admin)
+        .await?;
+    println!(
+        "Deleted all data of client {client_id}: {} meal ratings, {} image votes, {} image reports and {} images",
+        erasure.meal_ratings,
+        erasure.image_votes,
+        erasure.image_reports,
+        erasure.image_ids.len()
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
+
+    use clap::{CommandFactory, Parser};
+    use uuid::Uuid;
 
-    use crate::startup::config::ConfigReader;
+    use crate::{
+        startup::config::ConfigReader,
+        util::{ApiKeyScope, Date},
+    };
 
-    use super::{migrate_images, print_help};
+    use super::{
+        api_key, data_quality_report, migrate_images, print_config, ApiKeyCommand, Cli, CliCommand,
+        SubcommandError, EXIT_CONFIG,
+    };
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
 
     #[test]
-    fn test_print_cli() {
-        print_help();
+    fn test_parse_delete_client_data() {
+        let parse = |args: &[&str]| Cli::try_parse_from(args).map(|cli| cli.command);
+
+        assert_eq!(
+            parse(&[
+                "backend",
+                "delete-client-data",
+                "5b2a7d1e-7c3f-4b8e-9a0d-2f6c1e4b3a90"
+            ])
+            .expect("valid"),
+            Some(CliCommand::DeleteClientData {
+                client_id: Uuid::parse_str("5b2a7d1e-7c3f-4b8e-9a0d-2f6c1e4b3a90")
+                    .expect("valid uuid")
+            })
+        );
+        assert!(parse(&["backend", "delete-client-data", "not-a-uuid"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_cli() {
+        let parse = |args: &[&str]| Cli::try_parse_from(args).map(|cli| cli.command);
+
+        assert_eq!(parse(&["backend"]).expect("valid"), None);
+        assert_eq!(
+            parse(&["backend", "serve", "--migrate"]).expect("valid"),
+            Some(CliCommand::Serve { migrate: true })
+        );
+        assert_eq!(
+            parse(&["backend", "migrate"]).expect("valid"),
+            Some(CliCommand::Migrate)
+        );
+        assert_eq!(
+            parse(&["backend", "parse", "--full"]).expect("valid"),
+            Some(CliCommand::Parse { full: true })
+        );
+        assert_eq!(
+            parse(&["backend", "apikey", "list"]).expect("valid"),
+            Some(CliCommand::Apikey {
+                command: ApiKeyCommand::List
+            })
+        );
+        assert_eq!(
+            parse(&["backend", "export", "plans.json", "--date", "2024-01-02"]).expect("valid"),
+            Some(CliCommand::Export {
+                file: PathBuf::from("plans.json"),
+                date: Date::from_ymd_opt(2024, 1, 2),
+            })
+        );
+        assert_eq!(
+            parse(&["backend", "data-quality-report", "--mail"]).expect("valid"),
+            Some(CliCommand::DataQualityReport { mail: true })
+        );
+
+        // the old flags are no longer accepted
+        assert!(parse(&["backend", "--migrate"]).is_err());
+        assert!(parse(&["backend", "migrate", "--migrate"]).is_err());
+        assert_eq!(
+            parse(&["backend", "apikey", "revoke", "abcdef"]).expect("valid"),
+            Some(CliCommand::Apikey {
+                command: ApiKeyCommand::Revoke {
+                    ident: "abcdef".into()
+                }
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "backend",
+                "apikey",
+                "create",
+                "voting app",
+                "--scope",
+                "vote",
+                "--scope",
+                "rate",
+                "--rate-limit",
+                "60"
+            ])
+            .expect("valid"),
+            Some(CliCommand::Apikey {
+                command: ApiKeyCommand::Create {
+                    description: "voting app".into(),
+                    scopes: vec![ApiKeyScope::Vote, ApiKeyScope::Rate],
+                    read_only: false,
+                    expires: None,
+                    rate_limit: Some(60),
+                }
+            })
+        );
+        assert!(parse(&[
+            "backend",
+            "apikey",
+            "create",
+            "app",
+            "--expires",
+            "2030-01-01"
+        ])
+        .is_ok());
+        assert!(parse(&["backend", "apikey", "create", "app", "--expires", "soon"]).is_err());
+        assert!(parse(&[
+            "backend",
+            "apikey",
+            "create",
+            "app",
+            "--read-only",
+            "--scope",
+            "vote"
+        ])
+        .is_err());
+        assert!(parse(&["backend", "import"]).is_err());
+        assert!(parse(&["backend", "apikey", "create"]).is_err());
+        assert!(parse(&["backend", "export", "plans.json", "--date", "tomorrow"]).is_err());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(SubcommandError::InvalidConfig(2).exit_code(), EXIT_CONFIG);
+    }
+
+    #[tokio::test]
+    async fn test_print_config() {
+        let reader = ConfigReader::new().expect("config readable");
+        let _ = print_config(&reader).await;
     }
 
     #[tokio::test]
     async fn test_migrate_images() {
         let dir = tempfile::tempdir().expect("tempdir available");
         std::env::set_var("IMAGE_DIR", dir.path().as_os_str());
-        let reader = ConfigReader::default();
+        let reader = ConfigReader::new().expect("config readable");
         migrate_images(&reader).await.expect("ok");
     }
+
+    #[tokio::test]
+    async fn test_data_quality_report() {
+        let reader = ConfigReader::new().expect("config readable");
+        data_quality_report(&reader, false).await.expect("ok");
+    }
+
+    #[tokio::test]
+    async fn test_list_api_keys() {
+        let reader = ConfigReader::new().expect("config readable");
+        api_key(&reader, ApiKeyCommand::List).await.expect("ok");
+    }
 }
@@ -1,27 +1,72 @@
 //! Module for setting up the logging framework.
 
-use std::time::Duration;
+use std::{collections::HashMap, str::FromStr, time::Duration};
 
 use chrono::Local;
-use time::{format_description::well_known::Rfc2822, UtcOffset};
-use tracing::info;
+use opentelemetry::{global, trace::TracerProvider};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    trace::{SdkTracerProvider, Tracer},
+    Resource,
+};
+use time::{
+    format_description::well_known::{Rfc2822, Rfc3339},
+    UtcOffset,
+};
+use tracing::{info, warn, Subscriber};
 use tracing_loki::{url::Url, BackgroundTaskController};
+use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{
-    fmt::time::OffsetTime, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
-    Registry,
+    filter::Filtered, fmt::time::OffsetTime, layer::SubscriberExt, registry::LookupSpan,
+    util::SubscriberInitExt, EnvFilter, Layer, Registry,
 };
 
+use super::config::redact_url;
+
+/// Name of this service in external logging and tracing systems.
+const SERVICE_NAME: &str = "mensa-ka";
+
+/// Format of the log output on the console.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable, multi-line output.
+    #[default]
+    Pretty,
+    /// One JSON object per line containing the event fields and the fields of all surrounding spans, for shipping logs to other log stacks.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Struct containing all configurations available for the logging system.
 pub struct LogInfo {
     /// Logging specifier following the schema of <https://docs.rs/tracing-subscriber/latest/tracing_subscriber/filter/struct.EnvFilter.html>
     pub log_config: String,
+    /// Format of the log output on the console.
+    pub log_format: LogFormat,
     /// URL to the Grafana Loki instance to send logs to.
     pub loki_url: Option<String>,
+    /// Base URL of an OpenTelemetry collector to export traces to using OTLP over HTTP, e.g. `http://localhost:4318`.
+    pub otlp_endpoint: Option<String>,
+    /// Additional http headers sent to the OpenTelemetry collector, e.g. for authentication.
+    pub otlp_headers: HashMap<String, String>,
 }
 
 /// Class for initializing the logging.
 pub struct Logger {
     loki_shutdown: Option<BackgroundTaskController>,
+    tracer_provider: Option<SdkTracerProvider>,
 }
 
 impl Logger {
@@ -32,59 +77,96 @@ impl Logger {
     #[allow(clippy::cognitive_complexity)] // somehow this has high cognitive complexity...
     pub fn init(info: LogInfo) -> Self {
         // env logger
-        let env_layer = Self::get_env_fmt_layer(&info.log_config);
+        let env_layer = Self::get_env_fmt_layer(&info.log_config, info.log_format);
         // grafana loki
         let (loki, loki_shutdown) = info
             .loki_url
             .as_ref()
             .map(|url| Self::get_loki_layer(url))
             .unzip();
+        // opentelemetry
+        let (otlp, tracer_provider) = info
+            .otlp_endpoint
+            .as_ref()
+            .map(|endpoint| Self::get_otlp_layer(endpoint, info.otlp_headers, &info.log_config))
+            .unzip();
 
         tracing_subscriber::registry()
             .with(env_layer)
             .with(loki)
+            .with(otlp)
             .init();
 
-        info!("Using local log config `{}`.", info.log_config);
+        info!(
+            "Using local log config `{}` with {:?} format.",
+            info.log_config, info.log_format
+        );
         info!("Using time offset {}.", Local::now().offset().to_string());
 
         if let Some(loki_url) = info.loki_url {
-            info!("Logging to Grafana Loki at `{loki_url}`.",);
+            info!("Logging to Grafana Loki at `{}`.", redact_url(&loki_url));
         } else {
             info!("Logging to Grafana Loki is disabled.");
         }
 
-        Self { loki_shutdown }
+        if let Some(endpoint) = info.otlp_endpoint {
+            info!(
+                "Exporting traces to OpenTelemetry collector at `{}`.",
+                redact_url(&endpoint)
+            );
+        } else {
+            info!("Exporting traces to OpenTelemetry is disabled.");
+        }
+
+        Self {
+            loki_shutdown,
+            tracer_provider,
+        }
     }
 
-    /// Shuts down logger. Required when using external logging to Grafana loki, useless otherwise.
+    /// Shuts down logger. Required when using external logging to Grafana loki or OpenTelemetry, useless otherwise.
     pub async fn shutdown(self) {
         if let Some(s) = self.loki_shutdown {
             tokio::time::sleep(Duration::from_millis(1)).await; // allow for last log messages to be send
             s.shutdown().await;
         }
+        if let Some(provider) = self.tracer_provider {
+            // flushes remaining spans, blocking until done
+            let result = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+            if let Ok(Err(e)) = result {
+                warn!("Could not shut down trace export: {e}");
+            }
+        }
     }
 
-    fn get_env_fmt_layer(log_config: &str) -> impl Layer<Registry> {
+    fn get_env_fmt_layer(log_config: &str, log_format: LogFormat) -> impl Layer<Registry> {
         let env_filter = EnvFilter::builder()
             .parse(log_config)
             .expect("could not parse logging config");
 
         let sec_offset = Local::now().offset().local_minus_utc();
+        let offset = UtcOffset::from_whole_seconds(sec_offset).expect("valid utc offset");
 
-        tracing_subscriber::fmt::layer()
-            .with_timer(OffsetTime::new(
-                UtcOffset::from_whole_seconds(sec_offset).expect("valid utc offset"),
-                Rfc2822,
-            ))
-            .pretty()
-            .with_filter(env_filter)
+        match log_format {
+            LogFormat::Pretty => tracing_subscriber::fmt::layer()
+                .with_timer(OffsetTime::new(offset, Rfc2822))
+                .pretty()
+                .with_filter(env_filter)
+                .boxed(),
+            LogFormat::Json => tracing_subscriber::fmt::layer()
+                .with_timer(OffsetTime::new(offset, Rfc3339))
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_filter(env_filter)
+                .boxed(),
+        }
     }
 
     fn get_loki_layer(loki_url: &str) -> (tracing_loki::Layer, BackgroundTaskController) {
         let loki_url_parsed = Url::parse(loki_url).expect("valid loki url");
         let (loki_layer, controller, task) = tracing_loki::builder()
-            .label("service_name", "mensa-ka")
+            .label("service_name", SERVICE_NAME)
             .expect("label `service_name` not yet set")
             .extra_field("pid", format!("{}", std::process::id()))
             .expect("field `pid` not yet set")
@@ -94,13 +176,49 @@ impl Logger {
         tokio::spawn(task); // todo graceful shutdown
         (loki_layer, controller)
     }
+
+    /// Creates a layer exporting spans filtered by `log_config` to the OpenTelemetry collector at `endpoint`.
+    /// Trace context of incoming requests is read from W3C `traceparent` headers.
+    fn get_otlp_layer<S>(
+        endpoint: &str,
+        headers: HashMap<String, String>,
+        log_config: &str,
+    ) -> (
+        Filtered<OpenTelemetryLayer<S, Tracer>, EnvFilter, S>,
+        SdkTracerProvider,
+    )
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .with_headers(headers)
+            .build()
+            .expect("build otlp span exporter");
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build();
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let env_filter = EnvFilter::builder()
+            .parse(log_config)
+            .expect("could not parse logging config");
+        let layer = tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+            .with_filter(env_filter);
+        (layer, provider)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use rusty_fork::rusty_fork_test;
 
-    use super::{LogInfo, Logger};
+    use super::{LogFormat, LogInfo, Logger};
 
     // put in separate process to allow setting subscriber to avoid conflict with `traced_test`s
     rusty_fork_test! {
@@ -108,10 +226,49 @@ mod tests {
         fn test_logger_init() {
             let info = LogInfo {
                 log_config: "trace".into(),
+                log_format: LogFormat::Pretty,
                 loki_url: None,
+                otlp_endpoint: None,
+                otlp_headers: HashMap::new(),
             };
             let _ = Logger::init(info);
         }
 
+        #[test]
+        fn test_logger_init_json() {
+            let info = LogInfo {
+                log_config: "trace".into(),
+                log_format: LogFormat::Json,
+                loki_url: None,
+                otlp_endpoint: None,
+                otlp_headers: HashMap::new(),
+            };
+            let _ = Logger::init(info);
+            tracing::info_span!("test", canteen.id = 42).in_scope(|| tracing::info!(line.id = 1, "logged"));
+        }
+
+        #[test]
+        fn test_logger_init_otlp() {
+            let runtime = tokio::runtime::Runtime::new().expect("runtime can be created");
+            runtime.block_on(async {
+                let info = LogInfo {
+                    log_config: "trace".into(),
+                    log_format: LogFormat::default(),
+                    loki_url: None,
+                    otlp_endpoint: Some("http://localhost:4318/".into()),
+                    otlp_headers: HashMap::from([("authorization".into(), "Basic abc".into())]),
+                };
+                let logger = Logger::init(info);
+                tracing::info_span!("test").in_scope(|| tracing::info!("exported"));
+                logger.shutdown().await;
+            });
+        }
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!("pretty".parse(), Ok(LogFormat::Pretty));
+        assert_eq!(" JSON".parse(), Ok(LogFormat::Json));
+        assert!("xml".parse::<LogFormat>().is_err());
     }
 }
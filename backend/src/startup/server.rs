@@ -1,5 +1,7 @@
 //! This module contains the server, the heart of the application.
+use clap::Parser;
 use std::fmt::{Debug, Display};
+use std::process::ExitCode;
 use std::{env::VarError, num::ParseIntError};
 use thiserror::Error;
 use tokio::signal::ctrl_c;
@@ -17,14 +19,17 @@ use crate::{
         },
         logic::{
             api_command::command_handler::CommandHandler,
+            api_key_management::api_key_manager::ApiKeyManager,
+            maintenance::maintenance_manager::MaintenanceManager,
             mealplan_management::meal_plan_manager::MealPlanManager,
         },
         trigger::{api::server::ApiServer, scheduling::scheduler::Scheduler},
     },
     startup::{cli, config::ConfigReader, logging::Logger},
+    util,
 };
 
-use super::cli::SubcommandError;
+use super::cli::{Cli, CliCommand, SubcommandError, EXIT_CONFIG, EXIT_DATABASE, EXIT_FAILURE};
 
 /// Result returned from the server, potentially containing a [`ServerError`].
 pub type Result<T> = std::result::Result<T, ServerError>;
@@ -47,6 +52,24 @@ pub enum ServerError {
         /// expected format description
         expected_format: String,
     },
+    /// The config file could not be read or parsed.
+    #[error("could not read config file `{path}`: {reason}")]
+    ConfigFileError {
+        /// path of the config file
+        path: String,
+        /// description of what went wrong
+        reason: String,
+    },
+    /// A file containing the value of a variable could not be read.
+    #[error("could not read file `{path}` given by env var '{var}': {source}")]
+    SecretFileError {
+        /// variable pointing to the file
+        var: String,
+        /// path of the file
+        path: String,
+        /// cause of the error
+        source: std::io::Error,
+    },
     /// Error while creating the mail sender.
     #[error("error while creating mail sender component: {0}")]
     MailError(#[from] MailError),
@@ -76,6 +99,28 @@ pub enum ServerError {
     SubcommandError(#[from] SubcommandError),
 }
 
+impl ServerError {
+    /// Returns the exit code the binary should terminate with because of this error.
+    #[must_use]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::MissingEnvVar(..)
+            | Self::InvalidFormatError { .. }
+            | Self::ConfigFileError { .. }
+            | Self::SecretFileError { .. }
+            | Self::ParseIntError(_)
+            | Self::NonexistingDirectory(_) => EXIT_CONFIG,
+            Self::DataError(_) => EXIT_DATABASE,
+            Self::SubcommandError(error) => error.exit_code(),
+            Self::MailError(_)
+            | Self::CommandError(_)
+            | Self::ParseError(_)
+            | Self::ValidationApiError(_)
+            | Self::IoError(_) => EXIT_FAILURE,
+        }
+    }
+}
+
 impl Debug for ServerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Display::fmt(&self, f)
@@ -86,59 +131,130 @@ impl Debug for ServerError {
 pub struct Server;
 
 impl Server {
-    /// Runs the server and everything that belongs to.
+    /// Parses the command line arguments and runs the requested command, by default the server.
+    /// Invalid arguments terminate the process after printing the usage.
+    /// Returns the exit code the process should terminate with.
+    pub async fn run() -> ExitCode {
+        let cli = Cli::parse();
+        match Self::run_command(cli.command.unwrap_or_default()).await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("Error: {error}");
+                ExitCode::from(error.exit_code())
+            }
+        }
+    }
+
+    /// Runs the given command.
     /// Therefore the configuration is read from environment variables.
-    /// Afterwards, the component structure is created.
     /// # Errors
     /// - when the the config could not read environment variables
-    /// - when crating a component fails
-    #[allow(clippy::cognitive_complexity)]
-    pub async fn run() -> Result<()> {
-        let config = ConfigReader::default();
+    /// - when the command fails
+    pub async fn run_command(command: CliCommand) -> Result<()> {
+        let config = ConfigReader::new()?;
 
         // logging
         let logger = Logger::init(config.read_log_info()?);
 
-        // help text
-        if config.should_print_help() {
-            cli::print_help();
-            return Ok(());
+        // the configuration is printed even if invalid
+        if command != CliCommand::Config {
+            if let Some(base_url) = config.read_base_url()? {
+                util::set_base_url(base_url);
+            }
         }
 
-        if config.should_migrate_images() {
-            cli::migrate_images(&config).await?;
-            return Ok(());
-        }
+        let result = match command {
+            CliCommand::Serve { migrate } => Self::serve(&config, migrate).await,
+            CliCommand::Migrate => cli::migrate(&config).await.map_err(Into::into),
+            CliCommand::MigrateImages => cli::migrate_images(&config).await.map_err(Into::into),
+            CliCommand::Parse { full } => cli::parse(&config, full).await.map_err(Into::into),
+            CliCommand::Apikey { command } => {
+                cli::api_key(&config, command).await.map_err(Into::into)
+            }
+            CliCommand::Export { file, date } => {
+                cli::export(&config, &file, date).await.map_err(Into::into)
+            }
+            CliCommand::Import { file } => cli::import(&config, &file).await.map_err(Into::into),
+            CliCommand::DataQualityReport { mail } => cli::data_quality_report(&config, mail)
+                .await
+                .map_err(Into::into),
+            CliCommand::DeleteClientData { client_id } => {
+                cli::delete_client_data(&config, client_id)
+                    .await
+                    .map_err(Into::into)
+            }
+            CliCommand::Config => cli::print_config(&config).await.map_err(Into::into),
+        };
+
+        logger.shutdown().await;
+        result
+    }
 
+    /// Runs the server and everything that belongs to.
+    /// Therefore, the component structure is created from the configuration.
+    /// If `migrate` is set, the database migrations are run before.
+    /// # Errors
+    /// - when the the config could not read environment variables
+    /// - when crating a component fails
+    #[allow(clippy::cognitive_complexity)]
+    async fn serve(config: &ConfigReader, migrate: bool) -> Result<()> {
         info!("Starting server...");
 
         // data layer
-        let factory =
-            DataAccessFactory::new(config.read_database_info()?, config.should_migrate()).await?;
+        let factory = DataAccessFactory::new(config.read_database_info()?, migrate).await?;
+        factory.register_pool_metrics();
         let command_data = factory.get_command_data_access();
         let mealplan_management_data = factory.get_mealplan_management_data_access();
         let request_data = factory.get_request_data_access();
         let auth_data = factory.get_auth_data_access();
+        let maintenance_data = factory.get_maintenance_data_access();
+        let job_lock_data = factory.get_job_lock_data_access();
+        let health_data = factory.get_health_data_access();
 
         let mail = MailSender::new(config.read_mail_info()?)?;
+        let parse_mail = MailSender::new(config.read_mail_info()?)?;
+        let job_mail = MailSender::new(config.read_mail_info()?)?;
         let parser = SwKaParseManager::new(config.read_swka_info()?)?;
         let file_handler = FileHandler::new(config.read_file_handler_info().await?);
+        let maintenance_file_handler = FileHandler::new(config.read_file_handler_info().await?);
         let google_vision = GoogleApiHandler::new(config.get_image_validation_info().await?)?;
 
         // logic layer
         let command = CommandHandler::new(
-            config.read_image_preprocessing_info(),
+            config.read_image_preprocessing_info()?,
             command_data,
             mail,
             file_handler,
             google_vision,
         )?;
-        let mealplan_management = MealPlanManager::new(mealplan_management_data, parser);
+        let mealplan_management =
+            MealPlanManager::new(mealplan_management_data, parser, parse_mail);
+        let api_key_manager = ApiKeyManager::new(factory.get_auth_data_access());
+        let maintenance = MaintenanceManager::new(
+            config.read_maintenance_info()?,
+            maintenance_data,
+            maintenance_file_handler,
+        );
 
         // trigger layer
-        let mut api_server =
-            ApiServer::new(config.read_api_info()?, request_data, command, auth_data).await;
-        let mut scheduler = Scheduler::new(config.read_schedule_info()?, mealplan_management).await;
+        let mut scheduler = Scheduler::new(
+            config.read_schedule_info()?,
+            mealplan_management,
+            maintenance,
+            job_lock_data,
+            job_mail,
+        )
+        .await;
+        let mut api_server = ApiServer::new(
+            config.read_api_info()?,
+            request_data,
+            command,
+            auth_data,
+            api_key_manager,
+            health_data,
+            scheduler.job_control(),
+        )
+        .await;
 
         // run server
         scheduler.start().await;
@@ -154,7 +270,6 @@ impl Server {
         api_server.shutdown().await;
 
         info!("Server stopped.");
-        logger.shutdown().await;
 
         Ok(())
     }
@@ -1,4 +1,5 @@
 //! This module contains the server, the heart of the application.
+use clap::Parser;
 use tokio::signal::ctrl_c;
 use tracing::info;
 
@@ -15,13 +16,25 @@ use mensa_app_backend::{
                     CommandImageValidationMock,
                 },
             },
+            api_key_management::api_key_manager::ApiKeyManager,
+            maintenance::maintenance_manager::MaintenanceManager,
             mealplan_management::meal_plan_manager::MealPlanManager,
         },
         trigger::{api::server::ApiServer, scheduling::scheduler::Scheduler},
     },
-    startup::{cli, config::ConfigReader, logging::Logger, server::ServerError},
+    startup::{config::ConfigReader, logging::Logger, server::ServerError},
 };
 
+/// Runs the backend with mocked image storage, image validation and mail notifications.
+/// Other commands like migrating images are available through the main binary.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Runs the database migrations before starting the server.
+    #[arg(long)]
+    migrate: bool,
+}
+
 /// Result returned from the server, potentially containing a [`ServerError`].
 pub type Result<T> = std::result::Result<T, ServerError>;
 /// Runs the server and everything that belongs to.
@@ -36,31 +49,24 @@ async fn main() {
 }
 
 async fn run() -> Result<()> {
-    let config = ConfigReader::default();
+    let args = Args::parse();
+    let config = ConfigReader::new()?;
 
     // logging
     Logger::init(config.read_log_info()?);
 
     info!("Starting server...");
 
-    // help text
-    if config.should_print_help() {
-        cli::print_help();
-        return Ok(());
-    }
-
-    if config.should_migrate_images() {
-        cli::migrate_images(&config).await?;
-        return Ok(());
-    }
-
     // data layer
-    let factory =
-        DataAccessFactory::new(config.read_database_info()?, config.should_migrate()).await?;
+    let factory = DataAccessFactory::new(config.read_database_info()?, args.migrate).await?;
+    factory.register_pool_metrics();
     let command_data = factory.get_command_data_access();
     let mealplan_management_data = factory.get_mealplan_management_data_access();
     let request_data = factory.get_request_data_access();
     let auth_data = factory.get_auth_data_access();
+    let maintenance_data = factory.get_maintenance_data_access();
+    let job_lock_data = factory.get_job_lock_data_access();
+    let health_data = factory.get_health_data_access();
 
     let mail = CommandAdminNotificationMock;
     let parser = SwKaParseManager::new(config.read_swka_info()?)?;
@@ -69,18 +75,43 @@ async fn run() -> Result<()> {
 
     // logic layer
     let command = CommandHandler::new(
-        config.read_image_preprocessing_info(),
+        config.read_image_preprocessing_info()?,
         command_data,
         mail,
         file_handler,
         google_vision,
     )?;
-    let mealplan_management = MealPlanManager::new(mealplan_management_data, parser);
+    let mealplan_management = MealPlanManager::new(
+        mealplan_management_data,
+        parser,
+        CommandAdminNotificationMock,
+    );
+    let api_key_manager = ApiKeyManager::new(factory.get_auth_data_access());
+    let maintenance = MaintenanceManager::new(
+        config.read_maintenance_info()?,
+        maintenance_data,
+        CommandImageStorageMock,
+    );
 
     // trigger layer
-    let mut api_server =
-        ApiServer::new(config.read_api_info()?, request_data, command, auth_data).await;
-    let mut scheduler = Scheduler::new(config.read_schedule_info()?, mealplan_management).await;
+    let mut scheduler = Scheduler::new(
+        config.read_schedule_info()?,
+        mealplan_management,
+        maintenance,
+        job_lock_data,
+        CommandAdminNotificationMock,
+    )
+    .await;
+    let mut api_server = ApiServer::new(
+        config.read_api_info()?,
+        request_data,
+        command,
+        auth_data,
+        api_key_manager,
+        health_data,
+        scheduler.job_control(),
+    )
+    .await;
 
     // run server
     scheduler.start().await;
@@ -2,11 +2,13 @@
 //!
 //! For a complete list and explanations you can see [here](https://www.sw-ka.de/media/?file=4458listeallergesetzlichausweisungspflichtigenzusatzstoffeundallergenefuerwebsite160218.pdf&download).
 
-use std::{fmt::Display, sync::LazyLock};
+use std::{fmt::Display, sync::OnceLock};
 
 use async_graphql::Enum;
 use image::DynamicImage;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+pub mod metrics;
 
 /// Date type used in multiple places.
 pub type Date = chrono::NaiveDate;
@@ -24,8 +26,9 @@ pub const IMAGE_EXTENSION: &str = "jpg";
 pub const IMAGE_BASE_PATH: &str = "/image";
 
 /// This enum lists every possible allergen a meal can have.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "allergen", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Allergen {
     /// This meal contains cashews.
     Ca,
@@ -86,8 +89,9 @@ pub enum Allergen {
 }
 
 /// This enum lists every possible additive a meal can have.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "additive", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Additive {
     /// This meal contains colorants.
     Colorant,
@@ -122,8 +126,9 @@ pub enum Additive {
 }
 
 /// This enum lists all the types a meal can be of.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type, Serialize, Deserialize)]
 #[sqlx(type_name = "meal_type", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FoodType {
     /// This meal is vegan.
     Vegan,
@@ -169,8 +174,65 @@ impl Display for ReportReason {
     }
 }
 
+/// This enum lists the reasons a canteen can be closed for on a day.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "closure_reason", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClosureReason {
+    /// The canteen is closed because of a public holiday.
+    Holiday,
+    /// The canteen is closed during the semester break.
+    SemesterBreak,
+    /// The canteen is closed because of a special event.
+    Event,
+    /// The canteen is closed for some other reason.
+    Other,
+    /// The canteen is closed for an unknown reason, e.g. because no dishes were listed on the meal plan.
+    Unspecified,
+}
+
+/// This enum lists who can request the erasure of all data linked to a client id.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "erasure_requester", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErasureRequester {
+    /// The client itself requested the erasure of its data.
+    Client,
+    /// An administrator erased the data, e.g. on request via mail.
+    Admin,
+}
+
+/// This enum lists the mutations an api key can be permitted to perform.
+/// Keys without any scope can only be used for reading.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Enum, sqlx::Type, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[sqlx(type_name = "api_key_scope", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ApiKeyScope {
+    /// Rating meals.
+    Rate,
+    /// Up- and downvoting images.
+    Vote,
+    /// Uploading images.
+    UploadImage,
+    /// Reporting images.
+    Report,
+}
+
+impl ApiKeyScope {
+    /// All scopes, as granted to api keys by default.
+    pub const ALL: [Self; 4] = [Self::Rate, Self::Vote, Self::UploadImage, Self::Report];
+}
+
+impl Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 /// This struct contains all price classes. All prices are listed in euro.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Price {
     /// Price of the dish for students.
     pub price_student: u32,
@@ -183,7 +245,7 @@ pub struct Price {
 }
 
 /// The nutrients of a dish
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NutritionData {
     /// Energy in Kcal
     pub energy: u32,
@@ -201,13 +263,22 @@ pub struct NutritionData {
     pub salt: u32,
 }
 
-static BASE_URL: LazyLock<String> =
-    LazyLock::new(|| std::env::var("BASE_URL").unwrap_or_else(|_| "localhost".into()));
+static BASE_URL: OnceLock<String> = OnceLock::new();
+
+/// Sets the url the server is publicly accessible under. Has no effect if it was already set or used.
+/// If not set, the `BASE_URL` environment variable is used.
+pub fn set_base_url(url: String) {
+    let _ = BASE_URL.set(url);
+}
+
+fn base_url() -> &'static str {
+    BASE_URL.get_or_init(|| std::env::var("BASE_URL").unwrap_or_else(|_| "localhost".into()))
+}
 
 /// Prepends the servers domain to get a global url. Slug should start with a `/`.
 #[must_use]
 pub fn local_to_global_url(slug: &str) -> String {
-    format!("{}{slug}", BASE_URL.as_str())
+    format!("{}{slug}", base_url())
 }
 
 /// Returns the url an image will be accessible given its id.
@@ -225,7 +296,7 @@ mod tests {
     #[test]
     fn test_global_url() {
         assert_eq!(
-            format!("{}/myslug", BASE_URL.as_str()),
+            format!("{}/myslug", base_url()),
             local_to_global_url("/myslug")
         );
     }
@@ -234,10 +305,7 @@ mod tests {
     fn test_image_to_url() {
         let uuid = Uuid::from_str("3945b556-2f02-427e-83be-46c0aa1a5cc9").expect("valid uuid");
         assert_eq!(
-            format!(
-                "{}{IMAGE_BASE_PATH}/{uuid}.{IMAGE_EXTENSION}",
-                BASE_URL.as_str()
-            ),
+            format!("{}{IMAGE_BASE_PATH}/{uuid}.{IMAGE_EXTENSION}", base_url()),
             image_id_to_url(uuid)
         );
     }
@@ -0,0 +1,179 @@
+//! Prometheus metrics collected across all layers and exported at `/metrics`.
+//!
+//! Metrics are registered in the default [`prometheus`] registry when first used.
+use std::{sync::LazyLock, time::Duration};
+
+use prometheus::{
+    core::Collector, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge_vec, Encoder, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec,
+    TextEncoder,
+};
+use tracing::warn;
+
+/// Label for parsing only the current day's meal plan.
+pub const UPDATE_PARSE: &str = "update";
+/// Label for parsing all available meal plans.
+pub const FULL_PARSE: &str = "full";
+
+/// Buckets in seconds for the duration of graphql requests.
+const REQUEST_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+/// Buckets in seconds for the duration of parse runs.
+const PARSE_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0];
+
+static GRAPHQL_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "mensa_graphql_requests_total",
+        "Number of handled graphql requests by operation and result.",
+        &["operation", "result"]
+    )
+    .expect("metric should only be registered once")
+});
+
+static GRAPHQL_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "mensa_graphql_request_duration_seconds",
+        "Time taken to handle graphql requests by operation.",
+        &["operation"],
+        REQUEST_BUCKETS.to_vec()
+    )
+    .expect("metric should only be registered once")
+});
+
+static PARSE_RUNS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "mensa_parse_runs_total",
+        "Number of meal plan parse runs by kind and result.",
+        &["kind", "result"]
+    )
+    .expect("metric should only be registered once")
+});
+
+static PARSE_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "mensa_parse_duration_seconds",
+        "Time taken by successful meal plan parse runs, including storing the results.",
+        &["kind"],
+        PARSE_BUCKETS.to_vec()
+    )
+    .expect("metric should only be registered once")
+});
+
+static PARSED_DISHES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "mensa_parse_dishes",
+        "Number of dishes found by the last successful parse run by kind.",
+        &["kind"]
+    )
+    .expect("metric should only be registered once")
+});
+
+static IMAGE_UPLOADS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "mensa_image_uploads_total",
+        "Number of validated image uploads by result and reason of rejection.",
+        &["result", "reason"]
+    )
+    .expect("metric should only be registered once")
+});
+
+static IMAGE_REPORTS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("mensa_image_reports_total", "Number of image reports.")
+        .expect("metric should only be registered once")
+});
+
+static IMAGE_AUTO_HIDES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "mensa_image_auto_hides_total",
+        "Number of images hidden automatically because of reports."
+    )
+    .expect("metric should only be registered once")
+});
+
+/// Records a handled graphql request of the given operation.
+/// As the operation is used as label, it must be one of a bounded set of values and not chosen by callers.
+pub fn record_graphql_request(operation: &str, success: bool, duration: Duration) {
+    let result = if success { "success" } else { "error" };
+    GRAPHQL_REQUESTS
+        .with_label_values(&[operation, result])
+        .inc();
+    GRAPHQL_DURATION
+        .with_label_values(&[operation])
+        .observe(duration.as_secs_f64());
+}
+
+/// Records a successful parse run of the given kind, see [`UPDATE_PARSE`] and [`FULL_PARSE`].
+pub fn record_parse_success(kind: &str, duration: Duration, dishes: usize) {
+    PARSE_RUNS.with_label_values(&[kind, "success"]).inc();
+    PARSE_DURATION
+        .with_label_values(&[kind])
+        .observe(duration.as_secs_f64());
+    PARSED_DISHES
+        .with_label_values(&[kind])
+        .set(dishes.try_into().unwrap_or(i64::MAX));
+}
+
+/// Records a failed parse run of the given kind, see [`UPDATE_PARSE`] and [`FULL_PARSE`].
+pub fn record_parse_failure(kind: &str) {
+    PARSE_RUNS.with_label_values(&[kind, "failure"]).inc();
+}
+
+/// Records an image upload which passed validation.
+pub fn record_image_accepted() {
+    IMAGE_UPLOADS.with_label_values(&["accepted", ""]).inc();
+}
+
+/// Records an image upload which got rejected by validation for the given `reason`.
+pub fn record_image_rejected(reason: &str) {
+    IMAGE_UPLOADS.with_label_values(&["rejected", reason]).inc();
+}
+
+/// Records an image report and whether the image got hidden automatically because of it.
+pub fn record_image_report(hidden: bool) {
+    IMAGE_REPORTS.inc();
+    if hidden {
+        IMAGE_AUTO_HIDES.inc();
+    }
+}
+
+/// Registers an additional collector, e.g. for metrics determined when scraped.
+pub fn register_collector(collector: impl Collector + 'static) {
+    if let Err(e) = prometheus::register(Box::new(collector)) {
+        warn!("Could not register metrics collector: {e}");
+    }
+}
+
+/// Encodes all registered metrics in the prometheus text format.
+#[must_use]
+pub fn encode() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        warn!("Could not encode metrics: {e}");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        record_graphql_request("getCanteens", true, Duration::from_millis(20));
+        record_parse_success(FULL_PARSE, Duration::from_secs(30), 42);
+        record_image_rejected("SafeSearchRejectionError");
+        record_image_report(true);
+
+        let metrics = encode();
+        assert!(metrics
+            .contains(r#"mensa_graphql_requests_total{operation="getCanteens",result="success"}"#));
+        assert!(metrics.contains(r#"mensa_parse_dishes{kind="full"} 42"#));
+        assert!(metrics.contains(
+            r#"mensa_image_uploads_total{reason="SafeSearchRejectionError",result="rejected"}"#
+        ));
+        assert!(metrics.contains("mensa_image_auto_hides_total"));
+    }
+}
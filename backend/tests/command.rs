@@ -93,7 +93,7 @@ async fn test_remove_image_downvote() {
 
 // todo redo / remove test?
 async fn setup_cmd() -> impl Command {
-    let reader = ConfigReader::default();
+    let reader = ConfigReader::new().expect("config readable");
 
     let mail = MailSender::new(reader.read_mail_info().unwrap()).unwrap();
     let file_handler = FileHandler::new(reader.read_file_handler_info().await.unwrap());
@@ -104,7 +104,7 @@ async fn setup_cmd() -> impl Command {
         .unwrap();
     let data = factory.get_command_data_access();
     CommandHandler::new(
-        reader.read_image_preprocessing_info(),
+        reader.read_image_preprocessing_info().unwrap(),
         data,
         mail,
         file_handler,
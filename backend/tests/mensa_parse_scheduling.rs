@@ -1,9 +1,15 @@
+use std::collections::HashMap;
+
 use mensa_app_backend::{
     layer::{
         data::{
             database::factory::DataAccessFactory, swka_parser::swka_parse_manager::SwKaParseManager,
         },
-        logic::mealplan_management::meal_plan_manager::MealPlanManager,
+        logic::{
+            api_command::mocks::{CommandAdminNotificationMock, CommandImageStorageMock},
+            maintenance::maintenance_manager::MaintenanceManager,
+            mealplan_management::meal_plan_manager::MealPlanManager,
+        },
         trigger::scheduling::scheduler::{ScheduleInfo, Scheduler},
     },
     startup::config::ConfigReader,
@@ -18,6 +24,7 @@ async fn test_full_mensa_parse_scheduling() {
     let info = ScheduleInfo {
         full_parse_schedule: "0 */5 * * * *".to_string(),
         update_parse_schedule: NEVER.to_string(),
+        ..never_maintenance()
     };
     let mut scheduler = setup(info).await;
     scheduler.start().await;
@@ -31,6 +38,7 @@ async fn test_update_mensa_parse_scheduling() {
     let info = ScheduleInfo {
         full_parse_schedule: NEVER.to_string(),
         update_parse_schedule: "0 */5 * * * *".to_string(),
+        ..never_maintenance()
     };
     let mut scheduler = setup(info).await;
     scheduler.start().await;
@@ -38,14 +46,43 @@ async fn test_update_mensa_parse_scheduling() {
     scheduler.shutdown().await;
 }
 
+fn never_maintenance() -> ScheduleInfo {
+    ScheduleInfo {
+        full_parse_schedule: NEVER.to_string(),
+        update_parse_schedule: NEVER.to_string(),
+        expire_reports_schedule: NEVER.to_string(),
+        orphaned_images_schedule: NEVER.to_string(),
+        refresh_statistics_schedule: NEVER.to_string(),
+        purge_data_schedule: NEVER.to_string(),
+        retry_policies: HashMap::new(),
+        failure_notification_threshold: 0,
+    }
+}
+
 async fn setup(info: ScheduleInfo) -> Scheduler {
-    let reader = ConfigReader::default();
+    let reader = ConfigReader::new().expect("config readable");
     let database_factory = DataAccessFactory::new(reader.read_database_info().unwrap(), false)
         .await
         .unwrap();
     let mealplan_management_data = database_factory.get_mealplan_management_data_access();
     let parser = SwKaParseManager::new(reader.read_swka_info().unwrap()).unwrap();
 
-    let mealplan_management = MealPlanManager::new(mealplan_management_data, parser);
-    Scheduler::new(info, mealplan_management).await
+    let mealplan_management = MealPlanManager::new(
+        mealplan_management_data,
+        parser,
+        CommandAdminNotificationMock,
+    );
+    let maintenance = MaintenanceManager::new(
+        reader.read_maintenance_info().unwrap(),
+        database_factory.get_maintenance_data_access(),
+        CommandImageStorageMock,
+    );
+    Scheduler::new(
+        info,
+        mealplan_management,
+        maintenance,
+        database_factory.get_job_lock_data_access(),
+        CommandAdminNotificationMock,
+    )
+    .await
 }